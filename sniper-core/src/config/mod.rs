@@ -31,7 +31,9 @@ pub struct SolanaConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradingConfig {
     pub mode: String, // "paper" or "live"
+    /// SOL
     pub max_position_size: f64,
+    /// SOL
     pub max_daily_loss: f64,
     pub min_confidence: f64,
 }
@@ -75,13 +77,13 @@ impl Config {
             trading: TradingConfig {
                 mode: env::var("TRADING_MODE").unwrap_or_else(|_| "paper".to_string()),
                 max_position_size: env::var("MAX_POSITION_SIZE")
-                    .unwrap_or_else(|_| "1000.0".to_string())
+                    .unwrap_or_else(|_| "1.0".to_string())
                     .parse()
-                    .unwrap_or(1000.0),
+                    .unwrap_or(1.0),
                 max_daily_loss: env::var("MAX_DAILY_LOSS")
-                    .unwrap_or_else(|_| "500.0".to_string())
+                    .unwrap_or_else(|_| "5.0".to_string())
                     .parse()
-                    .unwrap_or(500.0),
+                    .unwrap_or(5.0),
                 min_confidence: env::var("MIN_CONFIDENCE")
                     .unwrap_or_else(|_| "0.6".to_string())
                     .parse()
@@ -1,5 +1,5 @@
 use axum::{
-    extract::Json,
+    extract::{Json, State},
     http::StatusCode,
     response::Json as ResponseJson,
     routing::{get, post},
@@ -15,7 +15,7 @@ use solana_sdk::{
     transaction::Transaction,
 };
 use spl_token::instruction as spl_instruction;
-use spl_associated_token_account;
+use std::collections::HashMap;
 use std::str::FromStr;
 use tokio::net::TcpListener;
 use tower_http::trace::TraceLayer;
@@ -45,6 +45,21 @@ struct TransactionResponse {
 // Konfiguracja Tokio dla Contabo VDS (5 z 6 rdzeni - Pure Rust Architecture)
 #[tokio::main(worker_threads = 5)]
 async fn main() -> anyhow::Result<()> {
+    // Offline backtests need neither the wallet key nor the HTTP server
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("backtest") => {
+            init_logging()?;
+            return modules::backtest::run_cli(&args[1..]).await;
+        }
+        Some("optimize") => {
+            init_logging()?;
+            // Workers run their own runtimes, which cannot start inside this one's worker threads
+            return tokio::task::block_in_place(|| modules::backtest::optimize::run_cli(&args[1..]));
+        }
+        _ => {}
+    }
+
     // Load configuration
     let config = config::Config::from_env()?;
 
@@ -60,13 +75,36 @@ async fn main() -> anyhow::Result<()> {
     info!("🔧 A2A protocol endpoints ready");
 
     // Konfiguracja serwera HTTP (Axum) z A2A endpoints
-    let app = Router::new()
+    let mut app = Router::new()
         .route("/health", get(health_check))
         .route("/api/v1/transaction/execute", post(execute_transaction))
         .route("/metrics", get(get_metrics))
         .route("/status", get(get_system_status))
-        .nest("/api/v1/a2a", modules::a2a_server::A2AServer::get_routes())
-        .layer(TraceLayer::new_for_http());
+        .nest("/api/v1/a2a", modules::a2a_server::A2AServer::get_routes());
+
+    // Live indicators, fed by the ingestor's candle builder and served with the candles
+    let indicators = modules::strategy_engine::IndicatorEngine::shared(Default::default());
+
+    // The trading pipeline needs DragonflyDB for persisted state
+    let mut candle_sink = None;
+    match init_trading(&config, indicators.clone()).await {
+        Ok((routes, candles)) => {
+            app = app.merge(routes);
+            candle_sink = Some(candles);
+        }
+        Err(e) => error!("❌ Trading and risk endpoints disabled: {}", e),
+    }
+
+    // Candle history is read back from DragonflyDB / PostgreSQL
+    match modules::cache_manager::CacheManager::new().await {
+        Ok(cache) => {
+            let store = modules::data_ingestor::CandleStore::connect(cache).await;
+            app = app.nest("/api/v1/candles", modules::data_ingestor::candle_api::routes(store, indicators));
+        }
+        Err(e) => error!("❌ Candle endpoints disabled: {}", e),
+    }
+
+    let app = app.layer(TraceLayer::new_for_http());
 
     let bind_addr = format!("{}:{}", config.server.host, config.server.port);
     let listener = TcpListener::bind(&bind_addr).await?;
@@ -78,6 +116,13 @@ async fn main() -> anyhow::Result<()> {
     info!("   GET  /metrics - System metrics");
     info!("   GET  /status - System status");
     info!("   *    /api/v1/a2a/* - A2A protocol endpoints");
+    info!("   GET  /api/v1/sources - Per-source lead/lag statistics");
+    info!("   GET  /api/v1/risk/halt - Circuit breaker state");
+    info!("   POST /api/v1/risk/halt - Halt or resume trading (admin)");
+    info!("   POST /api/v1/risk/emergency-stop - Liquidate all positions (admin)");
+    info!("   GET  /api/v1/risk/portfolio - Exposure, VaR and correlation (admin)");
+    info!("   GET  /api/v1/candles/:mint?interval= - OHLCV candles (1s/15s/1m/5m/1h)");
+    info!("   GET  /api/v1/candles/:mint/indicators?interval= - Live indicator values");
 
     // Graceful shutdown handling
     let server_handle = tokio::spawn(async move {
@@ -92,10 +137,80 @@ async fn main() -> anyhow::Result<()> {
         }
     }
 
+    // Persist the candles still open so the last bar of every mint is kept
+    if let Some(candles) = candle_sink {
+        candles.flush().await;
+    }
+
     info!("✅ SniperCore shutdown complete");
     Ok(())
 }
 
+// Start the trading pipeline (ingestor -> strategies -> risk manager -> executor);
+// returns the risk control and data source endpoints, and the ingestor's open candles
+async fn init_trading(
+    config: &config::Config,
+    indicators: modules::strategy_engine::SharedIndicators,
+) -> anyhow::Result<(Router, modules::data_ingestor::CandleSink)> {
+    use modules::data_ingestor::DataIngestor;
+    use modules::executor::Executor;
+    use modules::risk_manager::{CircuitBreaker, RiskApi, RiskManager};
+    use modules::strategy_engine::StrategyEngine;
+
+    let cache_manager = modules::cache_manager::CacheManager::new().await?;
+    let circuit_breaker = CircuitBreaker::load(cache_manager.clone(), config.trading.max_daily_loss).await?;
+
+    let (market_data_tx, market_data_rx) = tokio::sync::mpsc::channel(8192);
+    let (signal_tx, signal_rx) = tokio::sync::mpsc::channel(1024);
+    let (execution_tx, execution_rx) = tokio::sync::mpsc::channel(1024);
+    let (command_tx, command_rx) = tokio::sync::mpsc::channel(16);
+
+    let mut data_ingestor = DataIngestor::new(market_data_tx, cache_manager.clone())
+        .await?
+        .with_indicators(indicators);
+    let source_stats = data_ingestor.source_stats();
+    let candle_sink = data_ingestor.candle_sink();
+    let mut strategy_engine = StrategyEngine::new(market_data_rx, signal_tx).await?;
+    let mut executor = Executor::new(execution_rx).await?;
+    let mut risk_manager =
+        RiskManager::new(cache_manager, circuit_breaker.clone(), signal_rx, execution_tx, command_rx)
+            .await?
+            .with_strategy_fills(strategy_engine.fill_sender());
+
+    tokio::spawn(async move {
+        if let Err(e) = executor.run().await {
+            error!("❌ Executor stopped: {}", e);
+        }
+    });
+    tokio::spawn(async move {
+        if let Err(e) = risk_manager.run().await {
+            error!("❌ RiskManager stopped: {}", e);
+        }
+    });
+    tokio::spawn(async move {
+        if let Err(e) = strategy_engine.run().await {
+            error!("❌ StrategyEngine stopped: {}", e);
+        }
+    });
+    tokio::spawn(async move {
+        if let Err(e) = data_ingestor.run().await {
+            error!("❌ DataIngestor stopped: {}", e);
+        }
+    });
+
+    let routes = Router::new()
+        .nest("/api/v1/risk", RiskApi::new(circuit_breaker, command_tx).routes())
+        .route("/api/v1/sources", get(get_source_stats).with_state(source_stats));
+    Ok((routes, candle_sink))
+}
+
+// Per-source lead/lag statistics from the event merger
+async fn get_source_stats(
+    State(stats): State<modules::data_ingestor::SharedSourceStats>,
+) -> ResponseJson<HashMap<String, modules::data_ingestor::SourceStats>> {
+    ResponseJson(stats.read().clone())
+}
+
 // Health check endpoint
 async fn health_check() -> ResponseJson<serde_json::Value> {
     ResponseJson(serde_json::json!({
@@ -144,7 +259,7 @@ async fn execute_spl_transfer(request: TransactionRequest) -> anyhow::Result<Str
         .into_vec()
         .map_err(|e| anyhow::anyhow!("Invalid private key format: {}", e))?;
 
-    let keypair = Keypair::from_bytes(&private_key_bytes)
+    let keypair = Keypair::try_from(private_key_bytes.as_slice())
         .map_err(|e| anyhow::anyhow!("Failed to create keypair: {}", e))?;
 
     info!("🔑 Keypair created from private key");
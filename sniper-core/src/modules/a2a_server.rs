@@ -9,16 +9,18 @@ use axum::{
     Router,
 };
 use serde::{Deserialize, Serialize};
-use tracing::{info, error, debug};
+use tracing::{info, debug};
 use std::collections::HashMap;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
+#[allow(dead_code)]
 pub struct A2AServer {
     agent_registry: AgentRegistry,
     message_queue: MessageQueue,
 }
 
+#[allow(dead_code)]
 impl A2AServer {
     pub async fn new() -> anyhow::Result<Self> {
         info!("🔗 A2AServer initializing...");
@@ -114,10 +116,12 @@ pub enum MessagePriority {
 }
 
 /// Agent registry for service discovery
+#[allow(dead_code)]
 pub struct AgentRegistry {
     agents: HashMap<Uuid, AgentInfo>,
 }
 
+#[allow(dead_code)]
 impl AgentRegistry {
     pub fn new() -> Self {
         Self {
@@ -158,10 +162,12 @@ impl AgentRegistry {
 }
 
 /// Message queue for async communication
+#[allow(dead_code)]
 pub struct MessageQueue {
     messages: HashMap<Uuid, Vec<A2AMessage>>, // agent_id -> messages
 }
 
+#[allow(dead_code)]
 impl MessageQueue {
     pub fn new() -> Self {
         Self {
@@ -171,7 +177,7 @@ impl MessageQueue {
 
     pub fn send_message(&mut self, message: A2AMessage) {
         let to_agent = message.to_agent;
-        self.messages.entry(to_agent).or_insert_with(Vec::new).push(message);
+        self.messages.entry(to_agent).or_default().push(message);
         debug!("📨 Message queued for agent: {}", to_agent);
     }
 
@@ -218,7 +224,7 @@ async fn register_agent(
 }
 
 /// Get agent information
-async fn get_agent(Path(agent_id): Path<Uuid>) -> Result<ResponseJson<AgentInfo>, StatusCode> {
+async fn get_agent(Path(_agent_id): Path<Uuid>) -> Result<ResponseJson<AgentInfo>, StatusCode> {
     // TODO: Get from global registry
     Err(StatusCode::NOT_FOUND)
 }
@@ -245,6 +251,7 @@ async fn send_message(
 }
 
 /// Get messages for an agent
+#[allow(dead_code)]
 #[derive(Deserialize)]
 struct GetMessagesQuery {
     limit: Option<usize>,
@@ -253,7 +260,7 @@ struct GetMessagesQuery {
 
 async fn get_messages(
     Path(agent_id): Path<Uuid>,
-    Query(query): Query<GetMessagesQuery>,
+    Query(_query): Query<GetMessagesQuery>,
 ) -> ResponseJson<Vec<A2AMessage>> {
     debug!("📬 Getting messages for agent: {}", agent_id);
     
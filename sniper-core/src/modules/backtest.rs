@@ -0,0 +1,868 @@
+// 🧪 Backtest Module
+// Replay recorded market events through the strategies and risk rules offline
+
+pub mod fills;
+pub mod optimize;
+pub mod report;
+
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+
+use super::data_ingestor::{
+    self, MarketDataMessage, MarketEvent, RecordedEvent, TradeSide, WalletTracker, WalletTrackerConfig,
+};
+use super::executor::ExecutionReport;
+use super::risk_manager::{
+    decision, exits, sizing, var, BookState, DecisionRules, ExitReason, ExitRules, Position, PositionLimits,
+    RiskDecision, SizingConfig, SizingModel,
+};
+use super::strategy_engine::{self, SignalMessage, Strategy, StrategyConfig, StrategySignal};
+
+pub use fills::FillModel;
+pub use report::{BacktestMetrics, BacktestReport, EquityPoint, Trade};
+
+/// Positions smaller than this many tokens are treated as closed
+const DUST_TOKENS: f64 = 1e-9;
+
+const DAY_MS: i64 = 86_400_000;
+
+#[derive(Debug, Clone)]
+pub struct BacktestConfig {
+    /// NDJSON recording written by the ingestor's recorder
+    pub events_path: PathBuf,
+    pub output_dir: PathBuf,
+    pub initial_capital_sol: f64,
+    /// Spacing of equity curve samples, and so of the returns behind Sharpe and Sortino
+    pub equity_interval_ms: i64,
+    /// Assumed for mints whose decimals never appear in the recording
+    pub default_decimals: u8,
+    pub fills: FillModel,
+}
+
+impl BacktestConfig {
+    pub fn from_env() -> Self {
+        let events_path = std::env::var("BACKTEST_EVENTS")
+            .or_else(|_| std::env::var("REPLAY_FILE"))
+            .unwrap_or_else(|_| "recordings/market_data.ndjson".to_string());
+
+        Self {
+            events_path: events_path.into(),
+            output_dir: std::env::var("BACKTEST_OUTPUT_DIR")
+                .unwrap_or_else(|_| "backtest".to_string())
+                .into(),
+            initial_capital_sol: std::env::var("BACKTEST_CAPITAL_SOL")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(10.0),
+            equity_interval_ms: std::env::var("BACKTEST_EQUITY_INTERVAL_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(60_000),
+            default_decimals: std::env::var("BACKTEST_DEFAULT_DECIMALS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(6),
+            fills: FillModel::from_env(),
+        }
+    }
+}
+
+/// Recorded events in time order, each transaction preceded by the events the ingestor derives from it
+pub fn load_events(path: &Path) -> anyhow::Result<Vec<MarketEvent>> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| anyhow::anyhow!("cannot open recording {}: {}", path.display(), e))?;
+
+    let mut recorded = Vec::new();
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<RecordedEvent>(&line) {
+            Ok(event) => recorded.push(event),
+            Err(e) => warn!("⚠️ Skipping malformed recording line {}: {}", index + 1, e),
+        }
+    }
+    recorded.sort_by_key(|r| r.timestamp_us);
+
+    let tracker = WalletTracker::new(WalletTrackerConfig::from_env());
+    let mut signatures = HashSet::new();
+    let mut events = Vec::with_capacity(recorded.len());
+    for RecordedEvent { timestamp_us, source, event } in recorded {
+        let latency_us = event.timestamp_us().map(|t| timestamp_us - t);
+        if let MarketDataMessage::Transaction(transaction) = &event {
+            // A transaction recorded from several sources counts once, as after the live merger
+            if !signatures.insert(transaction.signature.clone()) {
+                continue;
+            }
+            for derived in data_ingestor::derive_events(&tracker, transaction) {
+                events.push(MarketEvent {
+                    source: source.clone(),
+                    received_at_us: timestamp_us,
+                    latency_us,
+                    event: derived,
+                });
+            }
+        }
+        events.push(MarketEvent { source, received_at_us: timestamp_us, latency_us, event });
+    }
+
+    Ok(events)
+}
+
+struct StrategySlot {
+    config: StrategyConfig,
+    strategy: Box<dyn Strategy>,
+    /// Same allocation accounting as the live strategy task
+    deployed_sol: f64,
+    sequence: u64,
+}
+
+struct OpenPosition {
+    position: Position,
+    strategy: usize,
+    /// SOL paid for the tokens still held, fees included
+    cost_sol: f64,
+    opened_ms: i64,
+}
+
+struct PendingOrder {
+    id: String,
+    strategy: usize,
+    mint: String,
+    side: TradeSide,
+    /// SOL to spend on a buy
+    size_sol: f64,
+    /// Share of the position a sell closes
+    fraction: f64,
+    exit_reason: ExitReason,
+    exit_rules: ExitRules,
+    placed_ms: i64,
+    fill_at_ms: i64,
+}
+
+/// Runs strategies over recorded events against a simulated wallet
+///
+/// Signals pass the live sizing models, position limits and exit rules. Checks that need the
+/// network (rug screening, Jupiter price impact, VaR and correlation from cached history) are
+/// not applied. Venue quotes are not recorded, so arbitrage round trips settle at the edge the
+/// signal quoted.
+pub struct Backtester {
+    config: BacktestConfig,
+    limits: PositionLimits,
+    sizing: SizingConfig,
+    exit_rules: ExitRules,
+    strategies: Vec<StrategySlot>,
+    cash_sol: f64,
+    positions: HashMap<String, OpenPosition>,
+    pending: Vec<PendingOrder>,
+    /// Latest price and its time (ms) per mint
+    marks: HashMap<String, (f64, i64)>,
+    /// Last `vol_lookback` marks per mint, oldest first, standing in for the live price cache
+    history: HashMap<String, VecDeque<(f64, i64)>>,
+    decimals: HashMap<String, u8>,
+    latest_slot: u64,
+    now_ms: i64,
+    /// UTC day and its realized PnL, for the daily loss halt
+    day: i64,
+    day_pnl: f64,
+    /// Day the daily loss limit tripped; like a live halt, it holds until the day is over
+    halted_day: Option<i64>,
+    trades: Vec<Trade>,
+    curve: Vec<EquityPoint>,
+    peak_equity: f64,
+    next_sample_ms: i64,
+    events: u64,
+    signals: u64,
+    rejected: u64,
+}
+
+impl Backtester {
+    /// Build every enabled strategy in `configs` from the built-in registry
+    pub fn new(config: BacktestConfig, configs: &[StrategyConfig]) -> anyhow::Result<Self> {
+        let registry = strategy_engine::builtin_registry();
+        let mut strategies = Vec::new();
+        for strategy_config in configs.iter().filter(|c| c.enabled) {
+            strategies.push(StrategySlot {
+                config: strategy_config.clone(),
+                strategy: registry.build(strategy_config)?,
+                deployed_sol: 0.0,
+                sequence: 0,
+            });
+        }
+        if strategies.is_empty() {
+            anyhow::bail!("no enabled strategies to backtest");
+        }
+
+        Ok(Self {
+            cash_sol: config.initial_capital_sol,
+            peak_equity: config.initial_capital_sol,
+            config,
+            limits: PositionLimits::from_env(),
+            sizing: SizingConfig::from_env(),
+            exit_rules: ExitRules::from_env(),
+            strategies,
+            positions: HashMap::new(),
+            pending: Vec::new(),
+            marks: HashMap::new(),
+            history: HashMap::new(),
+            decimals: HashMap::new(),
+            latest_slot: 0,
+            now_ms: 0,
+            day: 0,
+            day_pnl: 0.0,
+            halted_day: None,
+            trades: Vec::new(),
+            curve: Vec::new(),
+            next_sample_ms: 0,
+            events: 0,
+            signals: 0,
+            rejected: 0,
+        })
+    }
+
+    /// Replay `events` in order and report the results
+    pub async fn run(mut self, events: &[MarketEvent]) -> BacktestReport {
+        let started_ms = events.first().map_or(0, |e| e.received_at_us / 1000);
+
+        for event in events {
+            self.now_ms = event.received_at_us / 1000;
+            if let Some(slot) = event.event.slot() {
+                self.latest_slot = self.latest_slot.max(slot);
+            }
+            let priced = self.observe(&event.event);
+
+            self.fill_due_orders().await;
+            self.check_daily_loss();
+            if let Some(mint) = priced {
+                self.check_exits(&mint);
+            }
+
+            for index in 0..self.strategies.len() {
+                let strategy = &mut self.strategies[index].strategy;
+                let result = match &event.event {
+                    MarketDataMessage::PriceTick(tick) => strategy.on_tick(tick).await,
+                    _ => strategy.on_event(event).await,
+                };
+                match result {
+                    Ok(signals) => self.handle_signals(index, signals),
+                    Err(e) => warn!("⚠️ Strategy {} failed: {}", self.strategies[index].config.id, e),
+                }
+            }
+
+            self.events += 1;
+            if self.now_ms >= self.next_sample_ms {
+                self.sample_equity();
+            }
+        }
+
+        if !self.pending.is_empty() {
+            debug!("🧪 {} orders never saw a price after their latency", self.pending.len());
+        }
+        self.sample_equity();
+
+        let mut metrics = BacktestMetrics::compute(
+            self.config.initial_capital_sol,
+            &self.curve,
+            &self.trades,
+            self.config.equity_interval_ms,
+        );
+        metrics.signals = self.signals;
+        metrics.rejected_signals = self.rejected;
+
+        BacktestReport {
+            strategies: self.strategies.iter().map(|s| s.config.id.clone()).collect(),
+            events: self.events,
+            started_ms,
+            ended_ms: self.now_ms,
+            fill_model: self.config.fills.clone(),
+            metrics,
+            equity_curve: self.curve,
+            trades: self.trades,
+        }
+    }
+
+    /// Record prices and decimals carried by the event; returns the mint whose price moved
+    fn observe(&mut self, event: &MarketDataMessage) -> Option<String> {
+        let (mint, price) = match event {
+            MarketDataMessage::PriceTick(tick) => (&tick.mint, tick.price),
+            MarketDataMessage::Swap(swap) => (&swap.mint, swap.price()),
+            MarketDataMessage::WalletActivity(activity) => {
+                self.decimals.insert(activity.mint.clone(), activity.decimals);
+                return None;
+            }
+            MarketDataMessage::Transaction(transaction) => {
+                for balance in &transaction.post_token_balances {
+                    self.decimals.insert(balance.mint.clone(), balance.decimals);
+                }
+                return None;
+            }
+            _ => return None,
+        };
+        if price <= 0.0 || !price.is_finite() {
+            return None;
+        }
+
+        self.marks.insert(mint.clone(), (price, self.now_ms));
+        let history = self.history.entry(mint.clone()).or_default();
+        history.push_back((price, self.now_ms));
+        while history.len() > self.sizing.vol_lookback.max(0) as usize {
+            history.pop_front();
+        }
+        if let Some(open) = self.positions.get_mut(mint) {
+            open.position.last_price = price;
+            open.position.current_pnl = open.position.size * (price - open.position.entry_price);
+        }
+        Some(mint.clone())
+    }
+
+    fn decimals(&self, mint: &str) -> u8 {
+        self.decimals.get(mint).copied().unwrap_or(self.config.default_decimals)
+    }
+
+    fn exposure(&self) -> f64 {
+        self.positions.values().map(|p| p.position.market_value()).sum()
+    }
+
+    fn mint_exposure(&self, mint: &str) -> f64 {
+        self.positions.get(mint).map_or(0.0, |p| p.position.market_value())
+    }
+
+    fn available_capital(&self) -> f64 {
+        self.cash_sol - self.limits.min_sol_reserve
+    }
+
+    /// Trip the daily loss halt on realized plus open PnL, as the live circuit breaker does
+    fn check_daily_loss(&mut self) {
+        let today = self.now_ms.div_euclid(DAY_MS);
+        let realized = if self.day == today { self.day_pnl } else { 0.0 };
+        let unrealized: f64 = self.positions.values().map(|p| p.position.current_pnl).sum();
+        if self.halted_day != Some(today) && realized + unrealized <= -self.limits.max_daily_loss {
+            debug!("🧪 Daily loss limit hit: realized {:.4} SOL, open {:.4} SOL", realized, unrealized);
+            self.halted_day = Some(today);
+        }
+    }
+
+    fn halted(&self) -> bool {
+        self.halted_day == Some(self.now_ms.div_euclid(DAY_MS))
+    }
+
+    fn handle_signals(&mut self, strategy: usize, signals: Vec<StrategySignal>) {
+        for signal in signals {
+            self.signals += 1;
+            let slot = &mut self.strategies[strategy];
+            slot.sequence += 1;
+            let remaining = (slot.config.capital_sol - slot.deployed_sol).max(0.0);
+            let signal = SignalMessage::from_strategy(signal, &slot.config, slot.sequence, remaining);
+
+            let decision = self.assess(&signal);
+            if !decision.approved {
+                self.rejected += 1;
+                debug!("🧪 {} signal on {} rejected: {}", self.strategies[strategy].config.id,
+                       signal.mint, decision.reasons.join("; "));
+                continue;
+            }
+
+            let id = signal.id.clone();
+            match signal.side {
+                TradeSide::Buy if signal.arbitrage.is_some() => self.settle_arbitrage(strategy, &signal, &decision),
+                TradeSide::Buy => {
+                    let exit_rules = self
+                        .exit_rules
+                        .clone()
+                        .with_levels(signal.price, signal.target_price, signal.stop_price);
+                    self.place(id, strategy, &signal.mint, TradeSide::Buy, decision.adjusted_size, 1.0,
+                               ExitReason::Signal, exit_rules);
+                }
+                TradeSide::Sell => {
+                    let fraction = (decision.adjusted_size / self.mint_exposure(&signal.mint).max(f64::EPSILON)).min(1.0);
+                    self.place(id, strategy, &signal.mint, TradeSide::Sell, 0.0, fraction,
+                               ExitReason::Signal, self.exit_rules.clone());
+                }
+            }
+        }
+    }
+
+    /// The live manager's offline rules, against the simulated book
+    fn assess(&self, signal: &SignalMessage) -> RiskDecision {
+        let rules = DecisionRules {
+            limits: &self.limits,
+            sizing: &self.sizing,
+            stop_loss_pct: self.exit_rules.stop_loss_pct,
+        };
+        let mut book = BookState {
+            mint_exposure: self.mint_exposure(&signal.mint),
+            total_exposure: self.exposure(),
+            available_capital: Ok(self.available_capital()),
+            halted: self.halted().then(|| "daily loss limit".to_string()),
+            deadline_passed: signal.deadline_slot.is_some_and(|deadline| self.latest_slot > deadline),
+            order_in_flight: self.pending.iter().any(|o| o.mint == signal.mint),
+            held_by_other_wallet: None,
+            volatility: None,
+        };
+        if signal.side == TradeSide::Buy && signal.size_sol.is_none() && self.sizing.model == SizingModel::VolatilityTarget {
+            book.volatility = self.volatility(&signal.mint);
+        }
+        decision::assess(&rules, signal, &book)
+    }
+
+    /// Per-interval return volatility of the replayed marks, measured as the live manager does
+    fn volatility(&self, mint: &str) -> Option<f64> {
+        let history: Vec<_> = self.history.get(mint)?.iter().copied().collect();
+        let returns = var::aligned_log_returns(&[history], self.sizing.vol_interval_ms, self.sizing.vol_lookback.max(0) as usize);
+        sizing::volatility(&returns[0])
+    }
+
+    /// Book an approved round trip at the signal's quoted edge; nothing is left open
+    fn settle_arbitrage(&mut self, strategy: usize, signal: &SignalMessage, decision: &RiskDecision) {
+        let Some(arbitrage) = &signal.arbitrage else { return };
+        let fees = arbitrage.cost_lamports() as f64 / LAMPORTS_PER_SOL as f64;
+        let min_profit = arbitrage.min_profit_lamports as f64 / LAMPORTS_PER_SOL as f64;
+        let edge = decision.adjusted_size * signal.expected_return.unwrap_or(0.0);
+        // Below its minimum profit the transaction reverts and only the fees are lost
+        let pnl = if edge >= min_profit { edge } else { -fees };
+
+        let cost = decision.adjusted_size + fees;
+        self.cash_sol += pnl;
+        self.book_realized(pnl);
+        self.trades.push(Trade {
+            strategy_id: self.strategies[strategy].config.id.clone(),
+            mint: signal.mint.clone(),
+            opened_ms: self.now_ms,
+            closed_ms: self.now_ms,
+            entry_price: signal.price,
+            exit_price: signal.price,
+            tokens: 0.0,
+            cost_sol: cost,
+            proceeds_sol: cost + pnl,
+            pnl_sol: pnl,
+            return_pct: pnl / cost,
+            exit_reason: "Arbitrage".to_string(),
+        });
+    }
+
+    fn book_realized(&mut self, pnl: f64) {
+        let day = self.now_ms.div_euclid(DAY_MS);
+        if day != self.day {
+            self.day = day;
+            self.day_pnl = 0.0;
+        }
+        self.day_pnl += pnl;
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn place(
+        &mut self,
+        id: String,
+        strategy: usize,
+        mint: &str,
+        side: TradeSide,
+        size_sol: f64,
+        fraction: f64,
+        exit_reason: ExitReason,
+        exit_rules: ExitRules,
+    ) {
+        self.pending.push(PendingOrder {
+            id,
+            strategy,
+            mint: mint.to_string(),
+            side,
+            size_sol,
+            fraction,
+            exit_reason,
+            exit_rules,
+            placed_ms: self.now_ms,
+            fill_at_ms: self.now_ms + self.config.fills.latency_ms,
+        });
+    }
+
+    /// Exit rules of open positions, as the live monitor evaluates them
+    fn check_exits(&mut self, mint: &str) {
+        if self.pending.iter().any(|o| o.mint == mint) {
+            return;
+        }
+        let now_secs = self.now_ms / 1000;
+        let Some(open) = self.positions.get_mut(mint) else { return };
+        let position = &mut open.position;
+        let Some(trigger) = exits::evaluate(
+            &position.exit_rules,
+            &mut position.exit_state,
+            position.entry_price,
+            position.last_price,
+            now_secs,
+        ) else {
+            return;
+        };
+
+        let strategy = open.strategy;
+        let id = format!("exit-{}-{}", mint, self.now_ms);
+        self.place(id, strategy, mint, TradeSide::Sell, 0.0, trigger.fraction, trigger.reason, self.exit_rules.clone());
+    }
+
+    /// Fill orders whose latency has passed at the first price seen after it
+    async fn fill_due_orders(&mut self) {
+        let (due, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut self.pending).into_iter().partition(|order| {
+            order.fill_at_ms <= self.now_ms
+                && self.marks.get(&order.mint).is_some_and(|(_, at)| *at >= order.fill_at_ms)
+        });
+        self.pending = waiting;
+
+        for order in due {
+            let mark = self.marks[&order.mint].0;
+            let report = match order.side {
+                TradeSide::Buy => self.fill_buy(&order, mark),
+                TradeSide::Sell => self.fill_sell(&order, mark),
+            };
+
+            let slot = &mut self.strategies[order.strategy];
+            if report.success {
+                match report.side {
+                    TradeSide::Buy => slot.deployed_sol += report.in_amount as f64 / LAMPORTS_PER_SOL as f64,
+                    TradeSide::Sell => {
+                        slot.deployed_sol = (slot.deployed_sol - report.out_amount as f64 / LAMPORTS_PER_SOL as f64).max(0.0)
+                    }
+                }
+            }
+            match slot.strategy.on_fill(&report).await {
+                Ok(signals) => self.handle_signals(order.strategy, signals),
+                Err(e) => warn!("⚠️ Strategy {} failed on fill: {}", slot.config.id, e),
+            }
+        }
+    }
+
+    fn report(&self, order: &PendingOrder, in_amount: u64, out_amount: u64, error: Option<String>) -> ExecutionReport {
+        ExecutionReport {
+            order_id: order.id.clone(),
+            mint: order.mint.clone(),
+            side: order.side,
+            success: error.is_none(),
+            signature: None,
+            in_amount,
+            out_amount,
+            error,
+            latency_ms: (self.now_ms - order.placed_ms).max(0) as u64,
+            simulated: true,
+        }
+    }
+
+    fn fill_buy(&mut self, order: &PendingOrder, mark: f64) -> ExecutionReport {
+        let fill = self.config.fills.buy(order.size_sol, mark);
+        if fill.sol > self.cash_sol {
+            return self.report(order, 0, 0, Some("insufficient SOL".to_string()));
+        }
+
+        let decimals = self.decimals(&order.mint);
+        // Entry price includes the fee, as it does when derived from a live fill report
+        let entry_price = order.size_sol / fill.tokens;
+        self.cash_sol -= fill.sol;
+
+        match self.positions.get_mut(&order.mint) {
+            Some(open) => {
+                let position = &mut open.position;
+                let cost = position.size * position.entry_price + fill.tokens * entry_price;
+                position.size += fill.tokens;
+                position.entry_price = cost / position.size;
+                position.current_pnl = position.size * (position.last_price - position.entry_price);
+                open.cost_sol += fill.sol;
+            }
+            None => {
+                let mut position = Position::new(
+                    order.mint.clone(),
+                    fill.tokens,
+                    entry_price,
+                    decimals,
+                    order.exit_rules.clone(),
+                );
+                position.last_price = mark;
+                position.current_pnl = position.size * (mark - entry_price);
+                position.exit_state.opened_at = self.now_ms / 1000;
+                position.strategy_id = Some(self.strategies[order.strategy].config.id.clone());
+                self.positions.insert(order.mint.clone(), OpenPosition {
+                    position,
+                    strategy: order.strategy,
+                    cost_sol: fill.sol,
+                    opened_ms: self.now_ms,
+                });
+            }
+        }
+
+        let in_amount = (order.size_sol * LAMPORTS_PER_SOL as f64) as u64;
+        let out_amount = (fill.tokens * 10f64.powi(decimals as i32)) as u64;
+        self.report(order, in_amount, out_amount, None)
+    }
+
+    fn fill_sell(&mut self, order: &PendingOrder, mark: f64) -> ExecutionReport {
+        let Some(open) = self.positions.get_mut(&order.mint) else {
+            return self.report(order, 0, 0, Some("no open position".to_string()));
+        };
+
+        let fraction = order.fraction.clamp(0.0, 1.0);
+        let fill = self.config.fills.sell(open.position.size * fraction, mark);
+        let cost = open.cost_sol * fraction;
+        let pnl = fill.sol - cost;
+
+        let trade = Trade {
+            strategy_id: self.strategies[open.strategy].config.id.clone(),
+            mint: order.mint.clone(),
+            opened_ms: open.opened_ms,
+            closed_ms: self.now_ms,
+            entry_price: open.position.entry_price,
+            exit_price: fill.price,
+            tokens: fill.tokens,
+            cost_sol: cost,
+            proceeds_sol: fill.sol,
+            pnl_sol: pnl,
+            return_pct: if cost > 0.0 { pnl / cost } else { 0.0 },
+            exit_reason: format!("{:?}", order.exit_reason),
+        };
+
+        if let ExitReason::TakeProfit(tier) = order.exit_reason {
+            open.position.exit_state.tiers_filled = tier + 1;
+        }
+        open.position.size -= fill.tokens;
+        open.position.current_pnl = open.position.size * (mark - open.position.entry_price);
+        open.cost_sol -= cost;
+        let decimals = open.position.decimals;
+        if open.position.size <= DUST_TOKENS {
+            self.positions.remove(&order.mint);
+        }
+
+        self.cash_sol += fill.sol;
+        self.book_realized(pnl);
+        self.trades.push(trade);
+
+        let in_amount = (fill.tokens * 10f64.powi(decimals as i32)) as u64;
+        let out_amount = (fill.sol * LAMPORTS_PER_SOL as f64) as u64;
+        self.report(order, in_amount, out_amount, None)
+    }
+
+    fn sample_equity(&mut self) {
+        let exposure = self.exposure();
+        let equity = self.cash_sol + exposure;
+        self.peak_equity = self.peak_equity.max(equity);
+        self.curve.push(EquityPoint {
+            timestamp_ms: self.now_ms,
+            equity_sol: equity,
+            cash_sol: self.cash_sol,
+            exposure_sol: exposure,
+            drawdown: if self.peak_equity > 0.0 { 1.0 - equity / self.peak_equity } else { 0.0 },
+        });
+
+        let interval = self.config.equity_interval_ms.max(1);
+        self.next_sample_ms = (self.now_ms.div_euclid(interval) + 1) * interval;
+    }
+}
+
+/// `sniper-core backtest [EVENTS] [--strategies PATH] [--out DIR] [--capital SOL]`
+pub async fn run_cli(args: &[String]) -> anyhow::Result<()> {
+    let mut config = BacktestConfig::from_env();
+    let mut strategies_path = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| {
+            args.next().cloned().ok_or_else(|| anyhow::anyhow!("{} needs a value", flag))
+        };
+        match arg.as_str() {
+            "--strategies" => strategies_path = Some(value(arg)?),
+            "--out" => config.output_dir = value(arg)?.into(),
+            "--capital" => config.initial_capital_sol = value(arg)?.parse()?,
+            flag if flag.starts_with("--") => anyhow::bail!("unknown backtest option {}", flag),
+            path => config.events_path = path.into(),
+        }
+    }
+
+    let configs = match strategies_path {
+        Some(path) => serde_json::from_str(&std::fs::read_to_string(&path)?)?,
+        None => strategy_engine::registry::load_configs()?,
+    };
+
+    info!("🧪 Backtesting {} against {}", config.events_path.display(), config.output_dir.display());
+    let events = load_events(&config.events_path)?;
+    info!("🧪 Loaded {} events", events.len());
+
+    let output_dir = config.output_dir.clone();
+    let report = Backtester::new(config, &configs)?.run(&events).await;
+    report.write(&output_dir)?;
+
+    let m = &report.metrics;
+    info!("🧪 Backtest done: return {:+.2}%, Sharpe {:?}, Sortino {:?}, max drawdown {:.2}%, {} trades, win rate {:?}",
+          m.total_return * 100.0, m.sharpe, m.sortino, m.max_drawdown * 100.0, m.trades, m.win_rate);
+    info!("🧪 Report written to {}", output_dir.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/range_ticks.ndjson");
+
+    fn config() -> BacktestConfig {
+        BacktestConfig {
+            events_path: FIXTURE.into(),
+            output_dir: std::env::temp_dir(),
+            initial_capital_sol: 10.0,
+            equity_interval_ms: 60_000,
+            default_decimals: 6,
+            fills: FillModel::default(),
+        }
+    }
+
+    /// Same range-trading setup as the mean reversion strategy tests
+    fn strategies() -> Vec<StrategyConfig> {
+        vec![StrategyConfig {
+            id: "mr".to_string(),
+            kind: "mean_reversion".to_string(),
+            enabled: true,
+            wallet: None,
+            capital_sol: 2.0,
+            params: json!({
+                "rsi_period": 3,
+                "bollinger_period": 5,
+                "bollinger_k": 1.0,
+                "sr_lookback": 10,
+                "sr_tolerance": 0.02,
+                "size_sol": 0.5,
+            }),
+        }]
+    }
+
+    #[tokio::test]
+    async fn replays_recording_into_report() {
+        let events = load_events(Path::new(FIXTURE)).unwrap();
+        // The truncated line and the blank line are skipped
+        assert_eq!(events.len(), 20);
+
+        let report = Backtester::new(config(), &strategies()).unwrap().run(&events).await;
+        assert_eq!(report.strategies, vec!["mr".to_string()]);
+        assert_eq!(report.events, 20);
+        assert_eq!(report.started_ms, 1_700_000_030_000);
+        assert_eq!(report.ended_ms, 1_700_001_170_000);
+
+        // Buy after candle 10, sell after candle 14 (lost to the take-profit already in flight),
+        // buy after candle 18 (never filled: the recording ends first)
+        let m = &report.metrics;
+        assert_eq!(m.signals, 3);
+        assert_eq!(m.rejected_signals, 1);
+
+        // The signal's target became the take-profit, which fills one tick after it triggers
+        assert_eq!(report.trades.len(), 1);
+        let trade = &report.trades[0];
+        assert_eq!(trade.strategy_id, "mr");
+        assert_eq!(trade.exit_reason, "TakeProfit(0)");
+        assert_eq!(trade.opened_ms, 1_700_000_750_000);
+        assert_eq!(trade.closed_ms, 1_700_000_990_000);
+        // Fills pay slippage, impact and fees both ways
+        assert!(trade.entry_price > 97.0);
+        assert!(trade.exit_price < 100.0 && trade.exit_price > 99.0);
+        assert!((trade.pnl_sol - (trade.proceeds_sol - trade.cost_sol)).abs() < 1e-12);
+        assert!(trade.pnl_sol > 0.0);
+
+        assert_eq!(m.trades, 1);
+        assert_eq!(m.win_rate, Some(1.0));
+        assert!((m.final_equity_sol - (10.0 + trade.pnl_sol)).abs() < 1e-9);
+        assert!((m.total_return - trade.pnl_sol / 10.0).abs() < 1e-9);
+
+        // One equity sample per minute plus the closing sample
+        assert_eq!(report.equity_curve.len(), 21);
+        let last = report.equity_curve.last().unwrap();
+        assert_eq!(last.exposure_sol, 0.0);
+        assert!((last.cash_sol - m.final_equity_sol).abs() < 1e-12);
+    }
+
+    /// The range setup with sizing left to the risk manager
+    fn unsized_strategies() -> Vec<StrategyConfig> {
+        let mut configs = strategies();
+        configs[0].params.as_object_mut().unwrap().remove("size_sol");
+        configs
+    }
+
+    async fn run_with(sizing: SizingConfig, limits: PositionLimits) -> BacktestReport {
+        let events = load_events(Path::new(FIXTURE)).unwrap();
+        let mut backtester = Backtester::new(config(), &unsized_strategies()).unwrap();
+        backtester.sizing = sizing;
+        backtester.limits = limits;
+        backtester.run(&events).await
+    }
+
+    fn volatility_target(target_volatility: f64) -> SizingConfig {
+        SizingConfig {
+            model: SizingModel::VolatilityTarget,
+            target_volatility,
+            vol_interval_ms: 60_000,
+            vol_lookback: 500,
+            ..SizingConfig::from_env()
+        }
+    }
+
+    #[tokio::test]
+    async fn volatility_target_sizes_from_replayed_marks() {
+        let limits = PositionLimits {
+            max_position_size: 1.0,
+            max_daily_loss: 5.0,
+            ..PositionLimits::from_env()
+        };
+        let low = run_with(volatility_target(0.000_5), limits.clone()).await;
+        let high = run_with(volatility_target(0.001), limits.clone()).await;
+
+        // Same entries as with a fixed size, sized from the realized volatility of the marks so far
+        for report in [&low, &high] {
+            assert_eq!((report.metrics.signals, report.metrics.rejected_signals), (3, 1));
+            assert_eq!(report.trades.len(), 1);
+            assert_eq!(report.trades[0].opened_ms, 1_700_000_750_000);
+        }
+        let size = |report: &BacktestReport| report.trades[0].cost_sol - FillModel::default().tx_fee_sol;
+        assert!(size(&low) > 0.01 && size(&high) < 1.0);
+        // Size scales linearly with the volatility budget
+        assert!((size(&high) / size(&low) - 2.0).abs() < 1e-9);
+
+        // Without enough marks for a return series, the buy cannot be sized
+        let blind = run_with(SizingConfig { vol_lookback: 1, ..volatility_target(0.001) }, limits).await;
+        assert!(blind.trades.is_empty());
+        assert_eq!(blind.metrics.rejected_signals, 3);
+    }
+
+    #[tokio::test]
+    async fn fixed_fractional_sizes_from_equity() {
+        let sizing = SizingConfig {
+            model: SizingModel::FixedFractional,
+            equity_fraction: 0.05,
+            ..SizingConfig::from_env()
+        };
+        let report = run_with(sizing, PositionLimits { max_position_size: 1.0, ..PositionLimits::from_env() }).await;
+        assert_eq!(report.trades.len(), 1);
+        // 5% of 10 SOL, scaled by the signal's confidence
+        let size = report.trades[0].cost_sol - FillModel::default().tx_fee_sol;
+        assert!(size > 0.5 * 0.5 && size < 0.5);
+    }
+
+    #[tokio::test]
+    async fn daily_loss_halt_blocks_entries_but_not_exits() {
+        let sizing = SizingConfig {
+            model: SizingModel::FixedNotional,
+            fixed_notional: 1.0,
+            ..SizingConfig::from_env()
+        };
+        // Fees and slippage put the fresh position this far under water
+        let limits = PositionLimits {
+            max_position_size: 1.0,
+            max_daily_loss: 0.001,
+            ..PositionLimits::from_env()
+        };
+        let report = run_with(sizing.clone(), limits.clone()).await;
+
+        // The take-profit still closes the open trade, but the candle 18 entry is refused
+        assert_eq!(report.trades.len(), 1);
+        assert_eq!(report.trades[0].exit_reason, "TakeProfit(0)");
+        assert_eq!(report.metrics.rejected_signals, 2);
+
+        // With room under the limit only the in-flight sell is refused
+        let loose = run_with(sizing, PositionLimits { max_daily_loss: 5.0, ..limits }).await;
+        assert_eq!(loose.metrics.rejected_signals, 1);
+    }
+}
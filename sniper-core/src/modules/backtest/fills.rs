@@ -0,0 +1,88 @@
+// 🧾 Fill Model
+// Simulated execution price, fees and latency for backtest orders
+
+use serde::{Deserialize, Serialize};
+
+/// How simulated orders fill against the replayed price
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FillModel {
+    /// Fixed slippage against the mark price
+    pub slippage_bps: f64,
+    /// Extra slippage per SOL of notional, a linear stand-in for price impact
+    pub impact_bps_per_sol: f64,
+    /// DEX fee charged on the input side
+    pub fee_bps: f64,
+    /// Network, priority and tip fees per transaction
+    pub tx_fee_sol: f64,
+    /// Time between the signal and the fill; the fill takes the first price after it
+    pub latency_ms: i64,
+}
+
+impl Default for FillModel {
+    fn default() -> Self {
+        Self {
+            slippage_bps: 50.0,
+            impact_bps_per_sol: 20.0,
+            fee_bps: 25.0,
+            tx_fee_sol: 0.000_01,
+            latency_ms: 400,
+        }
+    }
+}
+
+/// Result of one simulated fill
+#[derive(Debug, Clone, Copy)]
+pub struct Fill {
+    /// Average price paid or received, SOL per token
+    pub price: f64,
+    pub tokens: f64,
+    /// SOL leaving the wallet for buys, arriving for sells, fees included
+    pub sol: f64,
+}
+
+impl FillModel {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let parse = |key: &str, default: f64| {
+            std::env::var(key)
+                .ok()
+                .and_then(|v| v.parse::<f64>().ok())
+                .unwrap_or(default)
+        };
+
+        Self {
+            slippage_bps: parse("BACKTEST_SLIPPAGE_BPS", defaults.slippage_bps),
+            impact_bps_per_sol: parse("BACKTEST_IMPACT_BPS_PER_SOL", defaults.impact_bps_per_sol),
+            fee_bps: parse("BACKTEST_FEE_BPS", defaults.fee_bps),
+            tx_fee_sol: parse("BACKTEST_TX_FEE_SOL", defaults.tx_fee_sol),
+            latency_ms: parse("BACKTEST_LATENCY_MS", defaults.latency_ms as f64) as i64,
+        }
+    }
+
+    fn slippage(&self, notional_sol: f64) -> f64 {
+        (self.slippage_bps + self.impact_bps_per_sol * notional_sol.max(0.0)) / 10_000.0
+    }
+
+    /// Spend `size_sol` at `mark`; the fee comes out of the input
+    pub fn buy(&self, size_sol: f64, mark: f64) -> Fill {
+        let price = mark * (1.0 + self.slippage(size_sol));
+        let swapped = size_sol * (1.0 - self.fee_bps / 10_000.0);
+        Fill {
+            price,
+            tokens: swapped / price,
+            sol: size_sol + self.tx_fee_sol,
+        }
+    }
+
+    /// Sell `tokens` at `mark`; the fee comes out of the output
+    pub fn sell(&self, tokens: f64, mark: f64) -> Fill {
+        let price = mark * (1.0 - self.slippage(tokens * mark)).max(0.0);
+        let proceeds = tokens * price * (1.0 - self.fee_bps / 10_000.0);
+        Fill {
+            price,
+            tokens,
+            sol: (proceeds - self.tx_fee_sol).max(0.0),
+        }
+    }
+}
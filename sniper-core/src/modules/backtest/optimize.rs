@@ -0,0 +1,545 @@
+// 🎛️ Parameter Optimization
+// Grid and random search over strategy params with walk-forward validation
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+use tracing::{info, warn};
+
+use super::{BacktestConfig, BacktestMetrics, Backtester};
+use crate::modules::data_ingestor::MarketEvent;
+use crate::modules::strategy_engine::StrategyConfig;
+
+/// Grids larger than this are refused; use random search instead
+const MAX_GRID_CANDIDATES: usize = 10_000;
+
+/// Values one parameter may take
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ParamRange {
+    Values {
+        values: Vec<Value>,
+    },
+    /// Numeric range; grid search needs `step`, random search samples uniformly
+    Range {
+        min: f64,
+        max: f64,
+        #[serde(default)]
+        step: Option<f64>,
+        #[serde(default)]
+        integer: bool,
+    },
+}
+
+impl ParamRange {
+    fn grid(&self, name: &str) -> anyhow::Result<Vec<Value>> {
+        match self {
+            Self::Values { values } => Ok(values.clone()),
+            Self::Range { min, max, step, integer } => {
+                let step = step
+                    .filter(|s| *s > 0.0)
+                    .ok_or_else(|| anyhow::anyhow!("grid search needs a positive step for {}", name))?;
+                let count = ((max - min) / step + 1e-9).floor().max(0.0) as usize + 1;
+                Ok((0..count).map(|i| number(min + step * i as f64, *integer)).collect())
+            }
+        }
+    }
+
+    fn sample(&self, rng: &mut StdRng) -> Value {
+        match self {
+            Self::Values { values } if values.is_empty() => Value::Null,
+            Self::Values { values } => values[rng.gen_range(0..values.len())].clone(),
+            Self::Range { min, max, .. } if max <= min => number(*min, self.is_integer()),
+            Self::Range { min, max, integer, .. } => number(rng.gen_range(*min..=*max), *integer),
+        }
+    }
+
+    fn is_integer(&self) -> bool {
+        matches!(self, Self::Range { integer: true, .. })
+    }
+}
+
+fn number(value: f64, integer: bool) -> Value {
+    if integer {
+        Value::from(value.round() as i64)
+    } else {
+        Value::from(value)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchMethod {
+    Grid,
+    Random {
+        samples: usize,
+        #[serde(default)]
+        seed: Option<u64>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Objective {
+    Sharpe,
+    Sortino,
+    TotalReturn,
+    /// Total return over max drawdown
+    Calmar,
+    WinRate,
+    ProfitFactor,
+}
+
+impl Objective {
+    /// Higher is better; `None` when the run leaves the metric undefined
+    pub fn score(&self, metrics: &BacktestMetrics) -> Option<f64> {
+        let score = match self {
+            Self::Sharpe => metrics.sharpe,
+            Self::Sortino => metrics.sortino,
+            Self::TotalReturn => Some(metrics.total_return),
+            Self::Calmar => (metrics.max_drawdown > 0.0).then(|| metrics.total_return / metrics.max_drawdown),
+            Self::WinRate => metrics.win_rate,
+            Self::ProfitFactor => metrics.profit_factor,
+        };
+        score.filter(|s| s.is_finite())
+    }
+}
+
+/// Optimization run, read from a JSON spec file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizeSpec {
+    /// Base instance; searched params override its `params`, dotted names reach nested ones
+    pub strategy: StrategyConfig,
+    pub space: BTreeMap<String, ParamRange>,
+    pub method: SearchMethod,
+    pub objective: Objective,
+    /// Walk-forward windows; 0 scores every candidate on the whole recording
+    #[serde(default)]
+    pub folds: usize,
+    /// Share of each window used to pick parameters; the rest validates them
+    #[serde(default = "default_in_sample_fraction")]
+    pub in_sample_fraction: f64,
+    /// Worker threads; 0 uses every core
+    #[serde(default)]
+    pub threads: usize,
+    /// Candidates kept in the report
+    #[serde(default = "default_top")]
+    pub top: usize,
+}
+
+fn default_in_sample_fraction() -> f64 {
+    0.7
+}
+
+fn default_top() -> usize {
+    50
+}
+
+/// In-sample and out-of-sample event ranges of one walk-forward window
+#[derive(Debug, Clone, Serialize)]
+pub struct Fold {
+    pub index: usize,
+    pub in_sample_ms: (i64, i64),
+    pub out_of_sample_ms: (i64, i64),
+    #[serde(skip)]
+    in_sample: std::ops::Range<usize>,
+    #[serde(skip)]
+    out_of_sample: std::ops::Range<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CandidateResult {
+    pub rank: usize,
+    pub params: BTreeMap<String, Value>,
+    /// Mean objective across the folds where it is defined
+    pub in_sample_score: Option<f64>,
+    pub out_of_sample_score: Option<f64>,
+    /// Out-of-sample over a positive in-sample score; well below 1 suggests overfitting
+    pub efficiency: Option<f64>,
+    pub in_sample_metrics: Vec<BacktestMetrics>,
+    /// One per fold; `None` where the fold has no out-of-sample part
+    pub out_of_sample_metrics: Vec<Option<BacktestMetrics>>,
+}
+
+/// Parameters picked on one window's in-sample part and how they did afterwards
+#[derive(Debug, Clone, Serialize)]
+pub struct FoldSelection {
+    pub fold: Fold,
+    pub params: BTreeMap<String, Value>,
+    pub in_sample_score: Option<f64>,
+    pub out_of_sample_score: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OptimizationReport {
+    pub strategy_id: String,
+    pub kind: String,
+    pub objective: Objective,
+    pub method: SearchMethod,
+    pub candidates: usize,
+    /// Candidates whose strategy could not be built from the params
+    pub invalid: usize,
+    pub walk_forward: Vec<FoldSelection>,
+    /// Mean out-of-sample score of the per-fold selections
+    pub walk_forward_score: Option<f64>,
+    pub ranked: Vec<CandidateResult>,
+}
+
+impl OptimizationReport {
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+impl OptimizeSpec {
+    /// Parameter sets to evaluate
+    pub fn candidates(&self) -> anyhow::Result<Vec<BTreeMap<String, Value>>> {
+        match &self.method {
+            SearchMethod::Grid => {
+                let mut candidates = vec![BTreeMap::new()];
+                for (name, range) in &self.space {
+                    let values = range.grid(name)?;
+                    if candidates.len() * values.len() > MAX_GRID_CANDIDATES {
+                        anyhow::bail!("grid exceeds {} candidates - use random search", MAX_GRID_CANDIDATES);
+                    }
+                    candidates = candidates
+                        .into_iter()
+                        .flat_map(|candidate| {
+                            values.iter().map(move |value| {
+                                let mut candidate = candidate.clone();
+                                candidate.insert(name.clone(), value.clone());
+                                candidate
+                            })
+                        })
+                        .collect();
+                }
+                Ok(candidates)
+            }
+            SearchMethod::Random { samples, seed } => {
+                let mut rng = match seed {
+                    Some(seed) => StdRng::seed_from_u64(*seed),
+                    None => StdRng::from_entropy(),
+                };
+                Ok((0..*samples)
+                    .map(|_| {
+                        self.space
+                            .iter()
+                            .map(|(name, range)| (name.clone(), range.sample(&mut rng)))
+                            .collect()
+                    })
+                    .collect())
+            }
+        }
+    }
+
+    /// Consecutive windows over the events, each split into in-sample then out-of-sample
+    pub fn folds(&self, events: &[MarketEvent]) -> Vec<Fold> {
+        let time = |index: usize| events.get(index).map_or(0, |e| e.received_at_us / 1000);
+        let span = |range: &std::ops::Range<usize>| (time(range.start), time(range.end.saturating_sub(1)));
+
+        if self.folds == 0 {
+            let all = 0..events.len();
+            return vec![Fold {
+                index: 0,
+                in_sample_ms: span(&all),
+                out_of_sample_ms: (0, 0),
+                in_sample: all,
+                out_of_sample: 0..0,
+            }];
+        }
+
+        let window = events.len() / self.folds;
+        let fraction = self.in_sample_fraction.clamp(0.0, 1.0);
+        (0..self.folds)
+            .map(|index| {
+                let start = index * window;
+                let end = if index + 1 == self.folds { events.len() } else { start + window };
+                let split = start + ((end - start) as f64 * fraction) as usize;
+                let (in_sample, out_of_sample) = (start..split, split..end);
+                Fold {
+                    index,
+                    in_sample_ms: span(&in_sample),
+                    out_of_sample_ms: span(&out_of_sample),
+                    in_sample,
+                    out_of_sample,
+                }
+            })
+            .collect()
+    }
+
+    fn config_for(&self, params: &BTreeMap<String, Value>) -> StrategyConfig {
+        let mut config = self.strategy.clone();
+        if !config.params.is_object() {
+            config.params = Value::Object(Default::default());
+        }
+        for (name, value) in params {
+            set_path(&mut config.params, name, value.clone());
+        }
+        config
+    }
+}
+
+/// Set `value` at a dotted `path`, creating objects on the way
+fn set_path(target: &mut Value, path: &str, value: Value) {
+    let mut current = target;
+    let mut parts = path.split('.').peekable();
+    while let Some(part) = parts.next() {
+        if !current.is_object() {
+            *current = Value::Object(Default::default());
+        }
+        let object = current.as_object_mut().expect("made an object above");
+        if parts.peek().is_none() {
+            object.insert(part.to_string(), value);
+            return;
+        }
+        current = object.entry(part.to_string()).or_insert(Value::Null);
+    }
+}
+
+/// Backtest one parameter set on a slice of events; `None` if the strategy rejects the params
+fn evaluate(
+    runtime: &tokio::runtime::Runtime,
+    config: &BacktestConfig,
+    strategy: &StrategyConfig,
+    events: &[MarketEvent],
+) -> Option<BacktestMetrics> {
+    let backtester = match Backtester::new(config.clone(), std::slice::from_ref(strategy)) {
+        Ok(backtester) => backtester,
+        Err(e) => {
+            warn!("🎛️ Skipping params {}: {}", strategy.params, e);
+            return None;
+        }
+    };
+
+    Some(runtime.block_on(backtester.run(events)).metrics)
+}
+
+/// In-sample and out-of-sample metrics of one candidate, one entry per fold
+type FoldMetrics = (Vec<BacktestMetrics>, Vec<Option<BacktestMetrics>>);
+
+/// Mean of the defined scores
+fn mean(scores: impl IntoIterator<Item = Option<f64>>) -> Option<f64> {
+    let scores: Vec<f64> = scores.into_iter().flatten().collect();
+    (!scores.is_empty()).then(|| scores.iter().sum::<f64>() / scores.len() as f64)
+}
+
+/// Order for picking the best; undefined scores rank last
+fn rank_key(score: Option<f64>) -> f64 {
+    score.unwrap_or(f64::NEG_INFINITY)
+}
+
+/// Score every candidate on every fold in parallel and rank them by in-sample objective
+pub fn optimize(spec: &OptimizeSpec, config: &BacktestConfig, events: &[MarketEvent]) -> anyhow::Result<OptimizationReport> {
+    let candidates = spec.candidates()?;
+    let total = candidates.len();
+    let folds = spec.folds(events);
+    info!("🎛️ Optimizing {} over {} candidates x {} folds by {:?}",
+          spec.strategy.id, total, folds.len(), spec.objective);
+
+    // Backtests spawn nothing, so every worker thread can block on the same runtime
+    let runtime = tokio::runtime::Builder::new_multi_thread().worker_threads(1).enable_all().build()?;
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(spec.threads).build()?;
+    let results: Vec<Option<FoldMetrics>> = pool.install(|| {
+        candidates
+            .par_iter()
+            .map(|params| {
+                let strategy = spec.config_for(params);
+                let mut in_sample = Vec::with_capacity(folds.len());
+                let mut out_of_sample = Vec::with_capacity(folds.len());
+                for fold in &folds {
+                    in_sample.push(evaluate(&runtime, config, &strategy, &events[fold.in_sample.clone()])?);
+                    out_of_sample.push(if fold.out_of_sample.is_empty() {
+                        None
+                    } else {
+                        Some(evaluate(&runtime, config, &strategy, &events[fold.out_of_sample.clone()])?)
+                    });
+                }
+                Some((in_sample, out_of_sample))
+            })
+            .collect()
+    });
+
+    let objective = spec.objective;
+    let invalid = results.iter().filter(|r| r.is_none()).count();
+    let evaluated: Vec<_> = candidates
+        .into_iter()
+        .zip(results)
+        .filter_map(|(params, result)| result.map(|(is, oos)| (params, is, oos)))
+        .collect();
+
+    // Walk-forward: choose on each window's in-sample part only, then judge on what follows
+    let walk_forward: Vec<FoldSelection> = if spec.folds == 0 {
+        Vec::new()
+    } else {
+        folds
+            .iter()
+            .filter_map(|fold| {
+                let (params, is, oos) = evaluated
+                    .iter()
+                    .max_by(|a, b| {
+                        rank_key(objective.score(&a.1[fold.index])).total_cmp(&rank_key(objective.score(&b.1[fold.index])))
+                    })?;
+                Some(FoldSelection {
+                    fold: fold.clone(),
+                    params: params.clone(),
+                    in_sample_score: objective.score(&is[fold.index]),
+                    out_of_sample_score: oos[fold.index].as_ref().and_then(|m| objective.score(m)),
+                })
+            })
+            .collect()
+    };
+    let walk_forward_score = mean(walk_forward.iter().map(|s| s.out_of_sample_score));
+
+    let mut ranked: Vec<CandidateResult> = evaluated
+        .into_iter()
+        .map(|(params, in_sample_metrics, out_of_sample_metrics)| {
+            let in_sample_score = mean(in_sample_metrics.iter().map(|m| objective.score(m)));
+            let out_of_sample_score = mean(out_of_sample_metrics.iter().flatten().map(|m| objective.score(m)));
+            CandidateResult {
+                rank: 0,
+                params,
+                in_sample_score,
+                out_of_sample_score,
+                efficiency: in_sample_score
+                    .filter(|is| *is > 0.0)
+                    .zip(out_of_sample_score)
+                    .map(|(is, oos)| oos / is),
+                in_sample_metrics,
+                out_of_sample_metrics,
+            }
+        })
+        .collect();
+    ranked.sort_by(|a, b| rank_key(b.in_sample_score).total_cmp(&rank_key(a.in_sample_score)));
+    ranked.truncate(spec.top.max(1));
+    for (index, candidate) in ranked.iter_mut().enumerate() {
+        candidate.rank = index + 1;
+    }
+
+    Ok(OptimizationReport {
+        strategy_id: spec.strategy.id.clone(),
+        kind: spec.strategy.kind.clone(),
+        objective,
+        method: spec.method.clone(),
+        candidates: total,
+        invalid,
+        walk_forward,
+        walk_forward_score,
+        ranked,
+    })
+}
+
+/// `sniper-core optimize SPEC [EVENTS] [--out PATH]`
+pub fn run_cli(args: &[String]) -> anyhow::Result<()> {
+    let mut config = BacktestConfig::from_env();
+    let mut spec_path = None;
+    let mut output = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out" => output = Some(args.next().cloned().ok_or_else(|| anyhow::anyhow!("--out needs a value"))?),
+            flag if flag.starts_with("--") => anyhow::bail!("unknown optimize option {}", flag),
+            path if spec_path.is_none() => spec_path = Some(path.to_string()),
+            path => config.events_path = path.into(),
+        }
+    }
+
+    let spec_path = spec_path.ok_or_else(|| anyhow::anyhow!("usage: sniper-core optimize SPEC [EVENTS] [--out PATH]"))?;
+    let spec: OptimizeSpec = serde_json::from_str(&std::fs::read_to_string(&spec_path)?)?;
+    let output = output.map(Into::into).unwrap_or_else(|| config.output_dir.join("optimization.json"));
+
+    let events = super::load_events(&config.events_path)?;
+    info!("🎛️ Loaded {} events from {}", events.len(), config.events_path.display());
+
+    let report = optimize(&spec, &config, &events)?;
+    report.write(&output)?;
+
+    if let Some(best) = report.ranked.first() {
+        info!("🎛️ Best of {} candidates: {:?} in-sample {:?}, out-of-sample {:?}",
+              report.candidates, best.params, best.in_sample_score, best.out_of_sample_score);
+    }
+    info!("🎛️ Walk-forward score {:?} - report written to {}", report.walk_forward_score, output.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::path::Path;
+
+    const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/range_ticks.ndjson");
+
+    fn spec(folds: usize, in_sample_fraction: f64) -> OptimizeSpec {
+        serde_json::from_value(json!({
+            "strategy": {
+                "id": "mr",
+                "kind": "mean_reversion",
+                "capital_sol": 2.0,
+                "params": {"rsi_period": 3, "bollinger_period": 5, "sr_lookback": 10, "size_sol": 0.5},
+            },
+            "space": {"bollinger_k": {"values": [0.5, 1.0, 1.5]}},
+            "method": "grid",
+            "objective": "total_return",
+            "folds": folds,
+            "in_sample_fraction": in_sample_fraction,
+            "threads": 2,
+            "top": 1,
+        }))
+        .unwrap()
+    }
+
+    fn config() -> BacktestConfig {
+        BacktestConfig {
+            events_path: FIXTURE.into(),
+            output_dir: std::env::temp_dir(),
+            initial_capital_sol: 10.0,
+            equity_interval_ms: 60_000,
+            default_decimals: 6,
+            fills: Default::default(),
+        }
+    }
+
+    #[test]
+    fn counts_candidates_before_keeping_the_top() {
+        let events = super::super::load_events(Path::new(FIXTURE)).unwrap();
+        let report = optimize(&spec(0, 0.7), &config(), &events).unwrap();
+
+        assert_eq!(report.candidates, 3);
+        assert_eq!(report.invalid, 0);
+        assert_eq!(report.ranked.len(), 1);
+        assert_eq!(report.ranked[0].rank, 1);
+        assert!(report.walk_forward.is_empty());
+    }
+
+    #[test]
+    fn out_of_sample_results_stay_aligned_with_folds() {
+        let events = super::super::load_events(Path::new(FIXTURE)).unwrap();
+
+        // Every window is all in-sample: no out-of-sample runs, but still one slot per fold
+        let report = optimize(&spec(2, 1.0), &config(), &events).unwrap();
+        let best = &report.ranked[0];
+        assert_eq!(best.in_sample_metrics.len(), 2);
+        assert_eq!(best.out_of_sample_metrics.len(), 2);
+        assert!(best.out_of_sample_metrics.iter().all(Option::is_none));
+        assert_eq!(best.out_of_sample_score, None);
+        assert_eq!(report.walk_forward.len(), 2);
+        assert!(report.walk_forward.iter().all(|s| s.out_of_sample_score.is_none()));
+
+        let report = optimize(&spec(2, 0.5), &config(), &events).unwrap();
+        let best = &report.ranked[0];
+        assert_eq!(best.out_of_sample_metrics.len(), 2);
+        assert!(best.out_of_sample_metrics.iter().all(Option::is_some));
+        for selection in &report.walk_forward {
+            assert!(selection.out_of_sample_score.is_some());
+            assert!(selection.fold.out_of_sample_ms.0 > selection.fold.in_sample_ms.1);
+        }
+    }
+}
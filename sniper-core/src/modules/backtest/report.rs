@@ -0,0 +1,160 @@
+// 📋 Backtest Report
+// Equity curve, closed trades and performance metrics, written as JSON and CSV
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::path::Path;
+
+use super::fills::FillModel;
+
+const MS_PER_YEAR: f64 = 365.0 * 86_400_000.0;
+
+/// Part or all of a position closed by one sell
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Trade {
+    pub strategy_id: String,
+    pub mint: String,
+    pub opened_ms: i64,
+    pub closed_ms: i64,
+    pub entry_price: f64,
+    pub exit_price: f64,
+    pub tokens: f64,
+    /// Share of the entry cost, fees included
+    pub cost_sol: f64,
+    pub proceeds_sol: f64,
+    pub pnl_sol: f64,
+    pub return_pct: f64,
+    pub exit_reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EquityPoint {
+    pub timestamp_ms: i64,
+    pub equity_sol: f64,
+    pub cash_sol: f64,
+    pub exposure_sol: f64,
+    /// Fall from the running peak, as a fraction
+    pub drawdown: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BacktestMetrics {
+    pub initial_equity_sol: f64,
+    pub final_equity_sol: f64,
+    pub total_return: f64,
+    /// Annualized from equity curve returns at the sampling interval
+    pub sharpe: Option<f64>,
+    pub sortino: Option<f64>,
+    pub max_drawdown: f64,
+    pub trades: usize,
+    pub winning_trades: usize,
+    pub win_rate: Option<f64>,
+    /// Gross profit over gross loss; `None` without losing trades
+    pub profit_factor: Option<f64>,
+    pub signals: u64,
+    pub rejected_signals: u64,
+}
+
+impl BacktestMetrics {
+    pub fn compute(initial_equity: f64, curve: &[EquityPoint], trades: &[Trade], interval_ms: i64) -> Self {
+        let final_equity = curve.last().map_or(initial_equity, |p| p.equity_sol);
+        let returns: Vec<f64> = curve
+            .windows(2)
+            .filter(|w| w[0].equity_sol > 0.0)
+            .map(|w| w[1].equity_sol / w[0].equity_sol - 1.0)
+            .collect();
+        let periods_per_year = MS_PER_YEAR / interval_ms.max(1) as f64;
+
+        let winning_trades = trades.iter().filter(|t| t.pnl_sol > 0.0).count();
+        let gross_profit: f64 = trades.iter().map(|t| t.pnl_sol.max(0.0)).sum();
+        let gross_loss: f64 = trades.iter().map(|t| (-t.pnl_sol).max(0.0)).sum();
+
+        Self {
+            initial_equity_sol: initial_equity,
+            final_equity_sol: final_equity,
+            total_return: if initial_equity > 0.0 { final_equity / initial_equity - 1.0 } else { 0.0 },
+            sharpe: sharpe(&returns, periods_per_year),
+            sortino: sortino(&returns, periods_per_year),
+            max_drawdown: curve.iter().map(|p| p.drawdown).fold(0.0, f64::max),
+            trades: trades.len(),
+            winning_trades,
+            win_rate: (!trades.is_empty()).then(|| winning_trades as f64 / trades.len() as f64),
+            profit_factor: (gross_loss > 0.0).then(|| gross_profit / gross_loss),
+            signals: 0,
+            rejected_signals: 0,
+        }
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn sharpe(returns: &[f64], periods_per_year: f64) -> Option<f64> {
+    if returns.len() < 2 {
+        return None;
+    }
+    let mean = mean(returns);
+    let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+    let std = variance.sqrt();
+    (std > 0.0).then(|| mean / std * periods_per_year.sqrt())
+}
+
+/// Like Sharpe, but only returns below zero count as risk
+fn sortino(returns: &[f64], periods_per_year: f64) -> Option<f64> {
+    if returns.len() < 2 {
+        return None;
+    }
+    let downside = (returns.iter().map(|r| r.min(0.0).powi(2)).sum::<f64>() / returns.len() as f64).sqrt();
+    (downside > 0.0).then(|| mean(returns) / downside * periods_per_year.sqrt())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestReport {
+    pub strategies: Vec<String>,
+    pub events: u64,
+    pub started_ms: i64,
+    pub ended_ms: i64,
+    pub fill_model: FillModel,
+    pub metrics: BacktestMetrics,
+    pub equity_curve: Vec<EquityPoint>,
+    pub trades: Vec<Trade>,
+}
+
+impl BacktestReport {
+    /// Write `report.json`, `trades.csv` and `equity.csv` into `dir`
+    pub fn write(&self, dir: &Path) -> anyhow::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(dir.join("report.json"), serde_json::to_string_pretty(self)?)?;
+
+        let mut trades = String::from(
+            "strategy_id,mint,opened_ms,closed_ms,entry_price,exit_price,tokens,cost_sol,proceeds_sol,pnl_sol,return_pct,exit_reason\n",
+        );
+        for t in &self.trades {
+            writeln!(
+                trades,
+                "{},{},{},{},{},{},{},{},{},{},{},{}",
+                csv_field(&t.strategy_id), csv_field(&t.mint), t.opened_ms, t.closed_ms,
+                t.entry_price, t.exit_price, t.tokens, t.cost_sol, t.proceeds_sol,
+                t.pnl_sol, t.return_pct, csv_field(&t.exit_reason)
+            )?;
+        }
+        std::fs::write(dir.join("trades.csv"), trades)?;
+
+        let mut equity = String::from("timestamp_ms,equity_sol,cash_sol,exposure_sol,drawdown\n");
+        for p in &self.equity_curve {
+            writeln!(equity, "{},{},{},{},{}", p.timestamp_ms, p.equity_sol, p.cash_sol, p.exposure_sol, p.drawdown)?;
+        }
+        std::fs::write(dir.join("equity.csv"), equity)?;
+
+        Ok(())
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -1,19 +1,29 @@
 // 💾 Cache Manager Module
 // DragonflyDB integration for ultra-fast data access
 
-use tokio::sync::mpsc;
 use redis::{AsyncCommands, Client};
 use tracing::{info, error, debug};
 use serde_json::Value;
 use std::time::Duration;
 
+use super::data_ingestor::{Candle, TokenProfile};
+
+/// Token profiles are refreshed periodically since authorities can be revoked
+pub const TOKEN_PROFILE_TTL_SECONDS: u64 = 600;
+
+/// Recent candles kept per mint and interval; older ones live in PostgreSQL
+const CANDLES_PER_SERIES: isize = 1000;
+
+#[derive(Clone)]
 pub struct CacheManager {
     // TODO: Add channels when ready
     // cache_rx: Option<mpsc::Receiver<CacheMessage>>,
+    #[allow(dead_code)]
     redis_client: Client,
     connection_pool: redis::aio::ConnectionManager,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub enum CacheOperation {
     Get,
@@ -32,12 +42,17 @@ impl CacheManager {
         let redis_url = std::env::var("DRAGONFLY_URL")
             .unwrap_or_else(|_| "redis://dragonfly:6379".to_string());
 
-        let client = Client::open(redis_url.clone())?;
+        Self::connect(&redis_url).await
+    }
+
+    /// Connect to the DragonflyDB (or Redis) server at `redis_url`
+    pub async fn connect(redis_url: &str) -> anyhow::Result<Self> {
+        let client = Client::open(redis_url)?;
         let connection_pool = redis::aio::ConnectionManager::new(client.clone()).await?;
 
         // Test connection
         let mut conn = connection_pool.clone();
-        let _: String = conn.ping().await?;
+        let _: String = redis::cmd("PING").query_async(&mut conn).await?;
 
         info!("✅ CacheManager connected to DragonflyDB: {}", redis_url);
 
@@ -47,6 +62,7 @@ impl CacheManager {
         })
     }
 
+    #[allow(dead_code)]
     pub async fn run(&mut self) -> anyhow::Result<()> {
         info!("🚀 Starting CacheManager...");
 
@@ -62,6 +78,7 @@ impl CacheManager {
     }
 
     /// Store market data with TTL
+    #[allow(dead_code)]
     pub async fn store_market_data(&mut self, symbol: &str, data: &Value, ttl_seconds: u64) -> anyhow::Result<()> {
         let key = format!("market_data:{}", symbol);
         let mut conn = self.connection_pool.clone();
@@ -74,6 +91,7 @@ impl CacheManager {
     }
 
     /// Get cached market data
+    #[allow(dead_code)]
     pub async fn get_market_data(&mut self, symbol: &str) -> anyhow::Result<Option<Value>> {
         let key = format!("market_data:{}", symbol);
         let mut conn = self.connection_pool.clone();
@@ -90,6 +108,7 @@ impl CacheManager {
     }
 
     /// Store trading signal
+    #[allow(dead_code)]
     pub async fn store_signal(&mut self, signal_id: &str, signal_data: &Value) -> anyhow::Result<()> {
         let key = format!("signal:{}", signal_id);
         let mut conn = self.connection_pool.clone();
@@ -103,6 +122,7 @@ impl CacheManager {
     }
 
     /// Store execution result
+    #[allow(dead_code)]
     pub async fn store_execution_result(&mut self, execution_id: &str, result: &Value) -> anyhow::Result<()> {
         let key = format!("execution:{}", execution_id);
         let mut conn = self.connection_pool.clone();
@@ -116,6 +136,7 @@ impl CacheManager {
     }
 
     /// Increment counter (for metrics)
+    #[allow(dead_code)]
     pub async fn increment_counter(&mut self, counter_name: &str) -> anyhow::Result<i64> {
         let key = format!("counter:{}", counter_name);
         let mut conn = self.connection_pool.clone();
@@ -127,6 +148,7 @@ impl CacheManager {
     }
 
     /// Publish real-time signal
+    #[allow(dead_code)]
     pub async fn publish_signal(&mut self, channel: &str, signal: &Value) -> anyhow::Result<()> {
         let mut conn = self.connection_pool.clone();
         let signal_str = serde_json::to_string(signal)?;
@@ -138,6 +160,7 @@ impl CacheManager {
     }
 
     /// Get system metrics from cache
+    #[allow(dead_code)]
     pub async fn get_metrics(&mut self) -> anyhow::Result<Value> {
         let mut conn = self.connection_pool.clone();
         
@@ -166,8 +189,9 @@ impl CacheManager {
         let key = format!("price_history:{}", symbol);
         let mut conn = self.connection_pool.clone();
         
-        // Use Redis sorted set to store price history with timestamp as score
-        let _: () = conn.zadd(&key, price, timestamp).await?;
+        // Sorted set scored by timestamp; the member carries the timestamp so
+        // repeated prices are kept as separate samples
+        let _: () = conn.zadd(&key, format!("{}:{}", timestamp, price), timestamp).await?;
         
         // Keep only last 1000 entries
         let _: () = conn.zremrangebyrank(&key, 0, -1001).await?;
@@ -182,17 +206,102 @@ impl CacheManager {
         let key = format!("price_history:{}", symbol);
         let mut conn = self.connection_pool.clone();
         
-        // Get recent prices with timestamps
-        let prices: Vec<(f64, i64)> = conn.zrevrange_withscores(&key, 0, limit - 1).await?;
+        // Get recent prices with timestamps, newest first
+        let members: Vec<String> = conn.zrevrange(&key, 0, limit - 1).await?;
+        let prices = members
+            .iter()
+            .filter_map(|member| {
+                let (timestamp, price) = member.split_once(':')?;
+                Some((price.parse().ok()?, timestamp.parse().ok()?))
+            })
+            .collect();
         
         Ok(prices)
     }
 
+    /// Store a closed candle; re-storing the same bucket replaces it
+    pub async fn store_candle(&mut self, candle: &Candle) -> anyhow::Result<()> {
+        let key = format!("candles:{}:{}", candle.interval_ms, candle.mint);
+        let mut conn = self.connection_pool.clone();
+        let data_str = serde_json::to_string(candle)?;
+
+        let _: () = conn.zrembyscore(&key, candle.start_ms, candle.start_ms).await?;
+        let _: () = conn.zadd(&key, data_str, candle.start_ms).await?;
+        let _: () = conn.zremrangebyrank(&key, 0, -(CANDLES_PER_SERIES + 1)).await?;
+
+        Ok(())
+    }
+
+    /// Get up to `limit` most recent candles starting before `until_ms`, oldest first
+    pub async fn get_candles(&mut self, mint: &str, interval_ms: i64, until_ms: i64, limit: isize) -> anyhow::Result<Vec<Candle>> {
+        let key = format!("candles:{}:{}", interval_ms, mint);
+        let mut conn = self.connection_pool.clone();
+
+        let members: Vec<String> = conn.zrevrangebyscore_limit(&key, until_ms - 1, "-inf", 0, limit).await?;
+        let mut candles: Vec<Candle> = members
+            .iter()
+            .filter_map(|member| serde_json::from_str(member).ok())
+            .collect();
+        candles.reverse();
+
+        Ok(candles)
+    }
+
+    /// Store token safety profile
+    pub async fn store_token_profile(&mut self, profile: &TokenProfile) -> anyhow::Result<()> {
+        let key = format!("token_profile:{}", profile.mint);
+        let mut conn = self.connection_pool.clone();
+        let data_str = serde_json::to_string(profile)?;
+
+        let _: () = conn.set_ex(&key, data_str, TOKEN_PROFILE_TTL_SECONDS).await?;
+        debug!("🪪 Stored token profile for {}", profile.mint);
+
+        Ok(())
+    }
+
+    /// Get cached token safety profile
+    pub async fn get_token_profile(&mut self, mint: &str) -> anyhow::Result<Option<TokenProfile>> {
+        let key = format!("token_profile:{}", mint);
+        let mut conn = self.connection_pool.clone();
+
+        let data: Option<String> = conn.get(&key).await?;
+
+        match data {
+            Some(data_str) => Ok(Some(serde_json::from_str(&data_str)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Store risk manager state that must survive restarts (no TTL)
+    pub async fn store_risk_state(&mut self, name: &str, state: &Value) -> anyhow::Result<()> {
+        let key = format!("risk_state:{}", name);
+        let mut conn = self.connection_pool.clone();
+
+        let _: () = conn.set(&key, state.to_string()).await?;
+        debug!("⚠️ Stored risk state {}", name);
+
+        Ok(())
+    }
+
+    /// Get persisted risk manager state
+    pub async fn get_risk_state(&mut self, name: &str) -> anyhow::Result<Option<Value>> {
+        let key = format!("risk_state:{}", name);
+        let mut conn = self.connection_pool.clone();
+
+        let data: Option<String> = conn.get(&key).await?;
+
+        match data {
+            Some(data_str) => Ok(Some(serde_json::from_str(&data_str)?)),
+            None => Ok(None),
+        }
+    }
+
     /// Health check for cache connection
+    #[allow(dead_code)]
     pub async fn health_check(&mut self) -> anyhow::Result<bool> {
         let mut conn = self.connection_pool.clone();
         
-        match tokio::time::timeout(Duration::from_secs(1), conn.ping()).await {
+        match tokio::time::timeout(Duration::from_secs(1), redis::cmd("PING").query_async::<String>(&mut conn)).await {
             Ok(Ok(_)) => Ok(true),
             Ok(Err(e)) => {
                 error!("❌ Cache health check failed: {}", e);
@@ -205,3 +314,69 @@ impl CacheManager {
         }
     }
 }
+
+/// In-process stand-in for DragonflyDB, shared by module tests
+#[cfg(test)]
+pub mod fake {
+    use super::CacheManager;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::tcp::OwnedReadHalf;
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio::sync::Mutex;
+
+    type Store = Arc<Mutex<HashMap<String, String>>>;
+
+    /// Cache that keeps `SET` strings for `GET`; every other write succeeds and is dropped
+    pub async fn cache() -> CacheManager {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("redis://{}", listener.local_addr().unwrap());
+        let store = Store::default();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, store.clone()));
+            }
+        });
+        CacheManager::connect(&url).await.unwrap()
+    }
+
+    async fn serve(stream: TcpStream, store: Store) {
+        let (read, mut write) = stream.into_split();
+        let mut reader = BufReader::new(read);
+        while let Some(command) = read_command(&mut reader).await {
+            let reply = match command.first().map(|c| c.to_ascii_uppercase()).as_deref() {
+                Some("PING") => "+PONG\r\n".to_string(),
+                Some("GET") => match store.lock().await.get(&command[1]) {
+                    Some(value) => format!("${}\r\n{}\r\n", value.len(), value),
+                    None => "$-1\r\n".to_string(),
+                },
+                Some("SET") => {
+                    store.lock().await.insert(command[1].clone(), command[2].clone());
+                    "+OK\r\n".to_string()
+                }
+                _ => "+OK\r\n".to_string(),
+            };
+            if write.write_all(reply.as_bytes()).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    async fn read_command(reader: &mut BufReader<OwnedReadHalf>) -> Option<Vec<String>> {
+        let mut line = String::new();
+        reader.read_line(&mut line).await.ok()?;
+        let count: usize = line.trim_end().strip_prefix('*')?.parse().ok()?;
+
+        let mut args = Vec::with_capacity(count);
+        for _ in 0..count {
+            line.clear();
+            reader.read_line(&mut line).await.ok()?;
+            let len: usize = line.trim_end().strip_prefix('$')?.parse().ok()?;
+            let mut arg = vec![0; len + 2];
+            reader.read_exact(&mut arg).await.ok()?;
+            args.push(String::from_utf8_lossy(&arg[..len]).into_owned());
+        }
+        Some(args)
+    }
+}
@@ -1,105 +1,420 @@
 // 📡 Data Ingestor Module
 // Real-time market data ingestion from multiple sources
 
-use tokio::sync::mpsc;
+pub mod candle_api;
+pub mod candle_store;
+pub mod candles;
+pub mod events;
+pub mod geyser_proto;
+pub mod geyser_source;
+pub mod jupiter_source;
+pub mod launches;
+pub mod merge;
+pub mod pools;
+pub mod replay;
+pub mod source;
+pub mod swaps;
+pub mod token_profile;
+pub mod wallet_tracker;
+pub mod websocket_source;
+
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Semaphore};
 use tracing::{info, error, debug, warn};
-use serde_json::Value;
-use std::time::Duration;
+
+use super::cache_manager::{CacheManager, TOKEN_PROFILE_TTL_SECONDS};
+use super::strategy_engine::{IndicatorConfig, IndicatorEngine, SharedIndicators};
+
+pub use candle_store::{CandleSink, CandleStore};
+pub use candles::{Candle, CandleBuilder};
+pub use events::{
+    LaunchEvent, LaunchKind, MarketDataMessage, MarketEvent, PriceTick, SwapEvent, TransactionUpdate,
+};
+pub use merge::{EventMerger, MergeConfig, SourceStats};
+pub use replay::{RecordedEvent, RecordingSource, ReplaySource};
+pub use source::{DataSourceConfig, MarketDataSource, SourceKind};
+pub use pools::RaydiumAmmPool;
+pub use token_profile::{TokenProfile, TokenProfiler};
+pub use wallet_tracker::{TradeSide, WalletActivity, WalletTracker, WalletTrackerConfig};
+
+/// Per-source statistics shared between the ingestor and the HTTP API
+pub type SharedSourceStats = Arc<RwLock<HashMap<String, SourceStats>>>;
+
+/// Wallet activity, swaps and launches decoded from a transaction, in the order the ingestor emits them
+pub fn derive_events(wallet_tracker: &WalletTracker, transaction: &TransactionUpdate) -> Vec<MarketDataMessage> {
+    let activities = wallet_tracker.parse_transaction(transaction).into_iter().map(MarketDataMessage::WalletActivity);
+    let swaps = swaps::decode_swaps(transaction).into_iter().map(MarketDataMessage::Swap);
+    let launches = launches::decode_launches(transaction).into_iter().map(MarketDataMessage::Launch);
+    activities.chain(swaps).chain(launches).collect()
+}
 
 pub struct DataIngestor {
-    // TODO: Add channels when ready
-    // market_data_tx: mpsc::Sender<MarketDataMessage>,
+    market_data_tx: mpsc::Sender<MarketEvent>,
+    source_config: DataSourceConfig,
+    merge_config: MergeConfig,
+    wallet_tracker: WalletTracker,
+    source_stats: SharedSourceStats,
+    cache_manager: CacheManager,
+    token_profiler: Arc<TokenProfiler>,
+    /// When each mint was last enriched; entries expire with the cached profile
+    known_mints: Arc<DashMap<String, Instant>>,
+    enrichment_permits: Arc<Semaphore>,
+    candles: CandleSink,
 }
 
+/// Open candles are checked against the clock this often
+const CANDLE_CLOSE_INTERVAL: Duration = Duration::from_secs(1);
+/// Wall-clock closing waits this long past the bucket end for late-arriving trades
+const CANDLE_CLOSE_GRACE_MS: i64 = 2_000;
+
+/// Concurrent RPC enrichment requests for newly seen mints
+const MAX_CONCURRENT_ENRICHMENTS: usize = 8;
+
 impl DataIngestor {
-    pub async fn new() -> anyhow::Result<Self> {
+    pub async fn new(
+        market_data_tx: mpsc::Sender<MarketEvent>,
+        cache_manager: CacheManager,
+    ) -> anyhow::Result<Self> {
         info!("📡 DataIngestor initializing...");
 
-        // TODO: Initialize WebSocket connections
-        // - Helius WebSocket for real-time data
-        // - Jupiter API for price feeds
-        // - Solana RPC for transaction monitoring
+        let wallet_tracker = WalletTracker::new(WalletTrackerConfig::from_env());
+        info!("👛 Tracking {} smart-money wallets", wallet_tracker.wallets().len());
+
+        let mut source_config = DataSourceConfig::from_env()?;
+        source_config.filter.accounts.extend(wallet_tracker.wallets().iter().cloned());
+        info!("🔌 Market data sources: {:?}", source_config.kinds);
+
+        let rpc_url = std::env::var("QUICKNODE_RPC_URL")
+            .unwrap_or_else(|_| "https://api.devnet.solana.com".to_string());
+
+        let candle_store = CandleStore::connect(cache_manager.clone()).await;
 
         info!("✅ DataIngestor initialized");
 
         Ok(Self {
-            // market_data_tx,
+            market_data_tx,
+            source_config,
+            merge_config: MergeConfig::from_env(),
+            wallet_tracker,
+            source_stats: Arc::new(RwLock::new(HashMap::new())),
+            cache_manager,
+            token_profiler: Arc::new(TokenProfiler::new(rpc_url)),
+            known_mints: Arc::new(DashMap::new()),
+            enrichment_permits: Arc::new(Semaphore::new(MAX_CONCURRENT_ENRICHMENTS)),
+            candles: CandleSink::new(candle_store, IndicatorEngine::shared(IndicatorConfig::default())),
         })
     }
 
+    /// Feed closed candles into `indicators`, e.g. the set served by the candle API
+    pub fn with_indicators(mut self, indicators: SharedIndicators) -> Self {
+        self.candles = CandleSink::new(self.candles.store().clone(), indicators);
+        self
+    }
+
+    /// Handle to the open candles, to flush them on shutdown
+    pub fn candle_sink(&self) -> CandleSink {
+        self.candles.clone()
+    }
+
+    /// Shared handle to per-source lead/lag statistics, refreshed while running
+    pub fn source_stats(&self) -> SharedSourceStats {
+        self.source_stats.clone()
+    }
+
+    /// Build the configured sources, each wrapped in a recorder when enabled
+    fn build_sources(&self) -> Vec<Box<dyn MarketDataSource>> {
+        let config = &self.source_config;
+        let live_sources = config.kinds.len();
+
+        config
+            .kinds
+            .iter()
+            .map(|kind| {
+                let source: Box<dyn MarketDataSource> = match kind {
+                    SourceKind::WebSocket => Box::new(websocket_source::WebSocketSource::new(
+                        config.ws_url.clone(),
+                        config.filter.clone(),
+                    )),
+                    SourceKind::Geyser => Box::new(geyser_source::GeyserSource::new(
+                        config.geyser_url.clone(),
+                        config.geyser_x_token.clone(),
+                        config.filter.clone(),
+                    )),
+                    SourceKind::Jupiter => Box::new(jupiter_source::JupiterPriceSource::new(
+                        config.jupiter_api_url.clone(),
+                        config.jupiter_mints.clone(),
+                        config.jupiter_vs_token.clone(),
+                        config.jupiter_interval,
+                    )),
+                    SourceKind::Replay => {
+                        return Box::new(ReplaySource::new(
+                            config.replay_path.clone(),
+                            config.replay_speed,
+                        )) as Box<dyn MarketDataSource>;
+                    }
+                };
+
+                match &config.record_path {
+                    // One file per source so concurrent writers never interleave lines
+                    Some(path) if live_sources > 1 => {
+                        let path = format!("{}.{}", path, source.name());
+                        Box::new(RecordingSource::new(source, path)) as Box<dyn MarketDataSource>
+                    }
+                    Some(path) => Box::new(RecordingSource::new(source, path.clone())),
+                    None => source,
+                }
+            })
+            .collect()
+    }
+
     pub async fn run(&mut self) -> anyhow::Result<()> {
         info!("🚀 Starting DataIngestor...");
 
-        // TODO: Start data ingestion tasks
-        // - WebSocket connection to Helius
-        // - Periodic price updates from Jupiter
-        // - Real-time transaction monitoring
+        // Every source feeds one merged channel, tagged with its name
+        let (merged_tx, mut merged_rx) = mpsc::channel::<(String, MarketDataMessage)>(8192);
+        let mut handles = Vec::new();
+        for mut source in self.build_sources() {
+            let name = source.name().to_string();
+            let (source_tx, mut source_rx) = mpsc::channel(4096);
+            let merged_tx = merged_tx.clone();
+
+            handles.push(tokio::spawn(async move { source.run(source_tx).await }));
+            handles.push(tokio::spawn(async move {
+                while let Some(event) = source_rx.recv().await {
+                    if merged_tx.send((name.clone(), event)).await.is_err() {
+                        break;
+                    }
+                }
+                warn!("🔌 Source {} stopped", name);
+                Ok(())
+            }));
+        }
+        drop(merged_tx);
+
+        let mut merger = EventMerger::new(self.merge_config.clone());
+        let mut stats_interval = tokio::time::interval(Duration::from_secs(10));
+        let mut candle_interval = tokio::time::interval(CANDLE_CLOSE_INTERVAL);
 
-        // Placeholder implementation
         loop {
-            tokio::time::sleep(Duration::from_secs(30)).await;
-            debug!("📊 DataIngestor heartbeat - ready for implementation");
+            let deadline = merger
+                .next_deadline()
+                .map(tokio::time::Instant::from_std)
+                .unwrap_or_else(|| tokio::time::Instant::now() + Duration::from_secs(3600));
+
+            let released = tokio::select! {
+                received = merged_rx.recv() => match received {
+                    Some((source, event)) => merger.push(&source, event, std::time::Instant::now()),
+                    None => break,
+                },
+                _ = tokio::time::sleep_until(deadline) => merger.flush(std::time::Instant::now()),
+                _ = stats_interval.tick() => {
+                    self.report_source_stats(&merger);
+                    self.expire_known_mints();
+                    continue;
+                }
+                _ = candle_interval.tick() => {
+                    self.candles.close_due(chrono::Utc::now().timestamp_millis() - CANDLE_CLOSE_GRACE_MS);
+                    continue;
+                }
+            };
+
+            for event in released {
+                if let Err(e) = self.process_market_data(event).await {
+                    error!("❌ Market data processing failed: {}", e);
+                }
+            }
+
+            if self.market_data_tx.is_closed() {
+                break;
+            }
+        }
+
+        for event in merger.drain() {
+            self.process_market_data(event).await.ok();
         }
+        self.report_source_stats(&merger);
+        self.candles.flush().await;
+
+        for handle in handles {
+            handle.abort();
+        }
+
+        info!("📡 DataIngestor stopped");
+        Ok(())
     }
 
-    /// Connect to Helius WebSocket for real-time data
-    async fn connect_helius_websocket(&self) -> anyhow::Result<()> {
-        let ws_url = std::env::var("HELIUS_WS_URL")
-            .unwrap_or_else(|_| "wss://atlas-mainnet.helius-rpc.com".to_string());
+    /// Publish and log per-source lead/lag statistics
+    fn report_source_stats(&self, merger: &EventMerger) {
+        for (source, stats) in merger.stats() {
+            debug!("🔀 {}: {} events, lead {:.1}%, avg lag {:.2}ms, max lag {:.2}ms, late {}",
+                   source, stats.events, stats.lead_ratio() * 100.0,
+                   stats.avg_lag_ms, stats.max_lag_ms, stats.late);
+        }
+        *self.source_stats.write() = merger.stats().clone();
+    }
 
-        info!("🔗 Connecting to Helius WebSocket: {}", ws_url);
+    /// Monitor Solana transactions for trading signals
+    async fn monitor_solana_transactions(&self, origin: &MarketEvent, transaction: &TransactionUpdate) -> anyhow::Result<()> {
+        // Smart money tracking: tracked wallet buys/sells from token balance deltas
+        for activity in self.wallet_tracker.parse_transaction(transaction) {
+            info!("👛 {} {:?} {:.4} {} ({:.4} SOL)",
+                  activity.wallet, activity.side, activity.token_amount,
+                  activity.mint, activity.sol_amount);
 
-        // TODO: Implement WebSocket connection
-        // Subscribe to relevant data streams
-        // - Token price updates
-        // - Large transactions
-        // - DEX activity
+            self.market_data_tx
+                .send(MarketEvent {
+                    source: origin.source.clone(),
+                    received_at_us: origin.received_at_us,
+                    latency_us: origin.latency_us,
+                    event: MarketDataMessage::WalletActivity(activity),
+                })
+                .await?;
+        }
 
         Ok(())
     }
 
-    /// Fetch price data from Jupiter API
-    async fn fetch_jupiter_prices(&self) -> anyhow::Result<()> {
-        let jupiter_url = std::env::var("JUPITER_API_URL")
-            .unwrap_or_else(|_| "https://quote-api.jup.ag/v6".to_string());
+    /// Emit every token/SOL swap in the transaction and add it to the candles and price history
+    async fn process_swaps(&self, origin: &MarketEvent, transaction: &TransactionUpdate) -> anyhow::Result<()> {
+        let timestamp_ms = transaction
+            .block_time
+            .map(|t| t * 1000)
+            .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
 
-        debug!("💰 Fetching prices from Jupiter: {}", jupiter_url);
+        for swap in swaps::decode_swaps(transaction) {
+            let price = swap.price();
+            self.candles.update(&swap.mint, price, swap.token_amount, timestamp_ms);
+            // Marks open positions for exits, so they do not depend on a Jupiter price feed
+            if price > 0.0 && price.is_finite() {
+                let mut cache = self.cache_manager.clone();
+                if let Err(e) = cache.store_price_history(&swap.mint, price, timestamp_ms).await {
+                    warn!("⚠️ Failed to store swap price for {}: {}", swap.mint, e);
+                }
+            }
 
-        // TODO: Implement Jupiter API integration
-        // - Get token prices
-        // - Monitor liquidity
-        // - Track arbitrage opportunities
+            self.market_data_tx
+                .send(MarketEvent {
+                    source: origin.source.clone(),
+                    received_at_us: origin.received_at_us,
+                    latency_us: origin.latency_us,
+                    event: MarketDataMessage::Swap(swap),
+                })
+                .await?;
+        }
 
         Ok(())
     }
 
-    /// Monitor Solana transactions for trading signals
-    async fn monitor_solana_transactions(&self) -> anyhow::Result<()> {
-        let rpc_url = std::env::var("QUICKNODE_RPC_URL")
-            .unwrap_or_else(|_| "https://api.devnet.solana.com".to_string());
-
-        debug!("⛓️ Monitoring Solana transactions: {}", rpc_url);
+    /// Emit new pools and pump.fun tokens created by the transaction
+    async fn process_launches(&self, origin: &MarketEvent, transaction: &TransactionUpdate) -> anyhow::Result<()> {
+        for launch in launches::decode_launches(transaction) {
+            info!("🆕 {:?} launch of {} by {} with {:.4} quote liquidity",
+                  launch.kind, launch.mint, launch.creator, launch.quote_liquidity);
 
-        // TODO: Implement transaction monitoring
-        // - Large wallet movements
-        // - DEX transactions
-        // - Smart money tracking
+            self.market_data_tx
+                .send(MarketEvent {
+                    source: origin.source.clone(),
+                    received_at_us: origin.received_at_us,
+                    latency_us: origin.latency_us,
+                    event: MarketDataMessage::Launch(launch),
+                })
+                .await?;
+        }
 
         Ok(())
     }
 
+    /// Forget mints whose cached profile has expired so the map stays bounded
+    fn expire_known_mints(&self) {
+        let ttl = Duration::from_secs(TOKEN_PROFILE_TTL_SECONDS);
+        self.known_mints.retain(|_, seen| seen.elapsed() < ttl);
+    }
+
+    /// Fetch and cache a token profile for every mint not enriched within the profile TTL
+    fn enrich_new_mints<'a>(&self, mints: impl Iterator<Item = &'a str>) {
+        let ttl = Duration::from_secs(TOKEN_PROFILE_TTL_SECONDS);
+        for mint in mints {
+            if mint == wallet_tracker::WSOL_MINT {
+                continue;
+            }
+            match self.known_mints.entry(mint.to_string()) {
+                Entry::Occupied(seen) if seen.get().elapsed() < ttl => continue,
+                Entry::Occupied(mut seen) => {
+                    seen.insert(Instant::now());
+                }
+                Entry::Vacant(seen) => {
+                    seen.insert(Instant::now());
+                }
+            }
+
+            let mint = mint.to_string();
+            let profiler = self.token_profiler.clone();
+            let permits = self.enrichment_permits.clone();
+            let known_mints = self.known_mints.clone();
+            let mut cache = self.cache_manager.clone();
+
+            tokio::spawn(async move {
+                let Ok(_permit) = permits.acquire_owned().await else { return };
+
+                // Profile may survive a restart in the cache
+                if let Ok(Some(_)) = cache.get_token_profile(&mint).await {
+                    return;
+                }
+
+                match profiler.fetch(&mint).await {
+                    Ok(profile) => {
+                        info!("🪪 {} ({}) supply {} mint_auth {:?} freeze_auth {:?} top10 {:.1}%",
+                              mint,
+                              profile.metadata.as_ref().map(|m| m.symbol.as_str()).unwrap_or("?"),
+                              profile.supply, profile.mint_authority, profile.freeze_authority,
+                              profile.holders.as_ref().map_or(f64::NAN, |h| h.top10_share * 100.0));
+                        if let Err(e) = cache.store_token_profile(&profile).await {
+                            warn!("⚠️ Failed to cache token profile for {}: {}", mint, e);
+                        }
+                    }
+                    Err(e) => {
+                        warn!("⚠️ Token enrichment failed for {}: {}", mint, e);
+                        // Allow a retry the next time the mint shows up
+                        known_mints.remove(&mint);
+                    }
+                }
+            });
+        }
+    }
+
     /// Process incoming market data and send to strategy engine
-    async fn process_market_data(&self, data: Value) -> anyhow::Result<()> {
-        debug!("📊 Processing market data: {:?}", data);
+    async fn process_market_data(&self, data: MarketEvent) -> anyhow::Result<()> {
+        match &data.event {
+            MarketDataMessage::Transaction(transaction) => {
+                debug!("📊 Processing transaction {} @ {} via {}", transaction.signature, transaction.slot, data.source);
+                self.enrich_new_mints(transaction.post_token_balances.iter().map(|b| b.mint.as_str()));
+                self.monitor_solana_transactions(&data, transaction).await?;
+                self.process_swaps(&data, transaction).await?;
+                self.process_launches(&data, transaction).await?;
+            }
+            MarketDataMessage::PriceTick(tick) => {
+                self.enrich_new_mints(std::iter::once(tick.mint.as_str()));
+                self.candles.update(&tick.mint, tick.price, 0.0, tick.timestamp_ms);
+                let mut cache = self.cache_manager.clone();
+                if let Err(e) = cache.store_price_history(&tick.mint, tick.price, tick.timestamp_ms).await {
+                    warn!("⚠️ Failed to store price for {}: {}", tick.mint, e);
+                }
+            }
+            _ => {}
+        }
 
-        // TODO: Parse and validate market data
-        // TODO: Send to strategy engine via channels
-        // TODO: Store in cache for quick access
+        self.market_data_tx.send(data).await?;
 
         Ok(())
     }
 
     /// Health check for data connections
+    #[allow(dead_code)]
     pub async fn health_check(&self) -> anyhow::Result<bool> {
         // TODO: Check WebSocket connections
         // TODO: Verify API endpoints
@@ -0,0 +1,86 @@
+// 🌐 Candle API
+// HTTP endpoints serving OHLCV history and indicators per mint
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+    routing::get,
+    Router,
+};
+use serde::Deserialize;
+use tracing::error;
+
+use super::candle_store::CandleStore;
+use super::candles::{Candle, CandleInterval};
+use crate::modules::strategy_engine::{IndicatorSnapshot, SharedIndicators};
+
+/// Candles returned when the request sets no limit, and the most it may ask for
+const DEFAULT_LIMIT: usize = 300;
+const MAX_LIMIT: usize = 1000;
+
+#[derive(Debug, Deserialize)]
+pub struct CandleQuery {
+    /// One of 1s, 15s, 1m, 5m, 1h
+    pub interval: Option<String>,
+    pub limit: Option<usize>,
+    /// Only candles starting before this Unix millisecond timestamp
+    pub until: Option<i64>,
+}
+
+#[derive(Clone)]
+struct CandleApi {
+    store: CandleStore,
+    indicators: SharedIndicators,
+}
+
+/// Routes to be nested under `/api/v1/candles`
+pub fn routes(store: CandleStore, indicators: SharedIndicators) -> Router {
+    Router::new()
+        .route("/:mint", get(get_candles))
+        .route("/:mint/indicators", get(get_indicators))
+        .with_state(CandleApi { store, indicators })
+}
+
+async fn get_candles(
+    State(api): State<CandleApi>,
+    Path(mint): Path<String>,
+    Query(query): Query<CandleQuery>,
+) -> Result<ResponseJson<Vec<Candle>>, StatusCode> {
+    load(&api.store, &mint, &query).await.map(ResponseJson)
+}
+
+/// Live indicator values as of the mint's last closed candle at the interval
+async fn get_indicators(
+    State(api): State<CandleApi>,
+    Path(mint): Path<String>,
+    Query(query): Query<CandleQuery>,
+) -> Result<ResponseJson<IndicatorSnapshot>, StatusCode> {
+    let interval = interval(&query)?;
+    api.indicators
+        .read()
+        .snapshot(&mint, interval.ms())
+        .map(ResponseJson)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+fn interval(query: &CandleQuery) -> Result<CandleInterval, StatusCode> {
+    match query.interval.as_deref() {
+        Some(label) => CandleInterval::parse(label).ok_or(StatusCode::BAD_REQUEST),
+        None => Ok(CandleInterval::M1),
+    }
+}
+
+async fn load(store: &CandleStore, mint: &str, query: &CandleQuery) -> Result<Vec<Candle>, StatusCode> {
+    let interval = interval(query)?;
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let until = query.until.unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+
+    store
+        .get(mint, interval.ms(), until, limit)
+        .await
+        .map_err(|e| {
+            error!("❌ Candle query for {} failed: {}", mint, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
@@ -0,0 +1,222 @@
+// 🗄️ Candle Store
+// Closed candles in DragonflyDB for recent reads and PostgreSQL for history
+
+use parking_lot::Mutex;
+use sqlx::postgres::{PgPool, PgPoolOptions};
+use sqlx::Row;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use super::candles::{fill_gaps, Candle, CandleAggregator};
+use crate::modules::cache_manager::CacheManager;
+use crate::modules::strategy_engine::SharedIndicators;
+
+const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS candles (
+    mint TEXT NOT NULL,
+    interval_ms BIGINT NOT NULL,
+    start_ms BIGINT NOT NULL,
+    open DOUBLE PRECISION NOT NULL,
+    high DOUBLE PRECISION NOT NULL,
+    low DOUBLE PRECISION NOT NULL,
+    close DOUBLE PRECISION NOT NULL,
+    volume DOUBLE PRECISION NOT NULL,
+    trades BIGINT NOT NULL,
+    PRIMARY KEY (mint, interval_ms, start_ms)
+)";
+
+#[derive(Clone)]
+pub struct CandleStore {
+    cache: CacheManager,
+    /// `None` when no database is configured; only the cached window is served
+    pg: Option<PgPool>,
+}
+
+impl CandleStore {
+    /// Connect to `SNIPER_DATABASE_URL` if set, falling back to cache-only storage
+    pub async fn connect(cache: CacheManager) -> Self {
+        let pg = match std::env::var("SNIPER_DATABASE_URL").ok().filter(|url| !url.is_empty()) {
+            Some(url) => match Self::connect_pg(&url).await {
+                Ok(pool) => {
+                    info!("🗄️ Candle history stored in PostgreSQL");
+                    Some(pool)
+                }
+                Err(e) => {
+                    warn!("⚠️ PostgreSQL unavailable, candles kept in cache only: {}", e);
+                    None
+                }
+            },
+            None => {
+                warn!("⚠️ SNIPER_DATABASE_URL not set, candles kept in cache only");
+                None
+            }
+        };
+
+        Self { cache, pg }
+    }
+
+    async fn connect_pg(url: &str) -> anyhow::Result<PgPool> {
+        let pool = PgPoolOptions::new().max_connections(4).connect(url).await?;
+        sqlx::query(SCHEMA).execute(&pool).await?;
+        Ok(pool)
+    }
+
+    /// Persist closed candles; a bucket stored twice keeps the latest values
+    pub async fn store(&self, candles: &[Candle]) -> anyhow::Result<()> {
+        let mut cache = self.cache.clone();
+        for candle in candles {
+            cache.store_candle(candle).await?;
+        }
+
+        if let Some(pg) = &self.pg {
+            for candle in candles {
+                sqlx::query(
+                    "INSERT INTO candles (mint, interval_ms, start_ms, open, high, low, close, volume, trades)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                     ON CONFLICT (mint, interval_ms, start_ms) DO UPDATE SET
+                        open = EXCLUDED.open, high = EXCLUDED.high, low = EXCLUDED.low,
+                        close = EXCLUDED.close, volume = EXCLUDED.volume, trades = EXCLUDED.trades",
+                )
+                .bind(&candle.mint)
+                .bind(candle.interval_ms)
+                .bind(candle.start_ms)
+                .bind(candle.open)
+                .bind(candle.high)
+                .bind(candle.low)
+                .bind(candle.close)
+                .bind(candle.volume)
+                .bind(candle.trades as i64)
+                .execute(pg)
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Up to `limit` candles starting before `until_ms`, oldest first, with gaps filled
+    ///
+    /// Served from the cache when it holds enough history, otherwise from PostgreSQL.
+    pub async fn get(&self, mint: &str, interval_ms: i64, until_ms: i64, limit: usize) -> anyhow::Result<Vec<Candle>> {
+        let mut cache = self.cache.clone();
+        let mut candles = cache.get_candles(mint, interval_ms, until_ms, limit as isize).await?;
+
+        if candles.len() < limit {
+            if let Some(pg) = &self.pg {
+                candles = Self::load(pg, mint, interval_ms, until_ms, limit).await?;
+            }
+        }
+
+        let Some(last_start) = candles.last().map(|c| c.start_ms) else {
+            return Ok(candles);
+        };
+        let mut filled = fill_gaps(candles, last_start + interval_ms);
+        if filled.len() > limit {
+            filled.drain(..filled.len() - limit);
+        }
+        Ok(filled)
+    }
+
+    async fn load(pg: &PgPool, mint: &str, interval_ms: i64, until_ms: i64, limit: usize) -> anyhow::Result<Vec<Candle>> {
+        let rows = sqlx::query(
+            "SELECT start_ms, open, high, low, close, volume, trades FROM candles
+             WHERE mint = $1 AND interval_ms = $2 AND start_ms < $3
+             ORDER BY start_ms DESC LIMIT $4",
+        )
+        .bind(mint)
+        .bind(interval_ms)
+        .bind(until_ms)
+        .bind(limit as i64)
+        .fetch_all(pg)
+        .await?;
+
+        let mut candles = rows
+            .iter()
+            .map(|row| {
+                Ok(Candle {
+                    mint: mint.to_string(),
+                    start_ms: row.try_get("start_ms")?,
+                    interval_ms,
+                    open: row.try_get("open")?,
+                    high: row.try_get("high")?,
+                    low: row.try_get("low")?,
+                    close: row.try_get("close")?,
+                    volume: row.try_get("volume")?,
+                    trades: row.try_get::<i64, _>("trades")?.max(0) as u64,
+                })
+            })
+            .collect::<anyhow::Result<Vec<Candle>>>()?;
+        candles.reverse();
+
+        Ok(candles)
+    }
+}
+
+/// Open candles plus where closed ones go: the indicator sets and the store
+///
+/// Cloned handles share the open candles, so they can be flushed from outside the ingestor.
+#[derive(Clone)]
+pub struct CandleSink {
+    candles: Arc<Mutex<CandleAggregator>>,
+    store: CandleStore,
+    indicators: SharedIndicators,
+}
+
+impl CandleSink {
+    pub fn new(store: CandleStore, indicators: SharedIndicators) -> Self {
+        Self {
+            candles: Arc::new(Mutex::new(CandleAggregator::default())),
+            store,
+            indicators,
+        }
+    }
+
+    /// Fold a trade (or a quote with zero volume) into the candles
+    pub fn update(&self, mint: &str, price: f64, volume: f64, timestamp_ms: i64) {
+        let closed = self.candles.lock().update(mint, price, volume, timestamp_ms);
+        self.publish(closed);
+    }
+
+    /// Close candles whose bucket ended by `now_ms`, so quiet mints still get their last bar
+    pub fn close_due(&self, now_ms: i64) {
+        let closed = self.candles.lock().close_due(now_ms);
+        self.publish(closed);
+    }
+
+    /// Close and store every open candle, waiting for the writes
+    pub async fn flush(&self) {
+        let closed = self.candles.lock().flush();
+        if closed.is_empty() {
+            return;
+        }
+        self.update_indicators(&closed);
+        match self.store.store(&closed).await {
+            Ok(()) => info!("🕯️ Flushed {} open candles", closed.len()),
+            Err(e) => warn!("⚠️ Failed to flush {} candles: {}", closed.len(), e),
+        }
+    }
+
+    pub fn store(&self) -> &CandleStore {
+        &self.store
+    }
+
+    fn publish(&self, closed: Vec<Candle>) {
+        if closed.is_empty() {
+            return;
+        }
+        self.update_indicators(&closed);
+
+        let store = self.store.clone();
+        tokio::spawn(async move {
+            if let Err(e) = store.store(&closed).await {
+                warn!("⚠️ Failed to store {} candles: {}", closed.len(), e);
+            }
+        });
+    }
+
+    fn update_indicators(&self, closed: &[Candle]) {
+        let mut indicators = self.indicators.write();
+        for candle in closed {
+            indicators.update(candle);
+        }
+    }
+}
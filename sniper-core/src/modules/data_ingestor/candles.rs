@@ -0,0 +1,382 @@
+// 🕯️ OHLCV Candles
+// Time-bucketed bars aggregated from trades and price ticks
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Flat candles emitted for one gap at most; longer silences are left as holes
+pub const MAX_GAP_FILL: i64 = 300;
+
+/// Bar widths built for every mint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CandleInterval {
+    #[serde(rename = "1s")]
+    S1,
+    #[serde(rename = "15s")]
+    S15,
+    #[serde(rename = "1m")]
+    M1,
+    #[serde(rename = "5m")]
+    M5,
+    #[serde(rename = "1h")]
+    H1,
+}
+
+impl CandleInterval {
+    pub const ALL: [Self; 5] = [Self::S1, Self::S15, Self::M1, Self::M5, Self::H1];
+
+    pub fn ms(self) -> i64 {
+        match self {
+            Self::S1 => 1_000,
+            Self::S15 => 15_000,
+            Self::M1 => 60_000,
+            Self::M5 => 300_000,
+            Self::H1 => 3_600_000,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::S1 => "1s",
+            Self::S15 => "15s",
+            Self::M1 => "1m",
+            Self::M5 => "5m",
+            Self::H1 => "1h",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|interval| interval.label() == value)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Candle {
+    pub mint: String,
+    /// Bucket start, Unix milliseconds
+    pub start_ms: i64,
+    pub interval_ms: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    /// Traded token volume; 0 for bars built from quotes only
+    pub volume: f64,
+    pub trades: u64,
+}
+
+impl Candle {
+    /// No-trade bar carrying the previous close forward
+    pub fn flat(previous: &Candle, start_ms: i64) -> Self {
+        Self {
+            mint: previous.mint.clone(),
+            start_ms,
+            interval_ms: previous.interval_ms,
+            open: previous.close,
+            high: previous.close,
+            low: previous.close,
+            close: previous.close,
+            volume: 0.0,
+            trades: 0,
+        }
+    }
+
+    fn new(mint: &str, start_ms: i64, interval_ms: i64, price: f64, volume: f64) -> Self {
+        Self {
+            mint: mint.to_string(),
+            start_ms,
+            interval_ms,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume,
+            trades: u64::from(volume > 0.0),
+        }
+    }
+
+    fn apply(&mut self, price: f64, volume: f64) {
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.close = price;
+        self.volume += volume;
+        if volume > 0.0 {
+            self.trades += 1;
+        }
+    }
+
+    /// (high + low + close) / 3
+    pub fn typical_price(&self) -> f64 {
+        (self.high + self.low + self.close) / 3.0
+    }
+}
+
+/// Open candle per mint at one interval
+#[derive(Debug, Clone)]
+pub struct CandleBuilder {
+    interval_ms: i64,
+    open: HashMap<String, Candle>,
+    /// Candles closed by the clock, kept to fill the gap when the mint trades again
+    last_closed: HashMap<String, Candle>,
+}
+
+impl CandleBuilder {
+    pub fn new(interval_ms: i64) -> Self {
+        Self {
+            interval_ms: interval_ms.max(1),
+            open: HashMap::new(),
+            last_closed: HashMap::new(),
+        }
+    }
+
+    /// Add a trade (or a quote with zero volume); returns the candles it closed,
+    /// including flat ones filling any gap, oldest first
+    ///
+    /// Updates older than the open candle are ignored.
+    pub fn update(&mut self, mint: &str, price: f64, volume: f64, timestamp_ms: i64) -> Vec<Candle> {
+        if price <= 0.0 || !price.is_finite() {
+            return Vec::new();
+        }
+        let start_ms = timestamp_ms - timestamp_ms.rem_euclid(self.interval_ms);
+
+        match self.open.get_mut(mint) {
+            Some(candle) if candle.start_ms == start_ms => {
+                candle.apply(price, volume);
+                Vec::new()
+            }
+            Some(candle) if candle.start_ms < start_ms => {
+                let next = Candle::new(mint, start_ms, self.interval_ms, price, volume);
+                let closed = std::mem::replace(candle, next);
+                fill_gaps(vec![closed], start_ms)
+            }
+            Some(_) => Vec::new(),
+            None => {
+                // The previous candle was already emitted; only the flats after it are new
+                let flats = match self.last_closed.remove(mint) {
+                    Some(previous) if previous.start_ms >= start_ms => {
+                        self.last_closed.insert(mint.to_string(), previous);
+                        return Vec::new();
+                    }
+                    Some(previous) => fill_gaps(vec![previous], start_ms).split_off(1),
+                    None => Vec::new(),
+                };
+                self.open
+                    .insert(mint.to_string(), Candle::new(mint, start_ms, self.interval_ms, price, volume));
+                flats
+            }
+        }
+    }
+
+    /// Close every open candle whose bucket ended by `now_ms`, for mints that went quiet
+    pub fn close_due(&mut self, now_ms: i64) -> Vec<Candle> {
+        let due: Vec<String> = self
+            .open
+            .iter()
+            .filter(|(_, candle)| candle.start_ms + self.interval_ms <= now_ms)
+            .map(|(mint, _)| mint.clone())
+            .collect();
+
+        due.into_iter()
+            .filter_map(|mint| {
+                let candle = self.open.remove(&mint)?;
+                self.last_closed.insert(mint, candle.clone());
+                Some(candle)
+            })
+            .collect()
+    }
+
+    /// Close every open candle, e.g. on shutdown
+    pub fn flush(&mut self) -> Vec<Candle> {
+        self.close_due(i64::MAX)
+    }
+}
+
+/// Builders for every `CandleInterval`
+#[derive(Debug, Clone)]
+pub struct CandleAggregator {
+    builders: Vec<CandleBuilder>,
+}
+
+impl Default for CandleAggregator {
+    fn default() -> Self {
+        Self {
+            builders: CandleInterval::ALL
+                .into_iter()
+                .map(|interval| CandleBuilder::new(interval.ms()))
+                .collect(),
+        }
+    }
+}
+
+impl CandleAggregator {
+    /// Feed every interval; returns all candles closed by this update
+    pub fn update(&mut self, mint: &str, price: f64, volume: f64, timestamp_ms: i64) -> Vec<Candle> {
+        self.builders
+            .iter_mut()
+            .flat_map(|builder| builder.update(mint, price, volume, timestamp_ms))
+            .collect()
+    }
+
+    /// Close candles of every interval whose bucket ended by `now_ms`
+    pub fn close_due(&mut self, now_ms: i64) -> Vec<Candle> {
+        self.builders.iter_mut().flat_map(|builder| builder.close_due(now_ms)).collect()
+    }
+
+    pub fn flush(&mut self) -> Vec<Candle> {
+        self.builders.iter_mut().flat_map(CandleBuilder::flush).collect()
+    }
+}
+
+/// Insert flat candles between consecutive `candles` (oldest first) and up to `until_ms`
+pub fn fill_gaps(candles: Vec<Candle>, until_ms: i64) -> Vec<Candle> {
+    let mut filled: Vec<Candle> = Vec::with_capacity(candles.len());
+    let mut candles = candles.into_iter().peekable();
+
+    while let Some(candle) = candles.next() {
+        let next_start = candles.peek().map(|next| next.start_ms).unwrap_or(until_ms);
+        let interval_ms = candle.interval_ms.max(1);
+        let missing = ((next_start - candle.start_ms) / interval_ms - 1).clamp(0, MAX_GAP_FILL);
+        // Only fill the tail of over-long gaps so the series ends adjacent to the next bar
+        let first_fill = next_start - missing * interval_ms;
+
+        let flats: Vec<Candle> = (0..missing).map(|i| Candle::flat(&candle, first_fill + i * interval_ms)).collect();
+        filled.push(candle);
+        filled.extend(flats);
+    }
+
+    filled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINT: &str = "MintA";
+    const MINUTE: i64 = 60_000;
+
+    #[test]
+    fn updates_within_a_bucket_build_one_candle() {
+        let mut builder = CandleBuilder::new(MINUTE);
+        assert!(builder.update(MINT, 1.0, 10.0, 5_000).is_empty());
+        assert!(builder.update(MINT, 1.5, 0.0, 20_000).is_empty());
+        assert!(builder.update(MINT, 0.8, 5.0, 59_999).is_empty());
+
+        let closed = builder.update(MINT, 1.2, 1.0, MINUTE);
+        let [candle] = &closed[..] else { panic!("{:?}", closed) };
+        assert_eq!(candle.start_ms, 0);
+        assert_eq!((candle.open, candle.high, candle.low, candle.close), (1.0, 1.5, 0.8, 0.8));
+        assert_eq!(candle.volume, 15.0);
+        // The zero-volume quote moved the price but is not a trade
+        assert_eq!(candle.trades, 2);
+    }
+
+    #[test]
+    fn stale_and_invalid_updates_are_ignored() {
+        let mut builder = CandleBuilder::new(MINUTE);
+        builder.update(MINT, 1.0, 1.0, 2 * MINUTE);
+        assert!(builder.update(MINT, 9.0, 1.0, MINUTE).is_empty());
+        assert!(builder.update(MINT, 0.0, 1.0, 2 * MINUTE).is_empty());
+        assert!(builder.update(MINT, f64::NAN, 1.0, 2 * MINUTE).is_empty());
+
+        let closed = builder.update(MINT, 2.0, 1.0, 3 * MINUTE);
+        assert_eq!(closed[0].high, 1.0);
+        assert_eq!(closed[0].trades, 1);
+    }
+
+    #[test]
+    fn skipped_buckets_are_filled_with_flat_candles() {
+        let mut builder = CandleBuilder::new(MINUTE);
+        builder.update(MINT, 2.0, 1.0, 0);
+
+        let closed = builder.update(MINT, 3.0, 1.0, 4 * MINUTE);
+        let starts: Vec<i64> = closed.iter().map(|c| c.start_ms).collect();
+        assert_eq!(starts, vec![0, MINUTE, 2 * MINUTE, 3 * MINUTE]);
+        assert!(closed[1..].iter().all(|c| c.open == 2.0 && c.close == 2.0 && c.volume == 0.0 && c.trades == 0));
+    }
+
+    #[test]
+    fn quiet_mints_are_closed_by_the_clock() {
+        let mut builder = CandleBuilder::new(MINUTE);
+        builder.update(MINT, 1.0, 1.0, 10_000);
+        builder.update("MintB", 5.0, 1.0, 2 * MINUTE);
+
+        assert!(builder.close_due(MINUTE - 1).is_empty());
+        let closed = builder.close_due(MINUTE);
+        assert_eq!(closed.len(), 1);
+        assert_eq!((closed[0].mint.as_str(), closed[0].start_ms), (MINT, 0));
+        // Closed once only
+        assert!(builder.close_due(2 * MINUTE).is_empty());
+    }
+
+    #[test]
+    fn trading_after_a_clock_close_fills_only_the_gap() {
+        let mut builder = CandleBuilder::new(MINUTE);
+        builder.update(MINT, 1.0, 1.0, 0);
+        builder.close_due(MINUTE);
+
+        // A late trade for the closed bucket cannot reopen it
+        assert!(builder.update(MINT, 7.0, 1.0, 30_000).is_empty());
+
+        let flats = builder.update(MINT, 2.0, 1.0, 3 * MINUTE);
+        let starts: Vec<i64> = flats.iter().map(|c| c.start_ms).collect();
+        assert_eq!(starts, vec![MINUTE, 2 * MINUTE]);
+        assert!(flats.iter().all(|c| c.close == 1.0));
+
+        let closed = builder.update(MINT, 2.5, 1.0, 4 * MINUTE);
+        assert_eq!((closed[0].start_ms, closed[0].open), (3 * MINUTE, 2.0));
+    }
+
+    #[test]
+    fn flush_closes_every_open_candle() {
+        let mut aggregator = CandleAggregator::default();
+        aggregator.update(MINT, 1.0, 1.0, 1_500);
+
+        let closed = aggregator.flush();
+        let mut intervals: Vec<i64> = closed.iter().map(|c| c.interval_ms).collect();
+        intervals.sort();
+        assert_eq!(intervals, CandleInterval::ALL.map(CandleInterval::ms).to_vec());
+        assert!(aggregator.flush().is_empty());
+    }
+
+    #[test]
+    fn aggregator_closes_each_interval_on_its_own_clock() {
+        let mut aggregator = CandleAggregator::default();
+        aggregator.update(MINT, 1.0, 1.0, 0);
+
+        let closed = aggregator.close_due(20_000);
+        let intervals: Vec<i64> = closed.iter().map(|c| c.interval_ms).collect();
+        assert_eq!(intervals, vec![1_000, 15_000]);
+    }
+
+    #[test]
+    fn fill_gaps_pads_between_candles_and_up_to_the_end() {
+        let first = Candle::new(MINT, 0, MINUTE, 1.0, 1.0);
+        let second = Candle::new(MINT, 2 * MINUTE, MINUTE, 2.0, 1.0);
+
+        let filled = fill_gaps(vec![first, second], 4 * MINUTE);
+        let bars: Vec<(i64, f64)> = filled.iter().map(|c| (c.start_ms, c.close)).collect();
+        assert_eq!(bars, vec![(0, 1.0), (MINUTE, 1.0), (2 * MINUTE, 2.0), (3 * MINUTE, 2.0)]);
+    }
+
+    #[test]
+    fn fill_gaps_caps_long_silences_at_the_tail() {
+        let first = Candle::new(MINT, 0, MINUTE, 1.0, 1.0);
+        let until = 1_000 * MINUTE;
+
+        let filled = fill_gaps(vec![first], until);
+        assert_eq!(filled.len(), 1 + MAX_GAP_FILL as usize);
+        let flats = &filled[1..];
+        // The flats end right before the next bar, leaving the hole after the original candle
+        assert_eq!(flats[0].start_ms, until - MAX_GAP_FILL * MINUTE);
+        assert_eq!(flats.last().unwrap().start_ms, until - MINUTE);
+        assert!(flats.windows(2).all(|w| w[1].start_ms - w[0].start_ms == MINUTE));
+    }
+
+    #[test]
+    fn intervals_round_trip_through_their_labels() {
+        for interval in CandleInterval::ALL {
+            assert_eq!(CandleInterval::parse(interval.label()), Some(interval));
+        }
+        assert_eq!(CandleInterval::parse("2m"), None);
+    }
+}
@@ -0,0 +1,320 @@
+// 📨 Market Data Events
+// Source-agnostic typed events produced by every ingestion source
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::wallet_tracker::{TradeSide, WalletActivity};
+
+/// Typed events sent from the ingestor to the strategy engine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MarketDataMessage {
+    AccountUpdate(AccountUpdate),
+    Transaction(TransactionUpdate),
+    PriceTick(PriceTick),
+    WalletActivity(WalletActivity),
+    Swap(SwapEvent),
+    Launch(LaunchEvent),
+}
+
+impl MarketDataMessage {
+    /// Slot the event was observed at, if the source reports one
+    pub fn slot(&self) -> Option<u64> {
+        match self {
+            Self::AccountUpdate(update) => Some(update.slot),
+            Self::Transaction(transaction) => Some(transaction.slot),
+            Self::WalletActivity(activity) => Some(activity.slot),
+            Self::Swap(swap) => Some(swap.slot),
+            Self::Launch(launch) => Some(launch.slot),
+            Self::PriceTick(_) => None,
+        }
+    }
+
+    /// Event's own wall-clock timestamp in microseconds, if the source reports one
+    pub fn timestamp_us(&self) -> Option<i64> {
+        match self {
+            Self::Transaction(transaction) => transaction.block_time.map(|t| t * 1_000_000),
+            Self::WalletActivity(activity) => activity.block_time.map(|t| t * 1_000_000),
+            Self::Swap(swap) => swap.block_time.map(|t| t * 1_000_000),
+            Self::Launch(launch) => launch.block_time.map(|t| t * 1_000_000),
+            Self::PriceTick(tick) => Some(tick.timestamp_ms * 1_000),
+            Self::AccountUpdate(_) => None,
+        }
+    }
+}
+
+/// Deduplicated event tagged with the source that delivered it first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketEvent {
+    pub source: String,
+    /// Unix time in microseconds when the event reached the ingestor
+    pub received_at_us: i64,
+    /// Arrival delay relative to the event's own timestamp (block time or quote time), if known
+    pub latency_us: Option<i64>,
+    pub event: MarketDataMessage,
+}
+
+/// Account write observed for a subscribed program
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountUpdate {
+    pub pubkey: String,
+    pub owner: String,
+    pub lamports: u64,
+    #[serde(with = "base64_bytes")]
+    pub data: Vec<u8>,
+    pub slot: u64,
+    /// Geyser write version; 0 when the source does not provide it
+    pub write_version: u64,
+}
+
+/// Confirmed transaction with balances needed for decoding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionUpdate {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub success: bool,
+    /// Network fee in lamports, paid by the first account key
+    #[serde(default)]
+    pub fee: u64,
+    /// Static keys followed by loaded writable and readonly lookup-table keys
+    pub account_keys: Vec<String>,
+    pub instructions: Vec<InstructionUpdate>,
+    pub pre_balances: Vec<u64>,
+    pub post_balances: Vec<u64>,
+    pub pre_token_balances: Vec<TokenBalance>,
+    pub post_token_balances: Vec<TokenBalance>,
+    pub log_messages: Vec<String>,
+}
+
+/// Top-level instruction with resolved account keys
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstructionUpdate {
+    pub program_id: String,
+    pub accounts: Vec<String>,
+    #[serde(with = "base64_bytes")]
+    pub data: Vec<u8>,
+}
+
+/// Off-chain price quote (e.g. Jupiter)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceTick {
+    pub mint: String,
+    /// Price in the configured quote token (SOL by default)
+    pub price: f64,
+    pub timestamp_ms: i64,
+}
+
+/// Token/SOL swap by a transaction's fee payer, decoded from balance deltas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwapEvent {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub trader: String,
+    pub mint: String,
+    pub side: TradeSide,
+    /// UI token amount
+    pub token_amount: f64,
+    pub sol_amount: f64,
+}
+
+impl SwapEvent {
+    /// Execution price in SOL per token
+    pub fn price(&self) -> f64 {
+        if self.token_amount > 0.0 {
+            self.sol_amount / self.token_amount
+        } else {
+            0.0
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchKind {
+    /// Raydium AMM v4 `initialize2`
+    RaydiumPool,
+    /// pump.fun bonding curve `create`
+    PumpFun,
+}
+
+/// New token market: a fresh Raydium pool or a pump.fun launch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchEvent {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub kind: LaunchKind,
+    /// The launched token
+    pub mint: String,
+    pub quote_mint: String,
+    /// AMM or bonding curve account
+    pub pool: String,
+    pub creator: String,
+    /// Quote tokens deposited at launch, UI units
+    pub quote_liquidity: f64,
+    /// Opening price in quote per token, if it could be derived
+    pub price: Option<f64>,
+    /// Metadata carried by the create instruction (pump.fun only)
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub uri: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenBalance {
+    pub account_index: u32,
+    pub mint: String,
+    pub owner: String,
+    pub amount: u64,
+    pub decimals: u8,
+}
+
+impl AccountUpdate {
+    /// Parse a `programNotification` value (`{ pubkey, account }`) with base64 data
+    pub fn from_json(slot: u64, value: &Value) -> Option<Self> {
+        let account = &value["account"];
+        let data = account["data"][0]
+            .as_str()
+            .and_then(|b64| base64::engine::general_purpose::STANDARD.decode(b64).ok())
+            .unwrap_or_default();
+
+        Some(Self {
+            pubkey: value["pubkey"].as_str()?.to_string(),
+            owner: account["owner"].as_str()?.to_string(),
+            lamports: account["lamports"].as_u64().unwrap_or(0),
+            data,
+            slot,
+            write_version: 0,
+        })
+    }
+}
+
+impl TransactionUpdate {
+    /// Parse a `json`-encoded transaction (`{ transaction, meta, blockTime }`)
+    pub fn from_json(signature: &str, slot: u64, tx: &Value) -> Option<Self> {
+        let meta = &tx["meta"];
+        let message = &tx["transaction"]["message"];
+
+        let mut account_keys: Vec<String> = string_array(&message["accountKeys"]);
+        account_keys.extend(string_array(&meta["loadedAddresses"]["writable"]));
+        account_keys.extend(string_array(&meta["loadedAddresses"]["readonly"]));
+
+        let instructions = message["instructions"]
+            .as_array()
+            .map(|ixs| {
+                ixs.iter()
+                    .filter_map(|ix| {
+                        let program_id = account_keys.get(ix["programIdIndex"].as_u64()? as usize)?;
+                        let accounts = ix["accounts"]
+                            .as_array()?
+                            .iter()
+                            .filter_map(|i| account_keys.get(i.as_u64()? as usize).cloned())
+                            .collect();
+                        let data = bs58::decode(ix["data"].as_str().unwrap_or_default())
+                            .into_vec()
+                            .ok()?;
+                        Some(InstructionUpdate {
+                            program_id: program_id.clone(),
+                            accounts,
+                            data,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let signature = if signature.is_empty() {
+            tx["transaction"]["signatures"][0].as_str()?
+        } else {
+            signature
+        };
+
+        Some(Self {
+            signature: signature.to_string(),
+            slot,
+            block_time: tx["blockTime"].as_i64(),
+            success: meta["err"].is_null(),
+            fee: meta["fee"].as_u64().unwrap_or(0),
+            account_keys,
+            instructions,
+            pre_balances: u64_array(&meta["preBalances"]),
+            post_balances: u64_array(&meta["postBalances"]),
+            pre_token_balances: token_balances(&meta["preTokenBalances"]),
+            post_token_balances: token_balances(&meta["postTokenBalances"]),
+            log_messages: string_array(&meta["logMessages"]),
+        })
+    }
+
+    /// Lamport change of an account in this transaction
+    pub fn lamport_delta(&self, account: &str) -> i128 {
+        self.account_keys
+            .iter()
+            .position(|k| k == account)
+            .map(|i| {
+                let pre = self.pre_balances.get(i).copied().unwrap_or(0) as i128;
+                let post = self.post_balances.get(i).copied().unwrap_or(0) as i128;
+                post - pre
+            })
+            .unwrap_or(0)
+    }
+}
+
+fn string_array(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|k| k.as_str().or_else(|| k["pubkey"].as_str()))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn u64_array(value: &Value) -> Vec<u64> {
+    value
+        .as_array()
+        .map(|items| items.iter().filter_map(Value::as_u64).collect())
+        .unwrap_or_default()
+}
+
+fn token_balances(value: &Value) -> Vec<TokenBalance> {
+    value
+        .as_array()
+        .map(|entries| {
+            entries
+                .iter()
+                .filter_map(|b| {
+                    Some(TokenBalance {
+                        account_index: b["accountIndex"].as_u64()? as u32,
+                        mint: b["mint"].as_str()?.to_string(),
+                        owner: b["owner"].as_str().unwrap_or_default().to_string(),
+                        amount: b["uiTokenAmount"]["amount"].as_str()?.parse().ok()?,
+                        decimals: b["uiTokenAmount"]["decimals"].as_u64().unwrap_or(0) as u8,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Serialize raw bytes as base64 strings to keep recordings compact
+mod base64_bytes {
+    use base64::Engine;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&base64::engine::general_purpose::STANDARD.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,460 @@
+// 🌋 Yellowstone Geyser Source
+// Bidirectional gRPC subscription to a Yellowstone Geyser endpoint
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tonic::codec::ProstCodec;
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::transport::{ClientTlsConfig, Endpoint};
+use tracing::{debug, error, info, warn};
+
+use super::events::{AccountUpdate, InstructionUpdate, MarketDataMessage, TokenBalance, TransactionUpdate};
+use super::geyser_proto as proto;
+use super::geyser_proto::subscribe_update::UpdateOneof;
+use super::source::{MarketDataSource, SourceFilter};
+
+const SUBSCRIBE_PATH: &str = "/geyser.Geyser/Subscribe";
+
+pub struct GeyserSource {
+    endpoint: String,
+    x_token: Option<String>,
+    filter: SourceFilter,
+}
+
+impl GeyserSource {
+    pub fn new(endpoint: String, x_token: Option<String>, filter: SourceFilter) -> Self {
+        Self { endpoint, x_token, filter }
+    }
+
+    fn subscribe_request(&self) -> proto::SubscribeRequest {
+        let mut accounts = HashMap::new();
+        accounts.insert(
+            "programs".to_string(),
+            proto::SubscribeRequestFilterAccounts {
+                account: Vec::new(),
+                owner: self.filter.programs.clone(),
+            },
+        );
+
+        let mut transactions = HashMap::new();
+        transactions.insert(
+            "transactions".to_string(),
+            proto::SubscribeRequestFilterTransactions {
+                vote: Some(false),
+                failed: Some(false),
+                account_include: self.filter.transaction_accounts(),
+                account_exclude: Vec::new(),
+                account_required: Vec::new(),
+            },
+        );
+
+        proto::SubscribeRequest {
+            accounts,
+            transactions,
+            commitment: Some(proto::CommitmentLevel::from_name(&self.filter.commitment) as i32),
+            ping: None,
+        }
+    }
+
+    /// Single connection lifetime; returns when the server ends the stream
+    async fn stream(&self, tx: &mpsc::Sender<MarketDataMessage>) -> anyhow::Result<()> {
+        let mut endpoint = Endpoint::from_shared(self.endpoint.clone())?
+            .connect_timeout(Duration::from_secs(10))
+            .tcp_nodelay(true)
+            .http2_keep_alive_interval(Duration::from_secs(10));
+        if self.endpoint.starts_with("https") {
+            endpoint = endpoint.tls_config(ClientTlsConfig::new().with_webpki_roots())?;
+        }
+
+        let channel = endpoint.connect().await?;
+        let mut grpc = tonic::client::Grpc::new(channel);
+        grpc.ready().await?;
+
+        // Outbound half: initial subscription, then ping replies
+        let (outbound_tx, outbound_rx) = mpsc::channel::<proto::SubscribeRequest>(16);
+        outbound_tx.send(self.subscribe_request()).await?;
+        let outbound = futures_util::stream::unfold(outbound_rx, |mut rx| async move {
+            rx.recv().await.map(|request| (request, rx))
+        });
+
+        let mut request = tonic::Request::new(outbound);
+        if let Some(token) = &self.x_token {
+            request.metadata_mut().insert("x-token", token.parse()?);
+        }
+
+        let codec = ProstCodec::<proto::SubscribeRequest, proto::SubscribeUpdate>::default();
+        let mut inbound = grpc
+            .streaming(request, PathAndQuery::from_static(SUBSCRIBE_PATH), codec)
+            .await?
+            .into_inner();
+
+        info!("🌋 Geyser subscribed: {} programs, {} accounts",
+              self.filter.programs.len(), self.filter.accounts.len());
+
+        while let Some(update) = inbound.message().await? {
+            let event = match update.update_oneof {
+                Some(UpdateOneof::Account(account)) => convert_account(account),
+                Some(UpdateOneof::Transaction(transaction)) => convert_transaction(*transaction),
+                Some(UpdateOneof::Ping(_)) => {
+                    let ping = proto::SubscribeRequest {
+                        ping: Some(proto::SubscribeRequestPing { id: 1 }),
+                        ..Default::default()
+                    };
+                    outbound_tx.send(ping).await?;
+                    None
+                }
+                Some(UpdateOneof::Pong(_)) | None => None,
+            };
+
+            if let Some(event) = event {
+                tx.send(event).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn convert_account(update: proto::SubscribeUpdateAccount) -> Option<MarketDataMessage> {
+    let account = update.account?;
+    Some(MarketDataMessage::AccountUpdate(AccountUpdate {
+        pubkey: bs58::encode(&account.pubkey).into_string(),
+        owner: bs58::encode(&account.owner).into_string(),
+        lamports: account.lamports,
+        data: account.data,
+        slot: update.slot,
+        write_version: account.write_version,
+    }))
+}
+
+/// Geyser transaction updates carry no block time, so `block_time` is always `None`;
+/// latency stays unknown and ordering uses the receive time
+fn convert_transaction(update: proto::SubscribeUpdateTransaction) -> Option<MarketDataMessage> {
+    let info = update.transaction?;
+    if info.is_vote {
+        return None;
+    }
+    let message = info.transaction?.message?;
+    let meta = info.meta?;
+
+    let account_keys: Vec<String> = message
+        .account_keys
+        .iter()
+        .chain(meta.loaded_writable_addresses.iter())
+        .chain(meta.loaded_readonly_addresses.iter())
+        .map(|key| bs58::encode(key).into_string())
+        .collect();
+
+    let instructions = message
+        .instructions
+        .into_iter()
+        .filter_map(|ix| {
+            Some(InstructionUpdate {
+                program_id: account_keys.get(ix.program_id_index as usize)?.clone(),
+                accounts: ix
+                    .accounts
+                    .iter()
+                    .filter_map(|i| account_keys.get(*i as usize).cloned())
+                    .collect(),
+                data: ix.data,
+            })
+        })
+        .collect();
+
+    Some(MarketDataMessage::Transaction(TransactionUpdate {
+        signature: bs58::encode(&info.signature).into_string(),
+        slot: update.slot,
+        block_time: None,
+        success: meta.err.is_none(),
+        fee: meta.fee,
+        account_keys,
+        instructions,
+        pre_balances: meta.pre_balances,
+        post_balances: meta.post_balances,
+        pre_token_balances: convert_token_balances(meta.pre_token_balances),
+        post_token_balances: convert_token_balances(meta.post_token_balances),
+        log_messages: meta.log_messages,
+    }))
+}
+
+fn convert_token_balances(balances: Vec<proto::TokenBalance>) -> Vec<TokenBalance> {
+    balances
+        .into_iter()
+        .filter_map(|b| {
+            let ui = b.ui_token_amount?;
+            Some(TokenBalance {
+                account_index: b.account_index,
+                mint: b.mint,
+                owner: b.owner,
+                amount: ui.amount.parse().ok()?,
+                decimals: ui.decimals as u8,
+            })
+        })
+        .collect()
+}
+
+#[async_trait]
+impl MarketDataSource for GeyserSource {
+    fn name(&self) -> &str {
+        "geyser"
+    }
+
+    async fn run(&mut self, tx: mpsc::Sender<MarketDataMessage>) -> anyhow::Result<()> {
+        info!("🌋 Connecting to Yellowstone Geyser: {}", self.endpoint);
+
+        // Reconnect with exponential backoff, capped at 30s
+        let mut backoff = Duration::from_millis(250);
+        loop {
+            match self.stream(&tx).await {
+                Ok(()) => {
+                    warn!("🌋 Geyser stream ended, reconnecting");
+                    backoff = Duration::from_millis(250);
+                }
+                Err(e) => {
+                    error!("❌ Geyser stream failed: {} (retry in {:?})", e, backoff);
+                    backoff = (backoff * 2).min(Duration::from_secs(30));
+                }
+            }
+
+            if tx.is_closed() {
+                debug!("🌋 Geyser receiver dropped, stopping");
+                return Ok(());
+            }
+            tokio::time::sleep(backoff).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::{stream, Stream, StreamExt};
+    use std::convert::Infallible;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::{Context, Poll};
+    use tonic::body::BoxBody;
+    use tonic::codegen::{http, BoxFuture, Service};
+    use tonic::server::NamedService;
+    use tonic::transport::server::TcpIncoming;
+    use tonic::transport::Server;
+    use tonic::{Request, Response, Status, Streaming};
+
+    const PROGRAM: [u8; 32] = [7; 32];
+    const WALLET: [u8; 32] = [1; 32];
+    const MINT: [u8; 32] = [2; 32];
+    const LOADED: [u8; 32] = [3; 32];
+
+    type UpdateStream = Pin<Box<dyn Stream<Item = Result<proto::SubscribeUpdate, Status>> + Send>>;
+
+    /// Geyser stand-in: the first connection sends an account and a transaction then ends,
+    /// later connections send one account update and stay open
+    #[derive(Clone)]
+    struct MockGeyser {
+        connections: Arc<AtomicUsize>,
+        requests: mpsc::UnboundedSender<proto::SubscribeRequest>,
+    }
+
+    impl NamedService for MockGeyser {
+        const NAME: &'static str = "geyser.Geyser";
+    }
+
+    impl Service<http::Request<BoxBody>> for MockGeyser {
+        type Response = http::Response<BoxBody>;
+        type Error = Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: http::Request<BoxBody>) -> Self::Future {
+            let subscribe = Subscribe(self.clone());
+            Box::pin(async move {
+                let codec = ProstCodec::<proto::SubscribeUpdate, proto::SubscribeRequest>::default();
+                Ok(tonic::server::Grpc::new(codec).streaming(subscribe, request).await)
+            })
+        }
+    }
+
+    struct Subscribe(MockGeyser);
+
+    impl Service<Request<Streaming<proto::SubscribeRequest>>> for Subscribe {
+        type Response = Response<UpdateStream>;
+        type Error = Status;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: Request<Streaming<proto::SubscribeRequest>>) -> Self::Future {
+            let mock = self.0.clone();
+            Box::pin(async move {
+                let mut inbound = request.into_inner();
+                if let Some(subscription) = inbound.message().await? {
+                    mock.requests.send(subscription).ok();
+                }
+
+                let connection = mock.connections.fetch_add(1, Ordering::SeqCst);
+                let updates: UpdateStream = if connection == 0 {
+                    Box::pin(stream::iter(vec![Ok(account_update(100)), Ok(transaction_update())]))
+                } else {
+                    Box::pin(stream::iter(vec![Ok(account_update(200))]).chain(stream::pending()))
+                };
+                Ok(Response::new(updates))
+            })
+        }
+    }
+
+    fn account_update(slot: u64) -> proto::SubscribeUpdate {
+        proto::SubscribeUpdate {
+            filters: vec!["programs".to_string()],
+            update_oneof: Some(UpdateOneof::Account(proto::SubscribeUpdateAccount {
+                account: Some(proto::SubscribeUpdateAccountInfo {
+                    pubkey: MINT.to_vec(),
+                    lamports: 1_461_600,
+                    owner: PROGRAM.to_vec(),
+                    data: vec![1, 2, 3],
+                    write_version: slot + 1,
+                    ..Default::default()
+                }),
+                slot,
+                is_startup: false,
+            })),
+        }
+    }
+
+    fn transaction_update() -> proto::SubscribeUpdate {
+        let token_balance = |amount: &str| proto::TokenBalance {
+            account_index: 1,
+            mint: bs58::encode(MINT).into_string(),
+            ui_token_amount: Some(proto::UiTokenAmount {
+                ui_amount: 0.0,
+                decimals: 6,
+                amount: amount.to_string(),
+            }),
+            owner: bs58::encode(WALLET).into_string(),
+        };
+
+        proto::SubscribeUpdate {
+            filters: vec!["transactions".to_string()],
+            update_oneof: Some(UpdateOneof::Transaction(Box::new(proto::SubscribeUpdateTransaction {
+                transaction: Some(proto::SubscribeUpdateTransactionInfo {
+                    signature: vec![9; 64],
+                    is_vote: false,
+                    transaction: Some(proto::Transaction {
+                        signatures: vec![vec![9; 64]],
+                        message: Some(proto::Message {
+                            account_keys: vec![WALLET.to_vec(), PROGRAM.to_vec()],
+                            instructions: vec![proto::CompiledInstruction {
+                                program_id_index: 1,
+                                // Index 2 resolves through the loaded lookup-table address
+                                accounts: vec![0, 2],
+                                data: vec![42],
+                            }],
+                            versioned: true,
+                        }),
+                    }),
+                    meta: Some(proto::TransactionStatusMeta {
+                        fee: 5_000,
+                        pre_balances: vec![2_000_000_000, 1],
+                        post_balances: vec![1_000_000_000, 1],
+                        log_messages: vec!["Program log: swap".to_string()],
+                        pre_token_balances: vec![token_balance("0")],
+                        post_token_balances: vec![token_balance("1000000")],
+                        loaded_writable_addresses: vec![LOADED.to_vec()],
+                        ..Default::default()
+                    }),
+                    index: 0,
+                }),
+                slot: 100,
+            }))),
+        }
+    }
+
+    async fn next(rx: &mut mpsc::Receiver<MarketDataMessage>) -> MarketDataMessage {
+        tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .expect("no event from the Geyser source")
+            .expect("Geyser source stopped")
+    }
+
+    #[tokio::test]
+    async fn subscribes_maps_updates_and_reconnects() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let incoming = TcpIncoming::from_listener(listener, true, None).unwrap();
+
+        let connections = Arc::new(AtomicUsize::new(0));
+        let (requests_tx, mut requests_rx) = mpsc::unbounded_channel();
+        let mock = MockGeyser {
+            connections: connections.clone(),
+            requests: requests_tx,
+        };
+        let server = tokio::spawn(Server::builder().add_service(mock).serve_with_incoming(incoming));
+
+        let filter = SourceFilter {
+            programs: vec![bs58::encode(PROGRAM).into_string()],
+            accounts: vec![bs58::encode(WALLET).into_string()],
+            commitment: "processed".to_string(),
+        };
+        let mut source = GeyserSource::new(format!("http://{}", address), None, filter);
+        let (tx, mut rx) = mpsc::channel(16);
+        let client = tokio::spawn(async move { source.run(tx).await });
+
+        let MarketDataMessage::AccountUpdate(account) = next(&mut rx).await else {
+            panic!("expected an account update first");
+        };
+        assert_eq!(account.pubkey, bs58::encode(MINT).into_string());
+        assert_eq!(account.owner, bs58::encode(PROGRAM).into_string());
+        assert_eq!((account.lamports, account.slot, account.write_version), (1_461_600, 100, 101));
+        assert_eq!(account.data, vec![1, 2, 3]);
+
+        let MarketDataMessage::Transaction(transaction) = next(&mut rx).await else {
+            panic!("expected a transaction update second");
+        };
+        let program = bs58::encode(PROGRAM).into_string();
+        let wallet = bs58::encode(WALLET).into_string();
+        let loaded = bs58::encode(LOADED).into_string();
+        assert_eq!(transaction.signature, bs58::encode([9; 64]).into_string());
+        assert_eq!(transaction.slot, 100);
+        assert!(transaction.success);
+        assert_eq!(transaction.block_time, None);
+        assert_eq!(transaction.account_keys, vec![wallet.clone(), program.clone(), loaded.clone()]);
+        assert_eq!(transaction.instructions.len(), 1);
+        assert_eq!(transaction.instructions[0].program_id, program);
+        assert_eq!(transaction.instructions[0].accounts, vec![wallet.clone(), loaded]);
+        assert_eq!(transaction.instructions[0].data, vec![42]);
+        assert_eq!(transaction.pre_balances, vec![2_000_000_000, 1]);
+        assert_eq!(transaction.post_balances, vec![1_000_000_000, 1]);
+        assert_eq!(transaction.pre_token_balances[0].amount, 0);
+        assert_eq!(transaction.post_token_balances[0].amount, 1_000_000);
+        assert_eq!(transaction.post_token_balances[0].decimals, 6);
+        assert_eq!(transaction.post_token_balances[0].owner, wallet);
+        assert_eq!(transaction.log_messages, vec!["Program log: swap".to_string()]);
+
+        // The first stream ended, so the next update comes over a new connection
+        let MarketDataMessage::AccountUpdate(account) = next(&mut rx).await else {
+            panic!("expected an account update after reconnecting");
+        };
+        assert_eq!(account.slot, 200);
+        assert_eq!(connections.load(Ordering::SeqCst), 2);
+
+        for _ in 0..2 {
+            let request = requests_rx.recv().await.unwrap();
+            assert_eq!(request.commitment, Some(proto::CommitmentLevel::Processed as i32));
+            assert_eq!(request.accounts["programs"].owner, vec![program.clone()]);
+            let transactions = &request.transactions["transactions"];
+            assert_eq!(transactions.vote, Some(false));
+            assert_eq!(transactions.failed, Some(false));
+            assert_eq!(transactions.account_include, vec![program.clone(), bs58::encode(WALLET).into_string()]);
+        }
+
+        client.abort();
+        server.abort();
+    }
+}
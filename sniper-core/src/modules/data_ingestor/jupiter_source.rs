@@ -0,0 +1,104 @@
+// 🪐 Jupiter Price Source
+// Periodic price polling from the Jupiter Price API
+
+use async_trait::async_trait;
+use serde_json::Value;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+use super::events::{MarketDataMessage, PriceTick};
+use super::source::MarketDataSource;
+
+/// Jupiter accepts at most 100 ids per request
+const MAX_IDS_PER_REQUEST: usize = 100;
+
+pub struct JupiterPriceSource {
+    api_url: String,
+    mints: Vec<String>,
+    vs_token: String,
+    interval: Duration,
+    client: reqwest::Client,
+}
+
+impl JupiterPriceSource {
+    pub fn new(api_url: String, mints: Vec<String>, vs_token: String, interval: Duration) -> Self {
+        Self {
+            api_url,
+            mints,
+            vs_token,
+            interval,
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(5))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Fetch price data from Jupiter API
+    async fn fetch_prices(&self, mints: &[String]) -> anyhow::Result<Vec<PriceTick>> {
+        let url = format!(
+            "{}/price/v2?ids={}&vsToken={}",
+            self.api_url.trim_end_matches('/'),
+            mints.join(","),
+            self.vs_token
+        );
+        let response: Value = self.client.get(&url).send().await?.error_for_status()?.json().await?;
+        let timestamp_ms = chrono::Utc::now().timestamp_millis();
+
+        let ticks = response["data"]
+            .as_object()
+            .map(|data| {
+                data.iter()
+                    .filter_map(|(mint, entry)| {
+                        let price = entry["price"].as_str()?.parse().ok()?;
+                        Some(PriceTick {
+                            mint: mint.clone(),
+                            price,
+                            timestamp_ms,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(ticks)
+    }
+}
+
+#[async_trait]
+impl MarketDataSource for JupiterPriceSource {
+    fn name(&self) -> &str {
+        "jupiter"
+    }
+
+    async fn run(&mut self, tx: mpsc::Sender<MarketDataMessage>) -> anyhow::Result<()> {
+        if self.mints.is_empty() {
+            info!("🪐 No Jupiter price mints configured - polling disabled");
+            return Ok(());
+        }
+
+        info!("🪐 Polling Jupiter prices for {} mints every {:?}", self.mints.len(), self.interval);
+
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            interval.tick().await;
+
+            for chunk in self.mints.chunks(MAX_IDS_PER_REQUEST) {
+                match self.fetch_prices(chunk).await {
+                    Ok(ticks) => {
+                        debug!("💰 Fetched {} Jupiter prices", ticks.len());
+                        for tick in ticks {
+                            if tx.send(MarketDataMessage::PriceTick(tick)).await.is_err() {
+                                return Ok(());
+                            }
+                        }
+                    }
+                    Err(e) => warn!("⚠️ Jupiter price fetch failed: {}", e),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,125 @@
+// 🆕 Launch Decoding
+// New Raydium pools and pump.fun tokens from top-level instructions
+
+use super::events::{InstructionUpdate, LaunchEvent, LaunchKind, TransactionUpdate};
+use super::source::{PUMP_FUN_PROGRAM, RAYDIUM_AMM_PROGRAM};
+use super::wallet_tracker::WSOL_MINT;
+
+/// Raydium AMM v4 instruction tag for `initialize2`
+const RAYDIUM_INITIALIZE2: u8 = 1;
+
+/// Anchor discriminator of pump.fun `create`
+const PUMP_FUN_CREATE: [u8; 8] = [24, 30, 200, 40, 5, 28, 7, 119];
+
+/// pump.fun curves start with these virtual reserves (SOL, tokens)
+const PUMP_FUN_VIRTUAL_SOL: f64 = 30.0;
+const PUMP_FUN_VIRTUAL_TOKENS: f64 = 1_073_000_000.0;
+
+/// Launches created by the transaction's top-level instructions
+///
+/// Launches made through CPI from another program are not seen.
+pub fn decode_launches(tx: &TransactionUpdate) -> Vec<LaunchEvent> {
+    if !tx.success {
+        return Vec::new();
+    }
+
+    tx.instructions
+        .iter()
+        .filter_map(|ix| match ix.program_id.as_str() {
+            RAYDIUM_AMM_PROGRAM => decode_raydium_initialize(tx, ix),
+            PUMP_FUN_PROGRAM => decode_pump_fun_create(tx, ix),
+            _ => None,
+        })
+        .collect()
+}
+
+/// `initialize2` data: tag, nonce, open_time, init_pc_amount, init_coin_amount
+fn decode_raydium_initialize(tx: &TransactionUpdate, ix: &InstructionUpdate) -> Option<LaunchEvent> {
+    if ix.data.first() != Some(&RAYDIUM_INITIALIZE2) || ix.data.len() < 26 {
+        return None;
+    }
+    let pc_amount = u64::from_le_bytes(ix.data[10..18].try_into().ok()?);
+    let coin_amount = u64::from_le_bytes(ix.data[18..26].try_into().ok()?);
+
+    // amm = 4, coin mint = 8, pc mint = 9, creator wallet = 17
+    let pool = ix.accounts.get(4)?;
+    let coin_mint = ix.accounts.get(8)?;
+    let pc_mint = ix.accounts.get(9)?;
+    let creator = ix.accounts.get(17)?;
+
+    // The quote side is SOL when either side is; otherwise Raydium's pc convention
+    let (mint, quote_mint, token_amount, quote_amount) = if coin_mint == WSOL_MINT {
+        (pc_mint, coin_mint, pc_amount, coin_amount)
+    } else {
+        (coin_mint, pc_mint, coin_amount, pc_amount)
+    };
+
+    let quote_liquidity = quote_amount as f64 / 10f64.powi(decimals(tx, quote_mint)? as i32);
+    let tokens = token_amount as f64 / 10f64.powi(decimals(tx, mint)? as i32);
+
+    Some(LaunchEvent {
+        signature: tx.signature.clone(),
+        slot: tx.slot,
+        block_time: tx.block_time,
+        kind: LaunchKind::RaydiumPool,
+        mint: mint.clone(),
+        quote_mint: quote_mint.clone(),
+        pool: pool.clone(),
+        creator: creator.clone(),
+        quote_liquidity,
+        price: (tokens > 0.0).then(|| quote_liquidity / tokens),
+        name: None,
+        symbol: None,
+        uri: None,
+    })
+}
+
+/// `create` data: discriminator, then borsh strings name, symbol, uri
+fn decode_pump_fun_create(tx: &TransactionUpdate, ix: &InstructionUpdate) -> Option<LaunchEvent> {
+    let args = ix.data.strip_prefix(&PUMP_FUN_CREATE)?;
+    let (name, rest) = borsh_string(args)?;
+    let (symbol, rest) = borsh_string(rest)?;
+    let (uri, _) = borsh_string(rest)?;
+
+    // mint = 0, bonding curve = 2, creator = 7
+    let mint = ix.accounts.first()?;
+    let bonding_curve = ix.accounts.get(2)?;
+    let creator = ix.accounts.get(7)?;
+
+    // SOL reaching the curve in the same transaction is the creator's initial buy (plus rent)
+    let deposited = tx.lamport_delta(bonding_curve).max(0) as f64 / 1e9;
+    let virtual_sol = PUMP_FUN_VIRTUAL_SOL + deposited;
+    let virtual_tokens = PUMP_FUN_VIRTUAL_SOL * PUMP_FUN_VIRTUAL_TOKENS / virtual_sol;
+
+    Some(LaunchEvent {
+        signature: tx.signature.clone(),
+        slot: tx.slot,
+        block_time: tx.block_time,
+        kind: LaunchKind::PumpFun,
+        mint: mint.clone(),
+        quote_mint: WSOL_MINT.to_string(),
+        pool: bonding_curve.clone(),
+        creator: creator.clone(),
+        quote_liquidity: deposited,
+        price: Some(virtual_sol / virtual_tokens),
+        name: Some(name),
+        symbol: Some(symbol),
+        uri: Some(uri),
+    })
+}
+
+/// Decimals of `mint` from any token balance in the transaction
+fn decimals(tx: &TransactionUpdate, mint: &str) -> Option<u8> {
+    tx.post_token_balances
+        .iter()
+        .chain(&tx.pre_token_balances)
+        .find(|b| b.mint == mint)
+        .map(|b| b.decimals)
+        .or((mint == WSOL_MINT).then_some(9))
+}
+
+fn borsh_string(data: &[u8]) -> Option<(String, &[u8])> {
+    let len = u32::from_le_bytes(data.get(..4)?.try_into().ok()?) as usize;
+    let bytes = data.get(4..4 + len)?;
+    Some((String::from_utf8_lossy(bytes).into_owned(), &data[4 + len..]))
+}
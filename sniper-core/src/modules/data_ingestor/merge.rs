@@ -0,0 +1,435 @@
+// 🔀 Event Merge Stage
+// Cross-source deduplication, slot reordering and lead/lag statistics
+
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+use super::events::{MarketDataMessage, MarketEvent};
+
+#[derive(Debug, Clone)]
+pub struct MergeConfig {
+    /// How long an event may be held back to let lower slots arrive
+    pub reorder_window: Duration,
+    /// How many slots a dedup key is remembered for
+    pub dedup_retention_slots: u64,
+}
+
+impl MergeConfig {
+    pub fn from_env() -> Self {
+        Self {
+            reorder_window: Duration::from_millis(
+                std::env::var("MERGE_REORDER_WINDOW_MS")
+                    .unwrap_or_else(|_| "20".to_string())
+                    .parse()
+                    .unwrap_or(20),
+            ),
+            dedup_retention_slots: std::env::var("MERGE_DEDUP_RETENTION_SLOTS")
+                .unwrap_or_else(|_| "150".to_string())
+                .parse()
+                .unwrap_or(150),
+        }
+    }
+}
+
+/// Identity of an event across sources
+///
+/// Account writes are keyed by content rather than `write_version`, because
+/// WebSocket feeds do not carry it; `write_version` is still used to drop
+/// stale writes within a single source.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DedupKey {
+    Transaction(String),
+    Account { pubkey: String, slot: u64, fingerprint: u64 },
+}
+
+impl DedupKey {
+    pub fn of(event: &MarketDataMessage) -> Option<Self> {
+        match event {
+            MarketDataMessage::Transaction(transaction) => {
+                Some(Self::Transaction(transaction.signature.clone()))
+            }
+            MarketDataMessage::AccountUpdate(update) => {
+                let mut hasher = DefaultHasher::new();
+                update.lamports.hash(&mut hasher);
+                update.data.hash(&mut hasher);
+                Some(Self::Account {
+                    pubkey: update.pubkey.clone(),
+                    slot: update.slot,
+                    fingerprint: hasher.finish(),
+                })
+            }
+            MarketDataMessage::PriceTick(_)
+            | MarketDataMessage::WalletActivity(_)
+            | MarketDataMessage::Swap(_)
+            | MarketDataMessage::Launch(_) => None,
+        }
+    }
+}
+
+/// Per-source delivery statistics
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SourceStats {
+    pub events: u64,
+    /// Events this source delivered before any other source
+    pub first: u64,
+    /// Events another source had already delivered
+    pub duplicates: u64,
+    /// Account writes older than one already seen from this source
+    pub stale: u64,
+    /// Events that arrived after their slot had already been released
+    pub late: u64,
+    pub avg_lag_ms: f64,
+    pub max_lag_ms: f64,
+    #[serde(skip)]
+    total_lag_us: u64,
+}
+
+impl SourceStats {
+    /// Share of deduplicated events this source won
+    pub fn lead_ratio(&self) -> f64 {
+        let contested = self.first + self.duplicates;
+        if contested == 0 {
+            0.0
+        } else {
+            self.first as f64 / contested as f64
+        }
+    }
+
+    fn record_lag(&mut self, lag_us: i64) {
+        let lag_us = lag_us.max(0) as u64;
+        self.duplicates += 1;
+        self.total_lag_us += lag_us;
+        self.avg_lag_ms = self.total_lag_us as f64 / self.duplicates as f64 / 1000.0;
+        self.max_lag_ms = self.max_lag_ms.max(lag_us as f64 / 1000.0);
+    }
+}
+
+struct FirstSeen {
+    received_at_us: i64,
+    slot: u64,
+}
+
+/// Merges events from several sources into one ordered, deduplicated stream
+pub struct EventMerger {
+    config: MergeConfig,
+    seen: HashMap<DedupKey, FirstSeen>,
+    account_versions: HashMap<(String, String), (u64, u64)>,
+    buffer: BTreeMap<(u64, u64), MarketEvent>,
+    arrivals: VecDeque<(Instant, u64)>,
+    stats: HashMap<String, SourceStats>,
+    sequence: u64,
+    max_slot: u64,
+    released_slot: u64,
+    pruned_slot: u64,
+}
+
+impl EventMerger {
+    pub fn new(config: MergeConfig) -> Self {
+        Self {
+            config,
+            seen: HashMap::new(),
+            account_versions: HashMap::new(),
+            buffer: BTreeMap::new(),
+            arrivals: VecDeque::new(),
+            stats: HashMap::new(),
+            sequence: 0,
+            max_slot: 0,
+            released_slot: 0,
+            pruned_slot: 0,
+        }
+    }
+
+    /// Accept an event; returns events that can be released immediately
+    pub fn push(&mut self, source: &str, event: MarketDataMessage, now: Instant) -> Vec<MarketEvent> {
+        let received_at_us = chrono::Utc::now().timestamp_micros();
+        let stats = self.stats.entry(source.to_string()).or_default();
+        stats.events += 1;
+
+        if let MarketDataMessage::AccountUpdate(update) = &event {
+            if update.write_version != 0 {
+                let key = (source.to_string(), update.pubkey.clone());
+                let version = (update.slot, update.write_version);
+                match self.account_versions.get(&key) {
+                    Some(last) if *last >= version => {
+                        stats.stale += 1;
+                        return Vec::new();
+                    }
+                    _ => {
+                        self.account_versions.insert(key, version);
+                    }
+                }
+            }
+        }
+
+        if let Some(key) = DedupKey::of(&event) {
+            if let Some(first) = self.seen.get(&key) {
+                stats.record_lag(received_at_us - first.received_at_us);
+                return Vec::new();
+            }
+            stats.first += 1;
+            self.seen.insert(key, FirstSeen {
+                received_at_us,
+                slot: event.slot().unwrap_or(self.max_slot),
+            });
+        }
+
+        let tagged = MarketEvent {
+            source: source.to_string(),
+            received_at_us,
+            latency_us: event.timestamp_us().map(|t| received_at_us - t),
+            event,
+        };
+
+        let Some(slot) = tagged.event.slot() else {
+            return vec![tagged];
+        };
+
+        if slot < self.released_slot {
+            stats.late += 1;
+            return vec![tagged];
+        }
+
+        if slot > self.max_slot {
+            self.max_slot = slot;
+            self.prune_seen();
+        }
+
+        self.sequence += 1;
+        self.buffer.insert((slot, self.sequence), tagged);
+        self.arrivals.push_back((now, slot));
+
+        Vec::new()
+    }
+
+    /// Release buffered events whose reorder window has elapsed, in slot order
+    pub fn flush(&mut self, now: Instant) -> Vec<MarketEvent> {
+        let mut release_through: Option<u64> = None;
+        while let Some(&(arrived, slot)) = self.arrivals.front() {
+            if arrived + self.config.reorder_window > now {
+                break;
+            }
+            self.arrivals.pop_front();
+            release_through = Some(release_through.map_or(slot, |s| s.max(slot)));
+        }
+
+        let Some(through) = release_through else {
+            return Vec::new();
+        };
+
+        let remaining = self.buffer.split_off(&(through + 1, 0));
+        let released = std::mem::replace(&mut self.buffer, remaining);
+        self.released_slot = self.released_slot.max(through);
+
+        released.into_values().collect()
+    }
+
+    /// Release everything still buffered, e.g. on shutdown
+    pub fn drain(&mut self) -> Vec<MarketEvent> {
+        self.arrivals.clear();
+        std::mem::take(&mut self.buffer).into_values().collect()
+    }
+
+    /// When the oldest buffered event must be released
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.arrivals
+            .front()
+            .map(|(arrived, _)| *arrived + self.config.reorder_window)
+    }
+
+    pub fn stats(&self) -> &HashMap<String, SourceStats> {
+        &self.stats
+    }
+
+    fn prune_seen(&mut self) {
+        // Amortize: sweep at most every 16 slots
+        if self.max_slot < self.pruned_slot + 16 {
+            return;
+        }
+        self.pruned_slot = self.max_slot;
+
+        let cutoff = self.max_slot.saturating_sub(self.config.dedup_retention_slots);
+        self.seen.retain(|_, first| first.slot >= cutoff);
+        self.account_versions.retain(|_, (slot, _)| *slot >= cutoff);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::data_ingestor::events::{AccountUpdate, PriceTick, TransactionUpdate};
+
+    const WINDOW: Duration = Duration::from_millis(20);
+
+    fn merger() -> EventMerger {
+        EventMerger::new(MergeConfig {
+            reorder_window: WINDOW,
+            dedup_retention_slots: 150,
+        })
+    }
+
+    fn transaction(signature: &str, slot: u64) -> MarketDataMessage {
+        MarketDataMessage::Transaction(TransactionUpdate {
+            signature: signature.to_string(),
+            slot,
+            block_time: None,
+            success: true,
+            fee: 5000,
+            account_keys: Vec::new(),
+            instructions: Vec::new(),
+            pre_balances: Vec::new(),
+            post_balances: Vec::new(),
+            pre_token_balances: Vec::new(),
+            post_token_balances: Vec::new(),
+            log_messages: Vec::new(),
+        })
+    }
+
+    fn account(slot: u64, write_version: u64, data: &[u8]) -> MarketDataMessage {
+        MarketDataMessage::AccountUpdate(AccountUpdate {
+            pubkey: "Pool111".to_string(),
+            owner: "Program111".to_string(),
+            lamports: 1_000_000,
+            data: data.to_vec(),
+            slot,
+            write_version,
+        })
+    }
+
+    fn slots(events: &[MarketEvent]) -> Vec<u64> {
+        events.iter().filter_map(|e| e.event.slot()).collect()
+    }
+
+    #[test]
+    fn duplicates_across_sources_are_dropped_and_counted() {
+        let mut merger = merger();
+        let now = Instant::now();
+
+        merger.push("geyser", transaction("sig1", 10), now);
+        merger.push("websocket", transaction("sig1", 10), now);
+        // Websocket carries no write version; the same content still dedups
+        merger.push("geyser", account(10, 7, &[1, 2, 3]), now);
+        merger.push("websocket", account(10, 0, &[1, 2, 3]), now);
+        // A different write in the same slot is a distinct event
+        merger.push("websocket", account(10, 0, &[4, 5, 6]), now);
+
+        let released = merger.flush(now + WINDOW);
+        assert_eq!(released.len(), 3);
+        assert!(released.iter().take(2).all(|e| e.source == "geyser"));
+        assert_eq!(released[2].source, "websocket");
+
+        let geyser = &merger.stats()["geyser"];
+        let websocket = &merger.stats()["websocket"];
+        assert_eq!((geyser.events, geyser.first, geyser.duplicates), (2, 2, 0));
+        assert_eq!((websocket.events, websocket.first, websocket.duplicates), (3, 1, 2));
+        assert_eq!(geyser.lead_ratio(), 1.0);
+        assert!((websocket.lead_ratio() - 1.0 / 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn stale_write_versions_are_dropped_per_source() {
+        let mut merger = merger();
+        let now = Instant::now();
+
+        merger.push("geyser", account(10, 5, &[1]), now);
+        // Older and repeated versions from the same source
+        merger.push("geyser", account(10, 4, &[2]), now);
+        merger.push("geyser", account(10, 5, &[3]), now);
+        merger.push("geyser", account(9, 6, &[4]), now);
+        // A newer write is accepted
+        merger.push("geyser", account(10, 6, &[5]), now);
+        // Versions are tracked per source, so another feed is not stale
+        merger.push("geyser-backup", account(10, 1, &[6]), now);
+
+        assert_eq!(merger.stats()["geyser"].stale, 3);
+        assert_eq!(merger.stats()["geyser-backup"].stale, 0);
+        assert_eq!(merger.flush(now + WINDOW).len(), 3);
+    }
+
+    #[test]
+    fn buffered_events_are_released_in_slot_order() {
+        let mut merger = merger();
+        let now = Instant::now();
+
+        for (signature, slot) in [("c", 12), ("a", 10), ("d", 12), ("b", 11)] {
+            assert!(merger.push("geyser", transaction(signature, slot), now).is_empty());
+        }
+        assert_eq!(merger.next_deadline(), Some(now + WINDOW));
+
+        // Held until the reorder window elapses
+        assert!(merger.flush(now + WINDOW - Duration::from_millis(1)).is_empty());
+
+        let released = merger.flush(now + WINDOW);
+        assert_eq!(slots(&released), vec![10, 11, 12, 12]);
+        // Arrival order is kept within a slot
+        let signatures: Vec<_> = released
+            .iter()
+            .map(|e| match &e.event {
+                MarketDataMessage::Transaction(t) => t.signature.as_str(),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(signatures, vec!["a", "b", "c", "d"]);
+        assert_eq!(merger.next_deadline(), None);
+    }
+
+    #[test]
+    fn flush_releases_only_through_expired_slots() {
+        let mut merger = merger();
+        let now = Instant::now();
+        let later = now + Duration::from_millis(15);
+
+        merger.push("geyser", transaction("a", 10), now);
+        merger.push("geyser", transaction("b", 14), later);
+        merger.push("geyser", transaction("c", 9), later);
+
+        // Slot 10's window elapsed: release it and anything lower, keep slot 14
+        assert_eq!(slots(&merger.flush(now + WINDOW)), vec![9, 10]);
+        assert_eq!(merger.next_deadline(), Some(later + WINDOW));
+        assert_eq!(slots(&merger.flush(later + WINDOW)), vec![14]);
+    }
+
+    #[test]
+    fn late_and_slotless_events_pass_straight_through() {
+        let mut merger = merger();
+        let now = Instant::now();
+
+        merger.push("geyser", transaction("a", 10), now);
+        merger.flush(now + WINDOW);
+
+        let late = merger.push("websocket", transaction("b", 8), now + WINDOW);
+        assert_eq!(slots(&late), vec![8]);
+        assert_eq!(merger.stats()["websocket"].late, 1);
+
+        // The released slot itself is still buffered
+        assert!(merger.push("websocket", transaction("c", 10), now + WINDOW).is_empty());
+
+        let tick = MarketDataMessage::PriceTick(PriceTick {
+            mint: "Mint111".to_string(),
+            price: 1.5,
+            timestamp_ms: 0,
+        });
+        assert_eq!(merger.push("jupiter", tick, now).len(), 1);
+        assert_eq!(merger.stats()["jupiter"].late, 0);
+
+        assert_eq!(slots(&merger.drain()), vec![10]);
+        assert_eq!(merger.next_deadline(), None);
+    }
+
+    #[test]
+    fn dedup_keys_are_forgotten_after_retention() {
+        let mut merger = merger();
+        let now = Instant::now();
+
+        merger.push("geyser", transaction("old", 1), now);
+        merger.push("geyser", transaction("new", 200), now);
+        merger.drain();
+
+        // Beyond the retention window the signature is no longer remembered
+        merger.push("websocket", transaction("old", 1), now);
+        assert_eq!(merger.stats()["websocket"].duplicates, 0);
+        assert_eq!(merger.drain().len(), 1);
+    }
+}
@@ -0,0 +1,125 @@
+// 🏊 Liquidity Pools
+// Raydium AMM v4 pool state decoding and discovery
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_request::RpcRequest;
+
+use super::source::RAYDIUM_AMM_PROGRAM;
+
+/// `LIQUIDITY_STATE_LAYOUT_V4` account size
+pub const RAYDIUM_AMM_ACCOUNT_LEN: usize = 752;
+
+// Field offsets in the AMM v4 state account
+const OFFSET_BASE_DECIMALS: usize = 32;
+const OFFSET_QUOTE_DECIMALS: usize = 40;
+const OFFSET_TRADE_FEE_NUMERATOR: usize = 144;
+const OFFSET_TRADE_FEE_DENOMINATOR: usize = 152;
+const OFFSET_POOL_OPEN_TIME: usize = 224;
+const OFFSET_BASE_VAULT: usize = 336;
+const OFFSET_QUOTE_VAULT: usize = 368;
+const OFFSET_BASE_MINT: usize = 400;
+const OFFSET_QUOTE_MINT: usize = 432;
+const OFFSET_LP_MINT: usize = 464;
+const OFFSET_LP_RESERVE: usize = 720;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RaydiumAmmPool {
+    pub address: String,
+    pub base_mint: String,
+    pub quote_mint: String,
+    pub lp_mint: String,
+    pub base_vault: String,
+    pub quote_vault: String,
+    pub base_decimals: u8,
+    pub quote_decimals: u8,
+    /// Swap fee as a fraction (0.0025 = 25 bps)
+    pub trade_fee: f64,
+    pub open_time: u64,
+    /// LP supply the pool believes is outstanding; burned LP is not subtracted
+    pub lp_reserve: u64,
+}
+
+impl RaydiumAmmPool {
+    pub fn decode(address: &str, data: &[u8]) -> Option<Self> {
+        if data.len() != RAYDIUM_AMM_ACCOUNT_LEN {
+            return None;
+        }
+
+        let fee_denominator = read_u64(data, OFFSET_TRADE_FEE_DENOMINATOR);
+        Some(Self {
+            address: address.to_string(),
+            base_mint: read_pubkey(data, OFFSET_BASE_MINT),
+            quote_mint: read_pubkey(data, OFFSET_QUOTE_MINT),
+            lp_mint: read_pubkey(data, OFFSET_LP_MINT),
+            base_vault: read_pubkey(data, OFFSET_BASE_VAULT),
+            quote_vault: read_pubkey(data, OFFSET_QUOTE_VAULT),
+            base_decimals: read_u64(data, OFFSET_BASE_DECIMALS) as u8,
+            quote_decimals: read_u64(data, OFFSET_QUOTE_DECIMALS) as u8,
+            trade_fee: if fee_denominator == 0 {
+                0.0
+            } else {
+                read_u64(data, OFFSET_TRADE_FEE_NUMERATOR) as f64 / fee_denominator as f64
+            },
+            open_time: read_u64(data, OFFSET_POOL_OPEN_TIME),
+            lp_reserve: read_u64(data, OFFSET_LP_RESERVE),
+        })
+    }
+
+    /// The other side of the pool, if it contains `mint`
+    pub fn other_mint(&self, mint: &str) -> Option<&str> {
+        if self.base_mint == mint {
+            Some(&self.quote_mint)
+        } else if self.quote_mint == mint {
+            Some(&self.base_mint)
+        } else {
+            None
+        }
+    }
+}
+
+/// Find Raydium AMM v4 pools that trade `mint` on either side
+pub async fn find_raydium_pools(rpc: &RpcClient, mint: &str) -> anyhow::Result<Vec<RaydiumAmmPool>> {
+    let mut pools = Vec::new();
+
+    for offset in [OFFSET_BASE_MINT, OFFSET_QUOTE_MINT] {
+        let params = serde_json::json!([
+            RAYDIUM_AMM_PROGRAM,
+            {
+                "encoding": "base64",
+                "commitment": "confirmed",
+                "filters": [
+                    { "dataSize": RAYDIUM_AMM_ACCOUNT_LEN },
+                    { "memcmp": { "offset": offset, "bytes": mint } }
+                ]
+            }
+        ]);
+
+        let accounts: Value = rpc.send(RpcRequest::GetProgramAccounts, params).await?;
+        for entry in accounts.as_array().into_iter().flatten() {
+            let Some(address) = entry["pubkey"].as_str() else { continue };
+            let Some(data) = entry["account"]["data"][0]
+                .as_str()
+                .and_then(|b64| base64::engine::general_purpose::STANDARD.decode(b64).ok())
+            else {
+                continue;
+            };
+
+            if let Some(pool) = RaydiumAmmPool::decode(address, &data) {
+                pools.push(pool);
+            }
+        }
+    }
+
+    Ok(pools)
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> String {
+    bs58::encode(&data[offset..offset + 32]).into_string()
+}
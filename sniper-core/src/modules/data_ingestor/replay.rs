@@ -0,0 +1,265 @@
+// ⏪ Record & Replay
+// NDJSON capture of live event streams and deterministic local playback
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::fs::File;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use super::events::MarketDataMessage;
+use super::source::MarketDataSource;
+
+/// Most recording time lost if the process dies without unwinding
+const RECORDER_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// One line of a recording: the event plus its original arrival time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    /// Unix time in microseconds when the event was received live
+    pub timestamp_us: i64,
+    pub source: String,
+    pub event: MarketDataMessage,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplaySpeed {
+    /// Preserve original inter-event gaps
+    RealTime,
+    /// Divide original gaps by this factor
+    Accelerated(f64),
+    /// No delay between events
+    AsFastAsPossible,
+}
+
+impl ReplaySpeed {
+    /// Parse `realtime`, `max` or a numeric speed-up factor such as `10`
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "realtime" | "1" | "1x" => Ok(Self::RealTime),
+            "max" | "fast" | "asap" => Ok(Self::AsFastAsPossible),
+            other => {
+                let factor: f64 = other.trim_end_matches('x').parse()
+                    .map_err(|_| anyhow::anyhow!("Invalid replay speed: {}", value))?;
+                if factor <= 0.0 {
+                    anyhow::bail!("Replay speed must be positive: {}", value);
+                }
+                Ok(Self::Accelerated(factor))
+            }
+        }
+    }
+
+    fn scale(&self, gap: Duration) -> Option<Duration> {
+        match self {
+            Self::RealTime => Some(gap),
+            Self::Accelerated(factor) => Some(gap.div_f64(*factor)),
+            Self::AsFastAsPossible => None,
+        }
+    }
+}
+
+/// Replays a recording produced by `RecordingSource`
+pub struct ReplaySource {
+    path: PathBuf,
+    speed: ReplaySpeed,
+}
+
+impl ReplaySource {
+    pub fn new(path: impl Into<PathBuf>, speed: ReplaySpeed) -> Self {
+        Self { path: path.into(), speed }
+    }
+}
+
+#[async_trait]
+impl MarketDataSource for ReplaySource {
+    fn name(&self) -> &str {
+        "replay"
+    }
+
+    async fn run(&mut self, tx: mpsc::Sender<MarketDataMessage>) -> anyhow::Result<()> {
+        info!("⏪ Replaying {} at {:?}", self.path.display(), self.speed);
+
+        let file = File::open(&self.path).await?;
+        let mut lines = BufReader::new(file).lines();
+        let mut previous_us: Option<i64> = None;
+        let mut replayed = 0u64;
+        let mut line_number = 0u64;
+
+        while let Some(line) = lines.next_line().await? {
+            line_number += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let recorded: RecordedEvent = match serde_json::from_str(&line) {
+                Ok(recorded) => recorded,
+                Err(e) => {
+                    warn!("⚠️ Skipping malformed recording line {}: {}", line_number, e);
+                    continue;
+                }
+            };
+
+            if let Some(previous) = previous_us {
+                let gap = Duration::from_micros((recorded.timestamp_us - previous).max(0) as u64);
+                if let Some(delay) = self.speed.scale(gap) {
+                    tokio::time::sleep(delay).await;
+                }
+            }
+            previous_us = Some(recorded.timestamp_us);
+
+            if tx.send(recorded.event).await.is_err() {
+                break;
+            }
+            replayed += 1;
+        }
+
+        info!("⏪ Replay finished: {} events", replayed);
+        Ok(())
+    }
+}
+
+/// Wraps a live source and appends every event it produces to an NDJSON file
+pub struct RecordingSource {
+    inner: Box<dyn MarketDataSource>,
+    path: PathBuf,
+    name: String,
+}
+
+impl RecordingSource {
+    pub fn new(inner: Box<dyn MarketDataSource>, path: impl Into<PathBuf>) -> Self {
+        let name = format!("{}+recorder", inner.name());
+        Self { inner, path: path.into(), name }
+    }
+}
+
+#[async_trait]
+impl MarketDataSource for RecordingSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn run(&mut self, tx: mpsc::Sender<MarketDataMessage>) -> anyhow::Result<()> {
+        let file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        // std's writer flushes on drop, so an aborted task still writes out what it buffered
+        let mut writer = BufWriter::new(file);
+        let mut flush_interval = tokio::time::interval(RECORDER_FLUSH_INTERVAL);
+        let source = self.inner.name().to_string();
+
+        info!("⏺️ Recording {} to {}", source, self.path.display());
+
+        let (inner_tx, mut inner_rx) = mpsc::channel(4096);
+        let inner = self.inner.run(inner_tx);
+        tokio::pin!(inner);
+
+        let mut inner_result = None;
+        loop {
+            tokio::select! {
+                result = &mut inner, if inner_result.is_none() => {
+                    inner_result = Some(result);
+                }
+                _ = flush_interval.tick() => {
+                    writer.flush()?;
+                }
+                event = inner_rx.recv() => {
+                    let Some(event) = event else { break };
+
+                    let recorded = RecordedEvent {
+                        timestamp_us: chrono::Utc::now().timestamp_micros(),
+                        source: source.clone(),
+                        event,
+                    };
+                    let mut line = serde_json::to_vec(&recorded)?;
+                    line.push(b'\n');
+                    writer.write_all(&line)?;
+
+                    if tx.send(recorded.event).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        writer.flush()?;
+        inner_result.unwrap_or(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::data_ingestor::PriceTick;
+
+    /// Emits a few ticks, then stays connected without sending more
+    struct TickSource;
+
+    #[async_trait]
+    impl MarketDataSource for TickSource {
+        fn name(&self) -> &str {
+            "ticks"
+        }
+
+        async fn run(&mut self, tx: mpsc::Sender<MarketDataMessage>) -> anyhow::Result<()> {
+            for i in 0..3 {
+                let tick = PriceTick {
+                    mint: "MINT".to_string(),
+                    price: 1.0 + i as f64,
+                    timestamp_ms: i,
+                };
+                tx.send(MarketDataMessage::PriceTick(tick)).await?;
+            }
+            std::future::pending().await
+        }
+    }
+
+    fn prices(events: &[MarketDataMessage]) -> Vec<f64> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                MarketDataMessage::PriceTick(tick) => Some(tick.price),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn aborted_recorder_keeps_buffered_events_for_replay() {
+        let path = std::env::temp_dir().join(format!("sniper-recorder-{}.ndjson", std::process::id()));
+        std::fs::remove_file(&path).ok();
+
+        let mut recorder = RecordingSource::new(Box::new(TickSource), &path);
+        let (tx, mut rx) = mpsc::channel(16);
+        let task = tokio::spawn(async move { recorder.run(tx).await });
+        let mut live = Vec::new();
+        for _ in 0..3 {
+            live.push(rx.recv().await.unwrap());
+        }
+
+        // Cancelled mid-stream, well inside the flush interval
+        task.abort();
+        assert!(task.await.unwrap_err().is_cancelled());
+
+        let recording = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(recording.lines().count(), 3);
+        let first: RecordedEvent = serde_json::from_str(recording.lines().next().unwrap()).unwrap();
+        assert_eq!(first.source, "ticks");
+
+        // A torn write and a blank line are skipped on replay
+        std::fs::write(&path, format!("{}\n{{\"timestamp_us\":1,\"sou\n", recording)).unwrap();
+        let mut replay = ReplaySource::new(&path, ReplaySpeed::AsFastAsPossible);
+        let (tx, mut rx) = mpsc::channel(16);
+        replay.run(tx).await.unwrap();
+        let mut replayed = Vec::new();
+        while let Some(event) = rx.recv().await {
+            replayed.push(event);
+        }
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(prices(&replayed), prices(&live));
+        assert_eq!(prices(&replayed), vec![1.0, 2.0, 3.0]);
+    }
+}
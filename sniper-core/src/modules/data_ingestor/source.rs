@@ -0,0 +1,141 @@
+// 🔌 Market Data Sources
+// Pluggable ingestion backends feeding the same typed events
+
+use async_trait::async_trait;
+use tokio::sync::mpsc;
+
+use super::events::MarketDataMessage;
+use super::replay::ReplaySpeed;
+use super::wallet_tracker::WSOL_MINT;
+use std::time::Duration;
+
+/// Raydium AMM v4
+pub const RAYDIUM_AMM_PROGRAM: &str = "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8";
+/// pump.fun bonding curve
+pub const PUMP_FUN_PROGRAM: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+
+/// A live or recorded stream of market events
+#[async_trait]
+pub trait MarketDataSource: Send {
+    /// Short identifier used in logs and metrics
+    fn name(&self) -> &str;
+
+    /// Stream events into `tx` until the source ends or the receiver is dropped
+    async fn run(&mut self, tx: mpsc::Sender<MarketDataMessage>) -> anyhow::Result<()>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceKind {
+    WebSocket,
+    Geyser,
+    Jupiter,
+    Replay,
+}
+
+impl SourceKind {
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "websocket" | "ws" => Ok(Self::WebSocket),
+            "geyser" | "grpc" => Ok(Self::Geyser),
+            "jupiter" => Ok(Self::Jupiter),
+            "replay" => Ok(Self::Replay),
+            other => anyhow::bail!("Unknown market data source: {}", other),
+        }
+    }
+}
+
+/// What to subscribe to, shared by all sources
+#[derive(Debug, Clone)]
+pub struct SourceFilter {
+    /// Programs whose accounts and transactions are streamed
+    pub programs: Vec<String>,
+    /// Extra accounts whose transactions are streamed (e.g. tracked wallets)
+    pub accounts: Vec<String>,
+    pub commitment: String,
+}
+
+impl SourceFilter {
+    /// Accounts to include in transaction subscriptions
+    pub fn transaction_accounts(&self) -> Vec<String> {
+        self.programs.iter().chain(self.accounts.iter()).cloned().collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DataSourceConfig {
+    /// Sources run concurrently and are merged; `Replay` must be the only one
+    pub kinds: Vec<SourceKind>,
+    pub ws_url: String,
+    pub geyser_url: String,
+    pub geyser_x_token: Option<String>,
+    pub jupiter_api_url: String,
+    pub jupiter_mints: Vec<String>,
+    /// Quote currency for Jupiter prices; SOL keeps prices comparable with position entries
+    pub jupiter_vs_token: String,
+    pub jupiter_interval: Duration,
+    pub replay_path: String,
+    pub replay_speed: ReplaySpeed,
+    /// When set, live events are also appended to this NDJSON file
+    pub record_path: Option<String>,
+    pub filter: SourceFilter,
+}
+
+impl DataSourceConfig {
+    pub fn from_env() -> anyhow::Result<Self> {
+        let kinds = std::env::var("MARKET_DATA_SOURCE")
+            .unwrap_or_else(|_| "websocket".to_string())
+            .split(',')
+            .filter(|k| !k.trim().is_empty())
+            .map(SourceKind::parse)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        if kinds.contains(&SourceKind::Replay) && kinds.len() > 1 {
+            anyhow::bail!("MARKET_DATA_SOURCE=replay cannot be combined with live sources");
+        }
+
+        Ok(Self {
+            kinds,
+            ws_url: std::env::var("HELIUS_WS_URL")
+                .unwrap_or_else(|_| "wss://atlas-mainnet.helius-rpc.com".to_string()),
+            geyser_url: std::env::var("GEYSER_GRPC_URL")
+                .unwrap_or_else(|_| "http://127.0.0.1:10000".to_string()),
+            geyser_x_token: std::env::var("GEYSER_X_TOKEN").ok().filter(|t| !t.is_empty()),
+            jupiter_api_url: std::env::var("JUPITER_PRICE_API_URL")
+                .unwrap_or_else(|_| "https://api.jup.ag".to_string()),
+            jupiter_mints: env_list("JUPITER_PRICE_MINTS", ""),
+            jupiter_vs_token: std::env::var("JUPITER_PRICE_VS_TOKEN")
+                .unwrap_or_else(|_| WSOL_MINT.to_string()),
+            jupiter_interval: Duration::from_millis(
+                std::env::var("JUPITER_POLL_INTERVAL_MS")
+                    .unwrap_or_else(|_| "1000".to_string())
+                    .parse()
+                    .unwrap_or(1000),
+            ),
+            replay_path: std::env::var("REPLAY_FILE")
+                .unwrap_or_else(|_| "recordings/market_data.ndjson".to_string()),
+            replay_speed: ReplaySpeed::parse(
+                &std::env::var("REPLAY_SPEED").unwrap_or_else(|_| "realtime".to_string()),
+            )?,
+            record_path: std::env::var("MARKET_DATA_RECORD_PATH").ok().filter(|p| !p.is_empty()),
+            filter: SourceFilter {
+                programs: env_list(
+                    "MARKET_DATA_PROGRAMS",
+                    &format!("{},{}", RAYDIUM_AMM_PROGRAM, PUMP_FUN_PROGRAM),
+                ),
+                accounts: Vec::new(),
+                commitment: std::env::var("SOLANA_COMMITMENT")
+                    .unwrap_or_else(|_| "confirmed".to_string()),
+            },
+        })
+    }
+}
+
+/// Comma-separated env var as a list, skipping empty entries
+fn env_list(name: &str, default: &str) -> Vec<String> {
+    std::env::var(name)
+        .unwrap_or_else(|_| default.to_string())
+        .split(',')
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
@@ -0,0 +1,61 @@
+// 🔁 Swap Decoding
+// Token/SOL swaps recovered from any transaction's balance deltas
+
+use std::collections::HashMap;
+
+use super::events::{SwapEvent, TransactionUpdate};
+use super::wallet_tracker::{sol_delta, TradeSide, WSOL_MINT};
+
+/// SOL moves smaller than this are fees and rent, not a swap leg
+const MIN_SWAP_SOL: f64 = 0.000_1;
+
+/// Swaps made by the fee payer; one per non-SOL mint whose balance moved against SOL
+///
+/// Multi-hop routes through other tokens are reported per mint with the full SOL leg.
+pub fn decode_swaps(tx: &TransactionUpdate) -> Vec<SwapEvent> {
+    let Some(trader) = tx.account_keys.first().filter(|_| tx.success) else {
+        return Vec::new();
+    };
+
+    let sol_amount = sol_delta(trader, tx);
+    if sol_amount.abs() < MIN_SWAP_SOL {
+        return Vec::new();
+    }
+
+    // mint -> (pre, post, decimals) for token accounts owned by the trader
+    let mut deltas: HashMap<&str, (i128, i128, u8)> = HashMap::new();
+    for balance in tx.pre_token_balances.iter().filter(|b| b.owner == *trader) {
+        let entry = deltas.entry(balance.mint.as_str()).or_insert((0, 0, balance.decimals));
+        entry.0 += balance.amount as i128;
+    }
+    for balance in tx.post_token_balances.iter().filter(|b| b.owner == *trader) {
+        let entry = deltas.entry(balance.mint.as_str()).or_insert((0, 0, balance.decimals));
+        entry.1 += balance.amount as i128;
+        entry.2 = balance.decimals;
+    }
+
+    deltas
+        .into_iter()
+        .filter(|(mint, _)| *mint != WSOL_MINT)
+        .filter_map(|(mint, (pre, post, decimals))| {
+            let delta = post - pre;
+            // Tokens in must be paid with SOL out, and vice versa
+            let side = match (delta > 0, sol_amount < 0.0) {
+                (true, true) => TradeSide::Buy,
+                (false, false) if delta < 0 => TradeSide::Sell,
+                _ => return None,
+            };
+
+            Some(SwapEvent {
+                signature: tx.signature.clone(),
+                slot: tx.slot,
+                block_time: tx.block_time,
+                trader: trader.clone(),
+                mint: mint.to_string(),
+                side,
+                token_amount: delta.unsigned_abs() as f64 / 10f64.powi(decimals as i32),
+                sol_amount: sol_amount.abs(),
+            })
+        })
+        .collect()
+}
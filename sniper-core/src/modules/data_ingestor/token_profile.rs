@@ -0,0 +1,512 @@
+// 🪪 Token Profiles
+// Mint, token metadata, Token-2022 extensions and holder distribution
+
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::str::FromStr;
+use tracing::{debug, warn};
+
+use super::pools::{find_raydium_pools, RaydiumAmmPool};
+use super::source::PUMP_FUN_PROGRAM;
+use super::wallet_tracker::WSOL_MINT;
+
+pub const TOKEN_2022_PROGRAM: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+pub const METADATA_PROGRAM: &str = "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s";
+
+/// Base SPL mint layout size; Token-2022 extensions start after the account type byte
+const MINT_LEN: usize = 82;
+const TOKEN_2022_ACCOUNT_TYPE_OFFSET: usize = 165;
+const ACCOUNT_TYPE_MINT: u8 = 1;
+
+// Token-2022 extension type discriminators
+const EXT_TRANSFER_FEE_CONFIG: u16 = 1;
+const EXT_PERMANENT_DELEGATE: u16 = 12;
+const EXT_TRANSFER_HOOK: u16 = 14;
+const EXT_TOKEN_METADATA: u16 = 19;
+
+/// Offset of the `complete` flag in a pump.fun bonding curve account
+const BONDING_CURVE_COMPLETE_OFFSET: usize = 48;
+
+/// Everything the risk manager needs to judge a mint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenProfile {
+    pub mint: String,
+    pub token_program: String,
+    pub supply: u64,
+    pub decimals: u8,
+    pub mint_authority: Option<String>,
+    pub freeze_authority: Option<String>,
+    pub metadata: Option<TokenMetadata>,
+    pub extensions: TokenExtensions,
+    /// `None` when the largest-accounts lookup failed
+    #[serde(default)]
+    pub holders: Option<HolderDistribution>,
+    /// Raydium AMM v4 liquidity, if the mint has a pool there
+    #[serde(default)]
+    pub liquidity: Option<LiquidityProfile>,
+    /// Still trading on the pump.fun bonding curve, so there is no LP to pull yet
+    #[serde(default)]
+    pub on_bonding_curve: bool,
+    pub fetched_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub update_authority: String,
+    pub is_mutable: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TokenExtensions {
+    /// Current transfer fee in basis points
+    pub transfer_fee_bps: Option<u16>,
+    /// Program invoked on every transfer
+    pub transfer_hook_program: Option<String>,
+    /// Account allowed to move or burn any holder's tokens
+    pub permanent_delegate: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HolderDistribution {
+    /// Largest token accounts (up to 20), as (address, share of supply)
+    pub top_holders: Vec<(String, f64)>,
+    pub top1_share: f64,
+    pub top10_share: f64,
+}
+
+/// LP token state of the mint's main pool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiquidityProfile {
+    pub pool: String,
+    pub quote_mint: String,
+    pub lp_mint: String,
+    pub lp_supply: u64,
+    pub lp_reserve: u64,
+    /// Share of minted LP that has been burned
+    pub burned_share: f64,
+    /// Share of minted LP held by program-owned (off-curve) accounts, i.e. lockers
+    pub locked_share: f64,
+}
+
+impl LiquidityProfile {
+    /// Share of LP the deployer can no longer withdraw
+    pub fn secured_share(&self) -> f64 {
+        (self.burned_share + self.locked_share).min(1.0)
+    }
+}
+
+/// Fetches token profiles over RPC
+pub struct TokenProfiler {
+    rpc: RpcClient,
+}
+
+impl TokenProfiler {
+    pub fn new(rpc_url: String) -> Self {
+        Self {
+            rpc: RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed()),
+        }
+    }
+
+    pub async fn fetch(&self, mint: &str) -> anyhow::Result<TokenProfile> {
+        let mint_pubkey = Pubkey::from_str(mint)?;
+        let account = self.rpc.get_account(&mint_pubkey).await?;
+        let token_program = account.owner.to_string();
+
+        if account.owner != spl_token::id() && token_program != TOKEN_2022_PROGRAM {
+            anyhow::bail!("{} is not a token mint (owner {})", mint, token_program);
+        }
+
+        let base = parse_mint(&account.data)
+            .ok_or_else(|| anyhow::anyhow!("Invalid mint account data for {}", mint))?;
+        let extensions = if token_program == TOKEN_2022_PROGRAM {
+            parse_extensions(&account.data)
+        } else {
+            TokenExtensions::default()
+        };
+
+        // Token-2022 mints may carry their metadata in the mint itself
+        let embedded = if token_program == TOKEN_2022_PROGRAM {
+            parse_token_metadata(&account.data)
+        } else {
+            None
+        };
+        let metadata = match embedded {
+            Some(metadata) => Some(metadata),
+            None => match self.fetch_metadata(&mint_pubkey).await {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    debug!("🪪 No Metaplex metadata for {}: {}", mint, e);
+                    None
+                }
+            },
+        };
+
+        let pool = match find_raydium_pools(&self.rpc, mint).await {
+            Ok(pools) => main_pool(mint, pools),
+            Err(e) => {
+                debug!("🪪 Raydium pool lookup failed for {}: {}", mint, e);
+                None
+            }
+        };
+
+        let liquidity = match &pool {
+            Some(pool) => match self.fetch_liquidity(mint, pool).await {
+                Ok(liquidity) => Some(liquidity),
+                Err(e) => {
+                    debug!("🪪 LP lookup failed for {}: {}", mint, e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let on_bonding_curve = match self.rpc.get_account(&bonding_curve(&mint_pubkey)).await {
+            Ok(curve) => curve.data.get(BONDING_CURVE_COMPLETE_OFFSET) == Some(&0),
+            Err(_) => false,
+        };
+
+        // Pool vaults and the pump.fun bonding curve hold supply on behalf of traders
+        let mut excluded = vec![bonding_curve_token_account(&mint_pubkey).to_string()];
+        if let Some(pool) = &pool {
+            excluded.push(pool.base_vault.clone());
+            excluded.push(pool.quote_vault.clone());
+        }
+
+        // Unknown concentration must not read as 0%, so keep the failure visible
+        let holders = match self.fetch_holders(&mint_pubkey, base.supply, &excluded).await {
+            Ok(holders) => Some(holders),
+            Err(e) => {
+                warn!("⚠️ Holder lookup failed for {}: {}", mint, e);
+                None
+            }
+        };
+
+        Ok(TokenProfile {
+            mint: mint.to_string(),
+            token_program,
+            supply: base.supply,
+            decimals: base.decimals,
+            mint_authority: base.mint_authority,
+            freeze_authority: base.freeze_authority,
+            metadata,
+            extensions,
+            holders,
+            liquidity,
+            on_bonding_curve,
+            fetched_at: chrono::Utc::now().timestamp(),
+        })
+    }
+
+    /// Metaplex metadata of `mint`, if it has any
+    pub async fn fetch_metadata(&self, mint: &Pubkey) -> anyhow::Result<Option<TokenMetadata>> {
+        let program = Pubkey::from_str(METADATA_PROGRAM)?;
+        let (pda, _) = Pubkey::find_program_address(
+            &[b"metadata", program.as_ref(), mint.as_ref()],
+            &program,
+        );
+
+        let accounts = self.rpc.get_multiple_accounts(&[pda]).await?;
+        Ok(accounts
+            .into_iter()
+            .next()
+            .flatten()
+            .and_then(|account| parse_metadata(&account.data)))
+    }
+
+    async fn fetch_liquidity(&self, mint: &str, pool: &RaydiumAmmPool) -> anyhow::Result<LiquidityProfile> {
+        let lp_mint = Pubkey::from_str(&pool.lp_mint)?;
+        let lp_account = self.rpc.get_account(&lp_mint).await?;
+        let lp_supply = parse_mint(&lp_account.data)
+            .ok_or_else(|| anyhow::anyhow!("Invalid LP mint {}", pool.lp_mint))?
+            .supply;
+
+        // Raydium's lp_reserve is not reduced by burns, so the gap to supply is burned LP
+        let burned_share = if pool.lp_reserve == 0 {
+            0.0
+        } else {
+            1.0 - (lp_supply as f64 / pool.lp_reserve as f64).min(1.0)
+        };
+
+        let mut locked_amount = 0u64;
+        if lp_supply > 0 {
+            let largest = self.rpc.get_token_largest_accounts(&lp_mint).await?;
+            let addresses: Vec<Pubkey> = largest
+                .iter()
+                .filter_map(|balance| Pubkey::from_str(&balance.address).ok())
+                .collect();
+            let accounts = self.rpc.get_multiple_accounts(&addresses).await?;
+
+            for (balance, account) in largest.iter().zip(accounts) {
+                // Token account layout: mint (32), owner (32), amount (8)
+                let Some(owner) = account.as_ref().and_then(|a| a.data.get(32..64)) else {
+                    continue;
+                };
+                let owner = Pubkey::new_from_array(owner.try_into().unwrap());
+                if !owner.is_on_curve() {
+                    locked_amount += balance.amount.amount.parse::<u64>().unwrap_or(0);
+                }
+            }
+        }
+
+        let locked_share = if pool.lp_reserve == 0 {
+            0.0
+        } else {
+            locked_amount as f64 / pool.lp_reserve as f64
+        };
+
+        debug!(
+            "🪪 {} LP: {:.1}% burned, {:.1}% locked (pool {})",
+            mint,
+            burned_share * 100.0,
+            locked_share * 100.0,
+            pool.address
+        );
+
+        Ok(LiquidityProfile {
+            pool: pool.address.clone(),
+            quote_mint: pool.other_mint(mint).unwrap_or_default().to_string(),
+            lp_mint: pool.lp_mint.clone(),
+            lp_supply,
+            lp_reserve: pool.lp_reserve,
+            burned_share,
+            locked_share,
+        })
+    }
+
+    async fn fetch_holders(
+        &self,
+        mint: &Pubkey,
+        supply: u64,
+        excluded: &[String],
+    ) -> anyhow::Result<HolderDistribution> {
+        let largest = self.rpc.get_token_largest_accounts(mint).await?;
+        if supply == 0 {
+            return Ok(HolderDistribution::default());
+        }
+
+        let top_holders: Vec<(String, f64)> = largest
+            .into_iter()
+            .filter(|balance| !excluded.contains(&balance.address))
+            .filter_map(|balance| {
+                let amount: u64 = balance.amount.amount.parse().ok()?;
+                Some((balance.address, amount as f64 / supply as f64))
+            })
+            .collect();
+
+        Ok(HolderDistribution {
+            top1_share: top_holders.first().map(|(_, share)| *share).unwrap_or(0.0),
+            top10_share: top_holders.iter().take(10).map(|(_, share)| share).sum(),
+            top_holders,
+        })
+    }
+}
+
+/// Prefer the SOL-quoted pool, falling back to any pool for the mint
+fn main_pool(mint: &str, pools: Vec<RaydiumAmmPool>) -> Option<RaydiumAmmPool> {
+    let sol_pool = pools.iter().position(|pool| pool.other_mint(mint) == Some(WSOL_MINT));
+    match sol_pool {
+        Some(index) => pools.into_iter().nth(index),
+        None => pools.into_iter().next(),
+    }
+}
+
+/// pump.fun bonding curve account for `mint`
+fn bonding_curve(mint: &Pubkey) -> Pubkey {
+    let program = Pubkey::from_str(PUMP_FUN_PROGRAM).unwrap();
+    Pubkey::find_program_address(&[b"bonding-curve", mint.as_ref()], &program).0
+}
+
+/// Token account of the pump.fun bonding curve for `mint`
+fn bonding_curve_token_account(mint: &Pubkey) -> Pubkey {
+    spl_associated_token_account::get_associated_token_address(&bonding_curve(mint), mint)
+}
+
+struct MintBase {
+    mint_authority: Option<String>,
+    supply: u64,
+    decimals: u8,
+    freeze_authority: Option<String>,
+}
+
+/// SPL mint layout: COption<Pubkey>, u64 supply, u8 decimals, bool, COption<Pubkey>
+fn parse_mint(data: &[u8]) -> Option<MintBase> {
+    if data.len() < MINT_LEN {
+        return None;
+    }
+
+    let mut reader = Reader::new(data);
+    let mint_authority = reader.coption_pubkey()?;
+    let supply = reader.u64()?;
+    let decimals = reader.u8()?;
+    let _is_initialized = reader.u8()?;
+    let freeze_authority = reader.coption_pubkey()?;
+
+    Some(MintBase {
+        mint_authority,
+        supply,
+        decimals,
+        freeze_authority,
+    })
+}
+
+/// Token-2022 TLV extensions: [type u16][length u16][value]
+fn tlv_entries(data: &[u8]) -> Vec<(u16, &[u8])> {
+    let mut entries = Vec::new();
+    if data.get(TOKEN_2022_ACCOUNT_TYPE_OFFSET) != Some(&ACCOUNT_TYPE_MINT) {
+        return entries;
+    }
+
+    let mut offset = TOKEN_2022_ACCOUNT_TYPE_OFFSET + 1;
+    while offset + 4 <= data.len() {
+        let ext_type = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let len = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        let start = offset + 4;
+        let Some(value) = data.get(start..start + len) else {
+            break;
+        };
+        if ext_type == 0 {
+            break;
+        }
+
+        entries.push((ext_type, value));
+        offset = start + len;
+    }
+
+    entries
+}
+
+fn parse_extensions(data: &[u8]) -> TokenExtensions {
+    let mut extensions = TokenExtensions::default();
+
+    for (ext_type, value) in tlv_entries(data) {
+        let len = value.len();
+        match ext_type {
+            // authority, withdraw authority, withheld u64, older fee, newer fee (epoch, max, bps)
+            EXT_TRANSFER_FEE_CONFIG if len >= 108 => {
+                extensions.transfer_fee_bps = Some(u16::from_le_bytes([value[106], value[107]]));
+            }
+            EXT_PERMANENT_DELEGATE if len >= 32 => {
+                extensions.permanent_delegate = nonzero_pubkey(&value[..32]);
+            }
+            // authority, program id
+            EXT_TRANSFER_HOOK if len >= 64 => {
+                extensions.transfer_hook_program = nonzero_pubkey(&value[32..64]);
+            }
+            _ => {}
+        }
+    }
+
+    extensions
+}
+
+/// Token-2022 `TokenMetadata` extension: update authority, mint, name, symbol, uri,
+/// additional fields; it stays mutable while the update authority is set
+fn parse_token_metadata(data: &[u8]) -> Option<TokenMetadata> {
+    let (_, value) = tlv_entries(data)
+        .into_iter()
+        .find(|(ext_type, _)| *ext_type == EXT_TOKEN_METADATA)?;
+
+    let mut reader = Reader::new(value);
+    let update_authority = nonzero_pubkey(reader.bytes(32)?);
+    let _mint = reader.pubkey()?;
+    let name = reader.string()?;
+    let symbol = reader.string()?;
+    let uri = reader.string()?;
+
+    Some(TokenMetadata {
+        name,
+        symbol,
+        uri,
+        is_mutable: update_authority.is_some(),
+        update_authority: update_authority.unwrap_or_default(),
+    })
+}
+
+/// Metaplex `Metadata` account (borsh): key, update authority, mint, name, symbol, uri,
+/// seller fee, creators, primary sale, is_mutable
+fn parse_metadata(data: &[u8]) -> Option<TokenMetadata> {
+    let mut reader = Reader::new(data);
+    let _key = reader.u8()?;
+    let update_authority = reader.pubkey()?;
+    let _mint = reader.pubkey()?;
+    let name = reader.string()?;
+    let symbol = reader.string()?;
+    let uri = reader.string()?;
+    let _seller_fee_basis_points = reader.bytes(2)?;
+
+    if reader.u8()? == 1 {
+        let creators = reader.u32()? as usize;
+        // address (32) + verified (1) + share (1)
+        reader.bytes(creators * 34)?;
+    }
+
+    let _primary_sale_happened = reader.u8()?;
+    let is_mutable = reader.u8()? == 1;
+
+    Some(TokenMetadata {
+        name,
+        symbol,
+        uri,
+        update_authority,
+        is_mutable,
+    })
+}
+
+fn nonzero_pubkey(bytes: &[u8]) -> Option<String> {
+    if bytes.iter().all(|b| *b == 0) {
+        None
+    } else {
+        Some(bs58::encode(bytes).into_string())
+    }
+}
+
+/// Minimal little-endian cursor over account data
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let slice = self.data.get(self.offset..self.offset + len)?;
+        self.offset += len;
+        Some(slice)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.bytes(1).map(|b| b[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.bytes(4).map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.bytes(8).map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    fn pubkey(&mut self) -> Option<String> {
+        self.bytes(32).map(|b| bs58::encode(b).into_string())
+    }
+
+    fn coption_pubkey(&mut self) -> Option<Option<String>> {
+        let tag = self.u32()?;
+        let key = self.bytes(32)?;
+        Some(if tag == 1 { Some(bs58::encode(key).into_string()) } else { None })
+    }
+
+    /// Borsh string, with Metaplex's NUL padding stripped
+    fn string(&mut self) -> Option<String> {
+        let len = self.u32()? as usize;
+        let bytes = self.bytes(len)?;
+        Some(String::from_utf8_lossy(bytes).trim_end_matches('\0').trim().to_string())
+    }
+}
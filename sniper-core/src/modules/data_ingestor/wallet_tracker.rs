@@ -0,0 +1,308 @@
+// 👛 Wallet Tracker
+// Smart-money wallet tracking from token balance deltas
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use super::events::{TokenBalance, TransactionUpdate};
+
+/// Wrapped SOL mint - treated as the quote side, never reported as activity
+pub const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+/// Default smallest SOL leg counted as a trade; above the rent of a new token account
+const DEFAULT_MIN_SOL_DELTA: f64 = 0.01;
+
+#[derive(Debug, Clone)]
+pub struct WalletTrackerConfig {
+    pub wallets: Vec<String>,
+    pub min_sol_delta: f64,
+}
+
+impl WalletTrackerConfig {
+    pub fn from_env() -> Self {
+        let wallets = std::env::var("TRACKED_WALLETS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|w| w.trim().to_string())
+            .filter(|w| !w.is_empty())
+            .collect();
+
+        Self {
+            wallets,
+            min_sol_delta: std::env::var("TRACKED_WALLET_MIN_SOL")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_MIN_SOL_DELTA),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
+/// Buy or sell by a tracked wallet, derived from one transaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletActivity {
+    pub wallet: String,
+    pub signature: String,
+    pub slot: u64,
+    pub mint: String,
+    pub side: TradeSide,
+    pub token_amount: f64,
+    pub token_amount_raw: i128,
+    pub decimals: u8,
+    pub sol_amount: f64,
+    pub block_time: Option<i64>,
+}
+
+/// Tracks a configured set of wallets and decodes their swaps
+pub struct WalletTracker {
+    config: WalletTrackerConfig,
+    wallets: HashSet<String>,
+}
+
+impl WalletTracker {
+    pub fn new(config: WalletTrackerConfig) -> Self {
+        let wallets = config.wallets.iter().cloned().collect();
+        Self { config, wallets }
+    }
+
+    pub fn wallets(&self) -> &[String] {
+        &self.config.wallets
+    }
+
+    /// Decode tracked wallet buys and sells from a transaction's balance deltas
+    ///
+    /// A trade moves exactly one token against SOL in the opposite direction. Transfers and
+    /// airdrops (no SOL leg) and multi-token transactions (no way to split the SOL) are skipped.
+    pub fn parse_transaction(&self, tx: &TransactionUpdate) -> Vec<WalletActivity> {
+        if !tx.success {
+            return Vec::new();
+        }
+
+        // (owner, mint) -> (pre, post, decimals)
+        let mut deltas: HashMap<(&str, &str), (i128, i128, u8)> = HashMap::new();
+        for balance in &tx.pre_token_balances {
+            let entry = deltas
+                .entry((balance.owner.as_str(), balance.mint.as_str()))
+                .or_insert((0, 0, balance.decimals));
+            entry.0 += balance.amount as i128;
+        }
+        for balance in &tx.post_token_balances {
+            let entry = deltas
+                .entry((balance.owner.as_str(), balance.mint.as_str()))
+                .or_insert((0, 0, balance.decimals));
+            entry.1 += balance.amount as i128;
+            entry.2 = balance.decimals;
+        }
+
+        // owner -> (mint, token delta, decimals) of every token that moved
+        let mut moved: HashMap<&str, Vec<(&str, i128, u8)>> = HashMap::new();
+        for ((owner, mint), (pre_amount, post_amount, decimals)) in deltas {
+            let delta = post_amount - pre_amount;
+            if mint != WSOL_MINT && delta != 0 && self.wallets.contains(owner) {
+                moved.entry(owner).or_default().push((mint, delta, decimals));
+            }
+        }
+
+        let mut activities = Vec::new();
+        for (owner, tokens) in moved {
+            let [(mint, delta, decimals)] = tokens[..] else {
+                continue;
+            };
+
+            let sol = sol_delta(owner, tx);
+            let side = match (delta > 0, sol < 0.0) {
+                (true, true) => TradeSide::Buy,
+                (false, false) if sol > 0.0 => TradeSide::Sell,
+                _ => continue,
+            };
+            let sol_amount = sol.abs();
+            if sol_amount < self.config.min_sol_delta {
+                continue;
+            }
+
+            activities.push(WalletActivity {
+                wallet: owner.to_string(),
+                signature: tx.signature.clone(),
+                slot: tx.slot,
+                mint: mint.to_string(),
+                side,
+                token_amount: delta.unsigned_abs() as f64 / 10f64.powi(decimals as i32),
+                token_amount_raw: delta.abs(),
+                decimals,
+                sol_amount,
+                block_time: tx.block_time,
+            });
+        }
+
+        activities
+    }
+}
+
+/// Net SOL change for a wallet: native lamports plus wrapped SOL it owns, before the network fee
+pub fn sol_delta(wallet: &str, tx: &TransactionUpdate) -> f64 {
+    let wsol = |balances: &[TokenBalance]| -> i128 {
+        balances
+            .iter()
+            .filter(|b| b.owner == wallet && b.mint == WSOL_MINT)
+            .map(|b| b.amount as i128)
+            .sum()
+    };
+    let fee = if tx.account_keys.first().is_some_and(|payer| payer == wallet) {
+        tx.fee as i128
+    } else {
+        0
+    };
+    let lamports = tx.lamport_delta(wallet)
+        + fee
+        + wsol(&tx.post_token_balances)
+        - wsol(&tx.pre_token_balances);
+
+    lamports as f64 / LAMPORTS_PER_SOL
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WALLET: &str = "Wallet1111111111111111111111111111111111111";
+    const OTHER: &str = "Other11111111111111111111111111111111111111";
+    const MINT: &str = "Mint111111111111111111111111111111111111111";
+    const MINT_B: &str = "MintB11111111111111111111111111111111111111";
+    const FEE: u64 = 5_000;
+
+    fn tracker() -> WalletTracker {
+        WalletTracker::new(WalletTrackerConfig {
+            wallets: vec![WALLET.to_string()],
+            min_sol_delta: DEFAULT_MIN_SOL_DELTA,
+        })
+    }
+
+    fn balance(account_index: u32, mint: &str, amount: u64) -> TokenBalance {
+        TokenBalance {
+            account_index,
+            mint: mint.to_string(),
+            owner: WALLET.to_string(),
+            amount,
+            decimals: 6,
+        }
+    }
+
+    /// Transaction paid by the tracked wallet; `lamports` is its change before the fee
+    fn transaction(lamports: i64, pre: Vec<TokenBalance>, post: Vec<TokenBalance>) -> TransactionUpdate {
+        let start = 10_000_000_000u64;
+        TransactionUpdate {
+            signature: "sig".to_string(),
+            slot: 1,
+            block_time: None,
+            success: true,
+            fee: FEE,
+            account_keys: vec![WALLET.to_string(), OTHER.to_string()],
+            instructions: Vec::new(),
+            pre_balances: vec![start, 0],
+            post_balances: vec![(start as i64 + lamports) as u64 - FEE, 0],
+            pre_token_balances: pre,
+            post_token_balances: post,
+            log_messages: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn buy_pays_sol_for_tokens() {
+        let tx = transaction(-500_000_000, vec![], vec![balance(2, MINT, 1_000_000_000)]);
+        let activities = tracker().parse_transaction(&tx);
+
+        assert_eq!(activities.len(), 1);
+        let buy = &activities[0];
+        assert_eq!(buy.side, TradeSide::Buy);
+        assert_eq!(buy.mint, MINT);
+        assert_eq!(buy.token_amount_raw, 1_000_000_000);
+        assert_eq!(buy.token_amount, 1_000.0);
+        // The network fee is not part of the price paid
+        assert_eq!(buy.sol_amount, 0.5);
+    }
+
+    #[test]
+    fn sell_receives_sol_for_tokens() {
+        let tx = transaction(
+            250_000_000,
+            vec![balance(2, MINT, 1_000_000_000)],
+            vec![balance(2, MINT, 400_000_000)],
+        );
+        let activities = tracker().parse_transaction(&tx);
+
+        assert_eq!(activities.len(), 1);
+        assert_eq!(activities[0].side, TradeSide::Sell);
+        assert_eq!(activities[0].token_amount_raw, 600_000_000);
+        assert_eq!(activities[0].sol_amount, 0.25);
+    }
+
+    #[test]
+    fn wrapped_sol_counts_toward_the_sol_leg() {
+        let tx = transaction(
+            0,
+            vec![balance(3, WSOL_MINT, 2_000_000_000)],
+            vec![balance(2, MINT, 5_000_000), balance(3, WSOL_MINT, 1_000_000_000)],
+        );
+        let activities = tracker().parse_transaction(&tx);
+
+        assert_eq!(activities.len(), 1);
+        assert_eq!(activities[0].side, TradeSide::Buy);
+        assert_eq!(activities[0].sol_amount, 1.0);
+    }
+
+    #[test]
+    fn airdrop_and_transfer_are_not_trades() {
+        // Tokens arrive, only the fee leaves
+        let airdrop = transaction(0, vec![], vec![balance(2, MINT, 1_000_000)]);
+        assert!(tracker().parse_transaction(&airdrop).is_empty());
+
+        // Tokens arrive and the wallet pays rent for the new token account
+        let claim = transaction(-2_039_280, vec![], vec![balance(2, MINT, 1_000_000)]);
+        assert!(tracker().parse_transaction(&claim).is_empty());
+
+        // Tokens leave, nothing comes back
+        let transfer = transaction(0, vec![balance(2, MINT, 1_000_000)], vec![balance(2, MINT, 0)]);
+        assert!(tracker().parse_transaction(&transfer).is_empty());
+
+        // Tokens and SOL both leave
+        let gift = transaction(-1_000_000_000, vec![balance(2, MINT, 1_000_000)], vec![balance(2, MINT, 0)]);
+        assert!(tracker().parse_transaction(&gift).is_empty());
+    }
+
+    #[test]
+    fn multi_token_transactions_are_skipped() {
+        let tx = transaction(
+            -1_000_000_000,
+            vec![],
+            vec![balance(2, MINT, 1_000_000), balance(4, MINT_B, 2_000_000)],
+        );
+        assert!(tracker().parse_transaction(&tx).is_empty());
+    }
+
+    #[test]
+    fn untracked_and_failed_transactions_are_ignored() {
+        let mut tx = transaction(-500_000_000, vec![], vec![balance(2, MINT, 1_000_000)]);
+        for balance in &mut tx.post_token_balances {
+            balance.owner = OTHER.to_string();
+        }
+        assert!(tracker().parse_transaction(&tx).is_empty());
+
+        let mut failed = transaction(-500_000_000, vec![], vec![balance(2, MINT, 1_000_000)]);
+        failed.success = false;
+        assert!(tracker().parse_transaction(&failed).is_empty());
+    }
+
+    #[test]
+    fn sol_delta_adds_back_the_fee_only_for_the_payer() {
+        let tx = transaction(-100_000_000, vec![], vec![]);
+        assert_eq!(sol_delta(WALLET, &tx), -0.1);
+        assert_eq!(sol_delta(OTHER, &tx), 0.0);
+    }
+}
@@ -0,0 +1,160 @@
+// 🌐 Helius WebSocket Source
+// programSubscribe + transactionSubscribe over a single WebSocket
+
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::Value;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tracing::{debug, error, info, warn};
+
+use super::events::{AccountUpdate, MarketDataMessage, TransactionUpdate};
+use super::source::{MarketDataSource, SourceFilter};
+
+pub struct WebSocketSource {
+    ws_url: String,
+    filter: SourceFilter,
+}
+
+impl WebSocketSource {
+    pub fn new(ws_url: String, filter: SourceFilter) -> Self {
+        Self { ws_url, filter }
+    }
+
+    fn subscribe_requests(&self) -> Vec<Value> {
+        let mut requests: Vec<Value> = self
+            .filter
+            .programs
+            .iter()
+            .enumerate()
+            .map(|(i, program)| {
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": i + 1,
+                    "method": "programSubscribe",
+                    "params": [
+                        program,
+                        { "commitment": self.filter.commitment, "encoding": "base64" }
+                    ]
+                })
+            })
+            .collect();
+
+        requests.push(serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": requests.len() + 1,
+            "method": "transactionSubscribe",
+            "params": [
+                {
+                    "accountInclude": self.filter.transaction_accounts(),
+                    "vote": false,
+                    "failed": false
+                },
+                {
+                    "commitment": self.filter.commitment,
+                    "encoding": "json",
+                    "transactionDetails": "full",
+                    "maxSupportedTransactionVersion": 0
+                }
+            ]
+        }));
+
+        requests
+    }
+
+    /// Single connection lifetime; returns when the socket closes
+    async fn stream(&self, tx: &mpsc::Sender<MarketDataMessage>) -> anyhow::Result<()> {
+        let (mut ws, _) = connect_async(self.ws_url.as_str()).await?;
+        for request in self.subscribe_requests() {
+            ws.send(Message::Text(request.to_string())).await?;
+        }
+
+        info!("🔗 Helius WebSocket subscribed: {} programs, {} accounts",
+              self.filter.programs.len(), self.filter.accounts.len());
+
+        while let Some(message) = ws.next().await {
+            let text = match message? {
+                Message::Text(text) => text,
+                Message::Ping(payload) => {
+                    ws.send(Message::Pong(payload)).await?;
+                    continue;
+                }
+                Message::Close(_) => break,
+                _ => continue,
+            };
+
+            let notification: Value = match serde_json::from_str(&text) {
+                Ok(value) => value,
+                Err(e) => {
+                    warn!("⚠️ Invalid WebSocket message: {}", e);
+                    continue;
+                }
+            };
+
+            if let Some(event) = parse_notification(&notification)? {
+                tx.send(event).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Map a subscription notification to a typed event
+fn parse_notification(notification: &Value) -> anyhow::Result<Option<MarketDataMessage>> {
+    let result = &notification["params"]["result"];
+
+    let event = match notification["method"].as_str() {
+        Some("programNotification") => {
+            let slot = result["context"]["slot"].as_u64().unwrap_or(0);
+            AccountUpdate::from_json(slot, &result["value"]).map(MarketDataMessage::AccountUpdate)
+        }
+        Some("transactionNotification") => {
+            let slot = result["slot"].as_u64().unwrap_or(0);
+            let signature = result["signature"].as_str().unwrap_or_default();
+            TransactionUpdate::from_json(signature, slot, &result["transaction"])
+                .map(MarketDataMessage::Transaction)
+        }
+        _ => {
+            if let Some(err) = notification.get("error") {
+                anyhow::bail!("Subscription rejected: {}", err);
+            }
+            debug!("🔗 Subscription ack: {}", notification);
+            None
+        }
+    };
+
+    Ok(event)
+}
+
+#[async_trait]
+impl MarketDataSource for WebSocketSource {
+    fn name(&self) -> &str {
+        "helius_ws"
+    }
+
+    async fn run(&mut self, tx: mpsc::Sender<MarketDataMessage>) -> anyhow::Result<()> {
+        info!("🔗 Connecting to Helius WebSocket: {}", self.ws_url);
+
+        // Reconnect with exponential backoff, capped at 30s
+        let mut backoff = Duration::from_millis(500);
+        loop {
+            match self.stream(&tx).await {
+                Ok(()) => {
+                    warn!("🔗 Helius WebSocket closed, reconnecting");
+                    backoff = Duration::from_millis(500);
+                }
+                Err(e) => {
+                    error!("❌ Helius WebSocket failed: {} (retry in {:?})", e, backoff);
+                    backoff = (backoff * 2).min(Duration::from_secs(30));
+                }
+            }
+
+            if tx.is_closed() {
+                return Ok(());
+            }
+            tokio::time::sleep(backoff).await;
+        }
+    }
+}
@@ -1,47 +1,143 @@
 // ⚡ Executor Module
 // Ultra-fast Solana transaction execution
 
-use tokio::sync::mpsc;
-use tracing::{info, error, debug};
-use solana_client::rpc_client::RpcClient;
+pub mod arbitrage;
+pub mod jupiter;
+
+use serde::Serialize;
+use tokio::sync::{mpsc, oneshot};
+use tracing::{info, error, debug, warn};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_request::RpcRequest;
 use solana_sdk::{
     commitment_config::CommitmentConfig,
+    message::{v0, VersionedMessage},
+    native_token::LAMPORTS_PER_SOL,
     pubkey::Pubkey,
     signature::Keypair,
     signer::Signer,
-    transaction::Transaction,
+    transaction::{Transaction, VersionedTransaction},
 };
 use spl_token::instruction as spl_instruction;
-use spl_associated_token_account;
+use std::collections::{HashMap, VecDeque};
 use std::str::FromStr;
 use std::time::Instant;
 
+use super::data_ingestor::events::{TokenBalance, TransactionUpdate};
+use super::data_ingestor::wallet_tracker::{sol_delta, WSOL_MINT};
+use super::data_ingestor::TradeSide;
+use arbitrage::ArbitrageOrder;
+use jupiter::{load_lookup_tables, JupiterClient};
+
 pub struct Executor {
-    // TODO: Add channels when ready
-    // execution_rx: mpsc::Receiver<ExecutionMessage>,
+    execution_rx: mpsc::Receiver<ExecutionMessage>,
     solana_client: RpcClient,
     keypair: Keypair,
+    /// Strategy wallets by public key, from STRATEGY_WALLET_KEYS
+    strategy_wallets: HashMap<Pubkey, Keypair>,
     execution_stats: ExecutionStats,
+    jupiter: JupiterClient,
+    /// Orders received but not yet started, executed by priority
+    pending_orders: VecDeque<OrderRequest>,
+    /// Quote only, never send transactions (TRADING_MODE != "live")
+    paper_trading: bool,
 }
 
 #[derive(Debug, Clone)]
 pub enum OrderType {
     Market,
+    #[allow(dead_code)]
     Limit,
+    #[allow(dead_code)]
     StopMarket,
+    #[allow(dead_code)]
     StopLimit,
+    /// Atomic buy-then-sell across two venues
+    Arbitrage(Box<ArbitrageOrder>),
 }
 
-#[derive(Debug, Clone)]
+/// Declared most urgent first, so ordering sorts Critical before Low
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 pub enum ExecutionPriority {
     Critical,
     High,
     Normal,
+    #[allow(dead_code)]
     Low,
 }
 
+impl ExecutionPriority {
+    /// Priority fee paid on top of the base fee
+    pub fn fee_lamports(&self) -> u64 {
+        match self {
+            Self::Critical => 1_000_000,
+            Self::High => 200_000,
+            Self::Normal => 50_000,
+            Self::Low => 10_000,
+        }
+    }
+}
+
+/// Messages from the risk manager to the executor
+#[derive(Debug)]
+pub enum ExecutionMessage {
+    Order(OrderRequest),
+    /// Drop every queued order that has not started executing; replies with the count
+    CancelAll { reply: oneshot::Sender<usize> },
+}
+
+/// Order approved by the risk manager
+#[derive(Debug)]
+pub struct OrderRequest {
+    pub id: String,
+    pub mint: String,
+    pub side: TradeSide,
+    pub order_type: OrderType,
+    /// Lamports to spend on buys, raw token units to sell on sells
+    pub amount: u64,
+    pub slippage_bps: u16,
+    pub priority: ExecutionPriority,
+    /// Public key of the wallet to trade from; `None` is the executor's own wallet
+    pub wallet: Option<String>,
+    pub reply: Option<oneshot::Sender<ExecutionReport>>,
+}
+
+/// Outcome of one order
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecutionReport {
+    pub order_id: String,
+    pub mint: String,
+    pub side: TradeSide,
+    pub success: bool,
+    pub signature: Option<String>,
+    /// Raw units spent and received, as settled on chain; quoted for paper trades and arbitrage
+    pub in_amount: u64,
+    pub out_amount: u64,
+    pub error: Option<String>,
+    pub latency_ms: u64,
+    /// Paper trade: quoted but not sent
+    pub simulated: bool,
+}
+
+impl ExecutionReport {
+    fn failed(order: &OrderRequest, error: String, latency_ms: u64) -> Self {
+        Self {
+            order_id: order.id.clone(),
+            mint: order.mint.clone(),
+            side: order.side,
+            success: false,
+            signature: None,
+            in_amount: 0,
+            out_amount: 0,
+            error: Some(error),
+            latency_ms,
+            simulated: false,
+        }
+    }
+}
+
 impl Executor {
-    pub async fn new() -> anyhow::Result<Self> {
+    pub async fn new(execution_rx: mpsc::Receiver<ExecutionMessage>) -> anyhow::Result<Self> {
         info!("⚡ Executor initializing...");
 
         // Initialize Solana client with optimized settings
@@ -56,63 +152,249 @@ impl Executor {
         // Load private key
         let private_key = std::env::var("SOLANA_PRIVATE_KEY")
             .map_err(|_| anyhow::anyhow!("SOLANA_PRIVATE_KEY environment variable not set"))?;
-        
-        let private_key_bytes = bs58::decode(&private_key)
-            .into_vec()
-            .map_err(|e| anyhow::anyhow!("Invalid private key format: {}", e))?;
-        
-        let keypair = Keypair::from_bytes(&private_key_bytes)
-            .map_err(|e| anyhow::anyhow!("Failed to create keypair: {}", e))?;
+        let keypair = parse_keypair(&private_key)?;
+
+        let mut strategy_wallets = HashMap::new();
+        for private_key in std::env::var("STRATEGY_WALLET_KEYS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|k| !k.is_empty())
+        {
+            let wallet = parse_keypair(private_key)?;
+            strategy_wallets.insert(wallet.pubkey(), wallet);
+        }
+
+        let paper_trading = std::env::var("TRADING_MODE").map(|m| m != "live").unwrap_or(true);
 
         info!("✅ Executor initialized");
         info!("🔗 RPC URL: {}", rpc_url);
         info!("🔑 Wallet: {}", keypair.pubkey());
+        for wallet in strategy_wallets.keys() {
+            info!("🔑 Strategy wallet: {}", wallet);
+        }
+        if paper_trading {
+            info!("📝 Paper trading - orders are quoted but not sent");
+        }
 
         Ok(Self {
+            execution_rx,
             solana_client: client,
             keypair,
+            strategy_wallets,
             execution_stats: ExecutionStats::new(),
+            jupiter: JupiterClient::from_env(),
+            pending_orders: VecDeque::new(),
+            paper_trading,
         })
     }
 
     pub async fn run(&mut self) -> anyhow::Result<()> {
         info!("🚀 Starting Executor...");
 
-        // TODO: Listen for execution messages
-        // TODO: Process orders with ultra-low latency
-        // TODO: Handle different order types
-
-        // Placeholder implementation
         loop {
-            tokio::time::sleep(tokio::time::Duration::from_secs(15)).await;
-            debug!("⚡ Executor heartbeat - ready for execution");
+            if self.pending_orders.is_empty() {
+                match self.execution_rx.recv().await {
+                    Some(message) => self.handle_message(message),
+                    None => {
+                        info!("⚡ Execution channel closed - Executor stopping");
+                        return Ok(());
+                    }
+                }
+            }
+
+            // Pick up everything already waiting so priorities are honoured
+            while let Ok(message) = self.execution_rx.try_recv() {
+                self.handle_message(message);
+            }
+
+            if let Some(order) = self.next_order() {
+                let report = self.execute_order(&order).await;
+                if let Some(reply) = order.reply {
+                    let _ = reply.send(report);
+                }
+            }
+        }
+    }
+
+    fn handle_message(&mut self, message: ExecutionMessage) {
+        match message {
+            ExecutionMessage::Order(order) => {
+                debug!("⚡ Queued order {} ({:?})", order.id, order.priority);
+                self.pending_orders.push_back(order);
+            }
+            ExecutionMessage::CancelAll { reply } => {
+                let cancelled = self.pending_orders.len();
+                for mut order in self.pending_orders.drain(..) {
+                    if let Some(order_reply) = order.reply.take() {
+                        let _ = order_reply.send(ExecutionReport::failed(&order, "cancelled".to_string(), 0));
+                    }
+                }
+                warn!("🚫 Cancelled {} pending orders", cancelled);
+                let _ = reply.send(cancelled);
+            }
+        }
+    }
+
+    /// Oldest order of the most urgent priority
+    fn next_order(&mut self) -> Option<OrderRequest> {
+        let index = self
+            .pending_orders
+            .iter()
+            .enumerate()
+            .min_by_key(|(i, order)| (order.priority, *i))
+            .map(|(i, _)| i)?;
+        self.pending_orders.remove(index)
+    }
+
+    async fn execute_order(&mut self, order: &OrderRequest) -> ExecutionReport {
+        let start_time = Instant::now();
+
+        // Checked up front so a misconfigured wallet also fails paper orders
+        let wallet = self.signer(order.wallet.as_deref()).map(|_| ());
+        let result = match (wallet, &order.order_type) {
+            (Err(e), _) => Err(e),
+            (Ok(()), OrderType::Market) => self.execute_market_order(order).await,
+            (Ok(()), OrderType::Arbitrage(arbitrage)) => self.execute_arbitrage(order, arbitrage).await,
+            (Ok(()), _) => self.execute_limit_order(order).await,
+        };
+
+        match result {
+            Ok(report) => report,
+            Err(e) => {
+                let execution_time = start_time.elapsed();
+                self.execution_stats.record_failure(execution_time);
+                error!("❌ Order {} failed: {}", order.id, e);
+                ExecutionReport::failed(order, e.to_string(), execution_time.as_millis() as u64)
+            }
         }
     }
 
     /// Execute market order with ultra-low latency
-    async fn execute_market_order(&mut self, symbol: &str, quantity: f64) -> anyhow::Result<String> {
+    async fn execute_market_order(&mut self, order: &OrderRequest) -> anyhow::Result<ExecutionReport> {
         let start_time = Instant::now();
         
-        info!("⚡ Executing market order: {} {}", quantity, symbol);
+        info!("⚡ Executing market order: {:?} {} {} ({:?})", order.side, order.amount, order.mint, order.priority);
 
-        // TODO: Implement DEX integration
-        // - Jupiter aggregator for best price
-        // - Direct DEX interaction for speed
-        // - Slippage protection
+        let (input_mint, output_mint) = match order.side {
+            TradeSide::Buy => (WSOL_MINT, order.mint.as_str()),
+            TradeSide::Sell => (order.mint.as_str(), WSOL_MINT),
+        };
+
+        // Jupiter aggregator for best price, slippage protected by the quote
+        let quote = self
+            .jupiter
+            .quote(input_mint, output_mint, order.amount, order.slippage_bps, None)
+            .await?;
+
+        let signature = if self.paper_trading {
+            None
+        } else {
+            Some(self.send_swap(&quote, order).await?)
+        };
+
+        // Report what settled on chain, the quote only when paper trading or the lookup fails
+        let (in_amount, out_amount) = match &signature {
+            Some(signature) => match self.settled_amounts(signature, order, &quote).await {
+                Ok(Some(amounts)) => amounts,
+                Ok(None) => {
+                    warn!("⚠️ Could not read settled amounts of {}, reporting the quote", signature);
+                    (quote.in_amount, quote.out_amount)
+                }
+                Err(e) => {
+                    warn!("⚠️ Failed to fetch {} for settled amounts, reporting the quote: {}", signature, e);
+                    (quote.in_amount, quote.out_amount)
+                }
+            },
+            None => (quote.in_amount, quote.out_amount),
+        };
 
-        let signature = self.execute_spl_transfer(symbol, quantity as u64, &self.keypair.pubkey()).await?;
-        
         let execution_time = start_time.elapsed();
         self.execution_stats.record_success(execution_time);
         
-        info!("✅ Market order executed: {} ({}ms)", signature, execution_time.as_millis());
+        info!("✅ Market order executed: {} ({}ms)", signature.as_deref().unwrap_or("paper"), execution_time.as_millis());
         
-        Ok(signature)
+        Ok(ExecutionReport {
+            order_id: order.id.clone(),
+            mint: order.mint.clone(),
+            side: order.side,
+            success: true,
+            simulated: signature.is_none(),
+            signature,
+            in_amount,
+            out_amount,
+            error: None,
+            latency_ms: execution_time.as_millis() as u64,
+        })
+    }
+
+    /// Amounts the confirmed swap `signature` actually moved for the order's wallet
+    async fn settled_amounts(&self, signature: &str, order: &OrderRequest, quote: &jupiter::Quote) -> anyhow::Result<Option<(u64, u64)>> {
+        let payer = self.signer(order.wallet.as_deref())?.pubkey().to_string();
+        let params = serde_json::json!([
+            signature,
+            { "encoding": "json", "commitment": "confirmed", "maxSupportedTransactionVersion": 0 }
+        ]);
+        let tx: serde_json::Value = self.solana_client.send(RpcRequest::GetTransaction, params).await?;
+        let slot = tx["slot"].as_u64().unwrap_or(0);
+
+        Ok(TransactionUpdate::from_json(signature, slot, &tx)
+            .and_then(|tx| settled_swap(&tx, &payer, &quote.input_mint, &quote.output_mint)))
+    }
+
+    /// Re-quote both legs and send them as one transaction if the round trip still pays
+    ///
+    /// The report's amounts are lamports spent and (quoted) lamports returned.
+    async fn execute_arbitrage(&mut self, order: &OrderRequest, arbitrage: &ArbitrageOrder) -> anyhow::Result<ExecutionReport> {
+        let start_time = Instant::now();
+
+        info!("🔀 Executing arbitrage {}: {} lamports of {} via {} -> {}",
+              order.id, order.amount, order.mint, arbitrage.buy_venue, arbitrage.sell_venue);
+
+        let quotes = arbitrage::quote_legs(&self.jupiter, &order.mint, order.amount, order.slippage_bps, arbitrage).await?;
+
+        let signature = if self.paper_trading {
+            None
+        } else {
+            let signer = self.signer(order.wallet.as_deref())?;
+            let payer = signer.pubkey();
+            let buy = self.jupiter.swap_instructions(&quotes.buy, &payer, None).await?;
+            let sell = self.jupiter.swap_instructions(&quotes.sell, &payer, None).await?;
+            let instructions = arbitrage::build_instructions(arbitrage, &payer, &buy, &sell)?;
+
+            let mut table_addresses = buy.lookup_tables.clone();
+            table_addresses.extend(sell.lookup_tables.iter().filter(|t| !buy.lookup_tables.contains(t)));
+            let tables = load_lookup_tables(&self.solana_client, &table_addresses).await?;
+
+            let recent_blockhash = self.solana_client.get_latest_blockhash().await?;
+            let message = v0::Message::try_compile(&payer, &instructions, &tables, recent_blockhash)?;
+            let transaction = VersionedTransaction::try_new(VersionedMessage::V0(message), &[signer])?;
+            Some(self.solana_client.send_and_confirm_transaction(&transaction).await?.to_string())
+        };
+
+        let execution_time = start_time.elapsed();
+        self.execution_stats.record_success(execution_time);
+
+        info!("✅ Arbitrage executed: {} expected profit {} lamports ({}ms)",
+              signature.as_deref().unwrap_or("paper"), quotes.expected_profit, execution_time.as_millis());
+
+        Ok(ExecutionReport {
+            order_id: order.id.clone(),
+            mint: order.mint.clone(),
+            side: order.side,
+            success: true,
+            simulated: signature.is_none(),
+            signature,
+            in_amount: quotes.buy.in_amount + arbitrage.cost_lamports(),
+            out_amount: quotes.sell.out_amount,
+            error: None,
+            latency_ms: execution_time.as_millis() as u64,
+        })
     }
 
     /// Execute limit order
-    async fn execute_limit_order(&mut self, symbol: &str, quantity: f64, price: f64) -> anyhow::Result<String> {
-        info!("📊 Executing limit order: {} {} @ {}", quantity, symbol, price);
+    async fn execute_limit_order(&mut self, order: &OrderRequest) -> anyhow::Result<ExecutionReport> {
+        info!("📊 Executing {:?} order: {} {}", order.order_type, order.amount, order.mint);
 
         // TODO: Implement limit order logic
         // - Place order on DEX
@@ -120,10 +402,47 @@ impl Executor {
         // - Handle partial fills
         
         // For now, simulate with market order
-        self.execute_market_order(symbol, quantity).await
+        self.execute_market_order(order).await
+    }
+
+    /// Keypair for `wallet`, the executor's own when `None`
+    fn signer(&self, wallet: Option<&str>) -> anyhow::Result<&Keypair> {
+        let Some(wallet) = wallet else {
+            return Ok(&self.keypair);
+        };
+        let pubkey = Pubkey::from_str(wallet).map_err(|e| anyhow::anyhow!("Invalid wallet {}: {}", wallet, e))?;
+        if pubkey == self.keypair.pubkey() {
+            return Ok(&self.keypair);
+        }
+        self.strategy_wallets
+            .get(&pubkey)
+            .ok_or_else(|| anyhow::anyhow!("No key loaded for wallet {} (STRATEGY_WALLET_KEYS)", wallet))
+    }
+
+    /// Build, sign and send the Jupiter swap for `quote` from the order's wallet
+    async fn send_swap(&self, quote: &jupiter::Quote, order: &OrderRequest) -> anyhow::Result<String> {
+        let signer = self.signer(order.wallet.as_deref())?;
+        let payer = signer.pubkey();
+        let swap = self
+            .jupiter
+            .swap_instructions(quote, &payer, Some(order.priority.fee_lamports()))
+            .await?;
+
+        let mut instructions = swap.compute_budget.clone();
+        instructions.extend(swap.core_instructions());
+
+        let tables = load_lookup_tables(&self.solana_client, &swap.lookup_tables).await?;
+        let recent_blockhash = self.solana_client.get_latest_blockhash().await?;
+        let message = v0::Message::try_compile(&payer, &instructions, &tables, recent_blockhash)?;
+        let transaction = VersionedTransaction::try_new(VersionedMessage::V0(message), &[signer])?;
+
+        let signature = self.solana_client.send_and_confirm_transaction(&transaction).await?;
+
+        Ok(signature.to_string())
     }
 
     /// Execute SPL token transfer (for demo/testing)
+    #[allow(dead_code)]
     async fn execute_spl_transfer(
         &self,
         token_symbol: &str,
@@ -160,7 +479,7 @@ impl Executor {
         )?;
 
         // Get recent blockhash for ultra-low latency
-        let recent_blockhash = self.solana_client.get_latest_blockhash()?;
+        let recent_blockhash = self.solana_client.get_latest_blockhash().await?;
 
         // Create and sign transaction
         let transaction = Transaction::new_signed_with_payer(
@@ -171,17 +490,19 @@ impl Executor {
         );
 
         // Send transaction with high priority
-        let signature = self.solana_client.send_and_confirm_transaction(&transaction)?;
+        let signature = self.solana_client.send_and_confirm_transaction(&transaction).await?;
 
         Ok(signature.to_string())
     }
 
     /// Get execution statistics
+    #[allow(dead_code)]
     pub fn get_stats(&self) -> &ExecutionStats {
         &self.execution_stats
     }
 
     /// Health check for executor
+    #[allow(dead_code)]
     pub async fn health_check(&self) -> anyhow::Result<bool> {
         // TODO: Check Solana connection
         // TODO: Verify wallet balance
@@ -232,6 +553,7 @@ impl ExecutionStats {
         self.average_latency_ms = (total_latency + latency_ms as f64) / self.total_executions as f64;
     }
 
+    #[allow(dead_code)]
     pub fn success_rate(&self) -> f64 {
         if self.total_executions == 0 {
             0.0
@@ -240,3 +562,120 @@ impl ExecutionStats {
         }
     }
 }
+
+/// (in, out) base units a successful swap moved for `payer`, from the transaction meta
+///
+/// SOL legs count lamports including WSOL, excluding the network fee and the rent of
+/// token accounts the swap opened.
+fn settled_swap(tx: &TransactionUpdate, payer: &str, input_mint: &str, output_mint: &str) -> Option<(u64, u64)> {
+    if !tx.success {
+        return None;
+    }
+
+    let owned = |balances: &[TokenBalance], mint: &str| -> i128 {
+        balances
+            .iter()
+            .filter(|b| b.owner == payer && b.mint == mint)
+            .map(|b| b.amount as i128)
+            .sum()
+    };
+    let rent: i128 = tx
+        .post_token_balances
+        .iter()
+        .filter(|b| b.owner == payer && b.mint != WSOL_MINT)
+        .filter(|b| !tx.pre_token_balances.iter().any(|pre| pre.account_index == b.account_index))
+        .filter_map(|b| tx.post_balances.get(b.account_index as usize))
+        .map(|&lamports| lamports as i128)
+        .sum();
+    let delta = |mint: &str| -> i128 {
+        if mint == WSOL_MINT {
+            (sol_delta(payer, tx) * LAMPORTS_PER_SOL as f64).round() as i128 + rent
+        } else {
+            owned(&tx.post_token_balances, mint) - owned(&tx.pre_token_balances, mint)
+        }
+    };
+
+    let spent = -delta(input_mint);
+    let received = delta(output_mint);
+    if spent <= 0 || received <= 0 {
+        return None;
+    }
+    Some((spent as u64, received as u64))
+}
+
+/// Keypair from a base58-encoded secret key
+fn parse_keypair(private_key: &str) -> anyhow::Result<Keypair> {
+    let private_key_bytes = bs58::decode(private_key)
+        .into_vec()
+        .map_err(|e| anyhow::anyhow!("Invalid private key format: {}", e))?;
+
+    Keypair::try_from(private_key_bytes.as_slice()).map_err(|e| anyhow::anyhow!("Failed to create keypair: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAYER: &str = "Payer111111111111111111111111111111111111111";
+    const MINT: &str = "Mint111111111111111111111111111111111111111";
+    const FEE: u64 = 5_000;
+    const RENT: u64 = 2_039_280;
+
+    fn balance(account_index: u32, mint: &str, amount: u64) -> TokenBalance {
+        TokenBalance {
+            account_index,
+            mint: mint.to_string(),
+            owner: PAYER.to_string(),
+            amount,
+            decimals: 6,
+        }
+    }
+
+    /// Swap paid by `PAYER`, whose SOL changes by `lamports` before the fee; account 1 is its token account
+    fn swap(lamports: i64, token_rent: u64, pre: Vec<TokenBalance>, post: Vec<TokenBalance>) -> TransactionUpdate {
+        let start = 10_000_000_000u64;
+        TransactionUpdate {
+            signature: "sig".to_string(),
+            slot: 1,
+            block_time: None,
+            success: true,
+            fee: FEE,
+            account_keys: vec![PAYER.to_string(), "TokenAccount".to_string()],
+            instructions: Vec::new(),
+            pre_balances: vec![start, 0],
+            post_balances: vec![(start as i64 + lamports) as u64 - FEE, token_rent],
+            pre_token_balances: pre,
+            post_token_balances: post,
+            log_messages: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn buy_reports_settled_sol_and_tokens() {
+        // 0.5 SOL swapped plus the rent of the new token account
+        let tx = swap(-500_000_000 - RENT as i64, RENT, vec![], vec![balance(1, MINT, 980_000)]);
+
+        assert_eq!(settled_swap(&tx, PAYER, WSOL_MINT, MINT), Some((500_000_000, 980_000)));
+    }
+
+    #[test]
+    fn sell_reports_settled_tokens_and_sol() {
+        let tx = swap(
+            240_000_000,
+            RENT,
+            vec![balance(1, MINT, 1_000_000)],
+            vec![balance(1, MINT, 400_000)],
+        );
+
+        assert_eq!(settled_swap(&tx, PAYER, MINT, WSOL_MINT), Some((600_000, 240_000_000)));
+    }
+
+    #[test]
+    fn failed_or_empty_swaps_have_no_settlement() {
+        let mut tx = swap(0, 0, vec![], vec![]);
+        assert_eq!(settled_swap(&tx, PAYER, WSOL_MINT, MINT), None);
+
+        tx.success = false;
+        assert_eq!(settled_swap(&tx, PAYER, WSOL_MINT, MINT), None);
+    }
+}
@@ -0,0 +1,163 @@
+// 🔀 Atomic Arbitrage
+// Buy on one venue and sell on another inside a single transaction
+
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use std::str::FromStr;
+
+use super::jupiter::{JupiterClient, Quote, SwapInstructions};
+use crate::modules::data_ingestor::wallet_tracker::WSOL_MINT;
+
+const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
+
+/// Signature fee of a single-signer transaction
+pub const BASE_FEE_LAMPORTS: u64 = 5_000;
+
+/// Compute budget requested for both swaps together
+const ARBITRAGE_COMPUTE_UNITS: u32 = 1_000_000;
+
+/// Public Jito tip accounts, used when `JITO_TIP_ACCOUNTS` is not set
+const DEFAULT_JITO_TIP_ACCOUNTS: [&str; 8] = [
+    "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5",
+    "HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe",
+    "Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY",
+    "ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49",
+    "DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh",
+    "ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt",
+    "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL",
+    "3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT",
+];
+
+/// Two-leg round trip SOL -> token -> SOL; the order's amount is the lamports spent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArbitrageOrder {
+    pub buy_venue: String,
+    pub sell_venue: String,
+    /// Jupiter DEX labels each leg is restricted to; empty means any route
+    pub buy_dexes: Vec<String>,
+    pub sell_dexes: Vec<String>,
+    /// Round trip must return at least this much over what it costs, or it reverts
+    pub min_profit_lamports: u64,
+    pub priority_fee_lamports: u64,
+    pub jito_tip_lamports: u64,
+    /// Account cap per leg so both fit in one transaction
+    pub max_accounts: u8,
+}
+
+impl ArbitrageOrder {
+    /// Fees paid whether or not the trade makes money
+    pub fn cost_lamports(&self) -> u64 {
+        BASE_FEE_LAMPORTS + self.priority_fee_lamports + self.jito_tip_lamports
+    }
+}
+
+/// Fresh quotes for both legs, with the sell leg's minimum output set to break even plus profit
+#[derive(Debug, Clone)]
+pub struct ArbitrageQuotes {
+    pub buy: Quote,
+    pub sell: Quote,
+    /// Expected lamports back minus lamports spent and fees
+    pub expected_profit: i64,
+}
+
+/// Quote both legs for `lamports` and check the round trip still pays
+pub async fn quote_legs(
+    jupiter: &JupiterClient,
+    mint: &str,
+    lamports: u64,
+    slippage_bps: u16,
+    order: &ArbitrageOrder,
+) -> anyhow::Result<ArbitrageQuotes> {
+    let max_accounts = Some(order.max_accounts);
+    let buy = jupiter
+        .quote_on_dexes(WSOL_MINT, mint, lamports, slippage_bps, max_accounts, &order.buy_dexes)
+        .await?;
+    let mut sell = jupiter
+        .quote_on_dexes(mint, WSOL_MINT, buy.out_amount, slippage_bps, max_accounts, &order.sell_dexes)
+        .await?;
+
+    let breakeven = lamports + order.cost_lamports();
+    let required = breakeven + order.min_profit_lamports;
+    if sell.out_amount < required {
+        anyhow::bail!(
+            "opportunity gone: {} -> {} returns {} lamports, needs {}",
+            order.buy_venue, order.sell_venue, sell.out_amount, required
+        );
+    }
+
+    // Tighten the sell leg so it reverts rather than land below break-even plus profit
+    let guard_bps = ((sell.out_amount - required) as u128 * 10_000 / sell.out_amount as u128) as u16;
+    if guard_bps < slippage_bps {
+        sell = jupiter
+            .quote_on_dexes(mint, WSOL_MINT, buy.out_amount, guard_bps, max_accounts, &order.sell_dexes)
+            .await?;
+    }
+
+    Ok(ArbitrageQuotes {
+        expected_profit: sell.out_amount as i64 - breakeven as i64,
+        buy,
+        sell,
+    })
+}
+
+/// Instructions for the whole round trip: compute budget, both legs, then the Jito tip
+///
+/// The sell leg spends the buy leg's quoted output; if the buy fills short, the sell and
+/// with it the whole transaction fail, so neither leg can land alone.
+pub fn build_instructions(
+    order: &ArbitrageOrder,
+    payer: &Pubkey,
+    buy: &SwapInstructions,
+    sell: &SwapInstructions,
+) -> anyhow::Result<Vec<Instruction>> {
+    // Each leg's own compute budget instructions would collide, so one budget covers both
+    let micro_lamports = order.priority_fee_lamports * 1_000_000 / ARBITRAGE_COMPUTE_UNITS as u64;
+    let mut instructions = vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(ARBITRAGE_COMPUTE_UNITS),
+        ComputeBudgetInstruction::set_compute_unit_price(micro_lamports),
+    ];
+    instructions.extend(buy.core_instructions());
+    instructions.extend(sell.core_instructions());
+
+    if order.jito_tip_lamports > 0 {
+        instructions.push(transfer(payer, &jito_tip_account()?, order.jito_tip_lamports)?);
+    }
+
+    Ok(instructions)
+}
+
+/// System program `Transfer` (instruction index 2)
+fn transfer(from: &Pubkey, to: &Pubkey, lamports: u64) -> anyhow::Result<Instruction> {
+    let mut data = 2u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&lamports.to_le_bytes());
+    Ok(Instruction {
+        program_id: Pubkey::from_str(SYSTEM_PROGRAM)?,
+        accounts: vec![AccountMeta::new(*from, true), AccountMeta::new(*to, false)],
+        data,
+    })
+}
+
+fn jito_tip_account() -> anyhow::Result<Pubkey> {
+    let configured: Vec<String> = std::env::var("JITO_TIP_ACCOUNTS")
+        .map(|accounts| {
+            accounts
+                .split(',')
+                .map(|a| a.trim().to_string())
+                .filter(|a| !a.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // Spread tips across accounts to avoid write-lock contention on one of them
+    let account = if configured.is_empty() {
+        DEFAULT_JITO_TIP_ACCOUNTS.choose(&mut rand::thread_rng()).map(|a| a.to_string())
+    } else {
+        configured.choose(&mut rand::thread_rng()).cloned()
+    };
+    Ok(Pubkey::from_str(&account.unwrap_or_default())?)
+}
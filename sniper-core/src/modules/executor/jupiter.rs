@@ -0,0 +1,272 @@
+// 🪐 Jupiter Swap Client
+// Quotes and swap instructions from the Jupiter v6 aggregator API
+
+use base64::Engine;
+use serde::Deserialize;
+use serde_json::Value;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    message::AddressLookupTableAccount,
+    pubkey::Pubkey,
+};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Address lookup table header: type, deactivation slot, last extended slot/index, authority
+const LOOKUP_TABLE_META_SIZE: usize = 56;
+
+/// Route returned by `/quote`, kept verbatim for `/swap-instructions`
+#[derive(Debug, Clone)]
+pub struct Quote {
+    pub input_mint: String,
+    pub output_mint: String,
+    pub in_amount: u64,
+    pub out_amount: u64,
+    /// Minimum output after slippage
+    pub min_out_amount: u64,
+    /// Price impact as a fraction (0.01 = 1%)
+    pub price_impact: f64,
+    pub raw: Value,
+}
+
+/// Instructions making up one Jupiter swap
+#[derive(Debug, Clone, Default)]
+pub struct SwapInstructions {
+    pub compute_budget: Vec<Instruction>,
+    pub setup: Vec<Instruction>,
+    pub swap: Option<Instruction>,
+    pub cleanup: Option<Instruction>,
+    pub lookup_tables: Vec<Pubkey>,
+}
+
+impl SwapInstructions {
+    /// Setup, swap and cleanup instructions without the compute budget
+    pub fn core_instructions(&self) -> Vec<Instruction> {
+        self.setup
+            .iter()
+            .chain(self.swap.iter())
+            .chain(self.cleanup.iter())
+            .cloned()
+            .collect()
+    }
+}
+
+pub struct JupiterClient {
+    api_url: String,
+    client: reqwest::Client,
+}
+
+impl JupiterClient {
+    pub fn from_env() -> Self {
+        Self::new(
+            std::env::var("JUPITER_SWAP_API_URL")
+                .unwrap_or_else(|_| "https://quote-api.jup.ag/v6".to_string()),
+        )
+    }
+
+    pub fn new(api_url: String) -> Self {
+        Self {
+            api_url: api_url.trim_end_matches('/').to_string(),
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(5))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Best route for swapping `amount` raw units of `input_mint`
+    ///
+    /// `max_accounts` caps route complexity so several swaps fit in one transaction.
+    pub async fn quote(
+        &self,
+        input_mint: &str,
+        output_mint: &str,
+        amount: u64,
+        slippage_bps: u16,
+        max_accounts: Option<u8>,
+    ) -> anyhow::Result<Quote> {
+        self.quote_on_dexes(input_mint, output_mint, amount, slippage_bps, max_accounts, &[])
+            .await
+    }
+
+    /// Like `quote`, but routed only through the given Jupiter DEX labels (all when empty)
+    pub async fn quote_on_dexes(
+        &self,
+        input_mint: &str,
+        output_mint: &str,
+        amount: u64,
+        slippage_bps: u16,
+        max_accounts: Option<u8>,
+        dexes: &[String],
+    ) -> anyhow::Result<Quote> {
+        let mut url = format!(
+            "{}/quote?inputMint={}&outputMint={}&amount={}&slippageBps={}",
+            self.api_url, input_mint, output_mint, amount, slippage_bps
+        );
+        if let Some(max_accounts) = max_accounts {
+            url.push_str(&format!("&maxAccounts={}", max_accounts));
+        }
+        if !dexes.is_empty() {
+            // Labels such as "Meteora DLMM" contain spaces
+            url.push_str(&format!("&dexes={}", dexes.join(",").replace(' ', "%20")));
+        }
+
+        let raw: Value = self.client.get(&url).send().await?.error_for_status()?.json().await?;
+        if let Some(error) = raw["error"].as_str() {
+            anyhow::bail!("Jupiter quote failed: {}", error);
+        }
+
+        Ok(Quote {
+            input_mint: input_mint.to_string(),
+            output_mint: output_mint.to_string(),
+            in_amount: parse_amount(&raw["inAmount"])?,
+            out_amount: parse_amount(&raw["outAmount"])?,
+            min_out_amount: parse_amount(&raw["otherAmountThreshold"])?,
+            price_impact: raw["priceImpactPct"]
+                .as_str()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(0.0),
+            raw,
+        })
+    }
+
+    /// Instructions executing `quote` for `user`, wrapping and unwrapping SOL as needed
+    pub async fn swap_instructions(
+        &self,
+        quote: &Quote,
+        user: &Pubkey,
+        priority_fee_lamports: Option<u64>,
+    ) -> anyhow::Result<SwapInstructions> {
+        let mut body = serde_json::json!({
+            "quoteResponse": quote.raw,
+            "userPublicKey": user.to_string(),
+            "wrapAndUnwrapSol": true,
+            "dynamicComputeUnitLimit": true,
+        });
+        if let Some(fee) = priority_fee_lamports {
+            body["prioritizationFeeLamports"] = fee.into();
+        }
+
+        let response: SwapInstructionsResponse = self
+            .client
+            .post(format!("{}/swap-instructions", self.api_url))
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        if let Some(error) = response.error {
+            anyhow::bail!("Jupiter swap-instructions failed: {}", error);
+        }
+
+        Ok(SwapInstructions {
+            compute_budget: convert_all(response.compute_budget_instructions)?,
+            setup: convert_all(response.setup_instructions)?,
+            swap: response.swap_instruction.map(ApiInstruction::into_instruction).transpose()?,
+            cleanup: response.cleanup_instruction.map(ApiInstruction::into_instruction).transpose()?,
+            lookup_tables: response
+                .address_lookup_table_addresses
+                .iter()
+                .map(|address| Pubkey::from_str(address))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// Fetch and decode address lookup tables referenced by a swap
+pub async fn load_lookup_tables(
+    rpc: &RpcClient,
+    addresses: &[Pubkey],
+) -> anyhow::Result<Vec<AddressLookupTableAccount>> {
+    if addresses.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let accounts = rpc.get_multiple_accounts(addresses).await?;
+    let mut tables = Vec::with_capacity(addresses.len());
+
+    for (key, account) in addresses.iter().zip(accounts) {
+        let account = account.ok_or_else(|| anyhow::anyhow!("Lookup table {} not found", key))?;
+        let entries = account
+            .data
+            .get(LOOKUP_TABLE_META_SIZE..)
+            .ok_or_else(|| anyhow::anyhow!("Invalid lookup table {}", key))?;
+
+        tables.push(AddressLookupTableAccount {
+            key: *key,
+            addresses: entries
+                .chunks_exact(32)
+                .map(|chunk| Pubkey::new_from_array(chunk.try_into().unwrap()))
+                .collect(),
+        });
+    }
+
+    Ok(tables)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SwapInstructionsResponse {
+    #[serde(default)]
+    compute_budget_instructions: Vec<ApiInstruction>,
+    #[serde(default)]
+    setup_instructions: Vec<ApiInstruction>,
+    swap_instruction: Option<ApiInstruction>,
+    cleanup_instruction: Option<ApiInstruction>,
+    #[serde(default)]
+    address_lookup_table_addresses: Vec<String>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiInstruction {
+    program_id: String,
+    accounts: Vec<ApiAccountMeta>,
+    data: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiAccountMeta {
+    pubkey: String,
+    is_signer: bool,
+    is_writable: bool,
+}
+
+impl ApiInstruction {
+    fn into_instruction(self) -> anyhow::Result<Instruction> {
+        let accounts = self
+            .accounts
+            .into_iter()
+            .map(|meta| {
+                let pubkey = Pubkey::from_str(&meta.pubkey)?;
+                Ok(if meta.is_writable {
+                    AccountMeta::new(pubkey, meta.is_signer)
+                } else {
+                    AccountMeta::new_readonly(pubkey, meta.is_signer)
+                })
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Instruction {
+            program_id: Pubkey::from_str(&self.program_id)?,
+            accounts,
+            data: base64::engine::general_purpose::STANDARD.decode(self.data)?,
+        })
+    }
+}
+
+fn convert_all(instructions: Vec<ApiInstruction>) -> anyhow::Result<Vec<Instruction>> {
+    instructions.into_iter().map(ApiInstruction::into_instruction).collect()
+}
+
+fn parse_amount(value: &Value) -> anyhow::Result<u64> {
+    value
+        .as_str()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| anyhow::anyhow!("Missing amount in Jupiter quote"))
+}
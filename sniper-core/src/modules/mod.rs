@@ -7,5 +7,6 @@ pub mod strategy_engine;
 pub mod risk_manager;
 pub mod executor;
 pub mod cache_manager;
+pub mod backtest;
 
 
@@ -1,184 +1,1194 @@
 // ⚠️ Risk Manager Module
 // Risk assessment and position management
 
-use tokio::sync::mpsc;
+pub mod api;
+pub mod circuit_breaker;
+pub mod correlation;
+pub mod decision;
+pub mod emergency;
+pub mod exits;
+pub mod reconcile;
+pub mod rug_screen;
+pub mod sizing;
+pub mod slippage;
+pub mod var;
+
+use serde::Serialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey,
+    signature::Keypair, signer::Signer,
+};
+use tokio::sync::{mpsc, oneshot};
 use tracing::{info, error, debug, warn};
 use serde_json::Value;
+use std::str::FromStr;
 use std::time::Duration;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use super::cache_manager::CacheManager;
+use super::data_ingestor::wallet_tracker::WSOL_MINT;
+use super::data_ingestor::{TokenProfile, TokenProfiler, TradeSide};
+use super::executor::arbitrage::ArbitrageOrder;
+use super::executor::jupiter::JupiterClient;
+use super::executor::{ExecutionMessage, ExecutionPriority, ExecutionReport, OrderRequest, OrderType};
+use super::strategy_engine::{SignalMessage, StrategyFill};
+
+pub use api::RiskApi;
+pub use circuit_breaker::CircuitBreaker;
+pub use correlation::{CorrelationConfig, CorrelationEngine, CorrelationMatrix};
+pub use decision::{BookState, DecisionRules};
+pub use emergency::{EmergencyStopConfig, EmergencyStopReport};
+pub use exits::{ExitReason, ExitRules, ExitState, ExitTrigger};
+pub use reconcile::{Discrepancy, ReconcileConfig, ReconcileReport};
+pub use rug_screen::{RejectionReason, RugScreenConfig, RugScreener, ScreeningReport};
+pub use sizing::{SizingConfig, SizingModel};
+pub use slippage::{ImpactEstimate, SlippageConfig};
+pub use var::{VarConfig, VarEstimate};
+
+/// Operator commands, e.g. from the HTTP API
+#[derive(Debug)]
+pub enum RiskCommand {
+    EmergencyStop { reply: oneshot::Sender<EmergencyStopReport> },
+    PortfolioMetrics { reply: oneshot::Sender<Value> },
+}
 
 pub struct RiskManager {
-    // TODO: Add channels when ready
-    // signal_rx: mpsc::Receiver<SignalMessage>,
-    // execution_tx: mpsc::Sender<ExecutionMessage>,
+    signal_rx: mpsc::Receiver<SignalMessage>,
+    execution_tx: mpsc::Sender<ExecutionMessage>,
+    command_rx: mpsc::Receiver<RiskCommand>,
     position_limits: PositionLimits,
     current_positions: HashMap<String, Position>,
+    cache_manager: CacheManager,
+    token_profiler: TokenProfiler,
+    rug_screener: RugScreener,
+    rpc: RpcClient,
+    wallet: Option<Pubkey>,
+    circuit_breaker: CircuitBreaker,
+    emergency_config: EmergencyStopConfig,
+    /// Exit orders sent to the executor and not yet reported back, by mint
+    pending_exits: HashMap<String, PendingExit>,
+    /// Buy orders sent to the executor and not yet reported back, by mint
+    pending_entries: HashMap<String, PendingEntry>,
+    exit_slippage_bps: u16,
+    entry_slippage_bps: u16,
+    /// Exit rules are skipped on prices older than this (ms)
+    max_price_age_ms: i64,
+    /// Positions whose latest price is too old, so the alert fires once per outage
+    stale_prices: HashSet<String>,
+    sizing_config: SizingConfig,
+    slippage_config: SlippageConfig,
+    jupiter: JupiterClient,
+    /// Recent entry fills next to their pre-trade impact estimate, newest last
+    fill_slippage: VecDeque<FillSlippage>,
+    reconcile_config: ReconcileConfig,
+    /// Paper fills never reach the chain, so there is nothing to reconcile against
+    paper_trading: bool,
+    var_config: VarConfig,
+    correlation: CorrelationEngine,
+    /// Order outcomes for strategy-owned positions
+    strategy_fills: Option<mpsc::Sender<StrategyFill>>,
+}
+
+struct PendingExit {
+    reason: ExitReason,
+    decimals: u8,
+    report_rx: oneshot::Receiver<ExecutionReport>,
+}
+
+struct PendingEntry {
+    decimals: u8,
+    wallet: Option<String>,
+    exit_rules: ExitRules,
+    strategy_id: Option<String>,
+    impact: Option<ImpactEstimate>,
+    report_rx: oneshot::Receiver<ExecutionReport>,
 }
 
+const FILL_SLIPPAGE_HISTORY: usize = 100;
+const POSITIONS_KEY: &str = "positions";
+
+/// Pre-trade impact estimate compared with what the order actually received
+#[derive(Debug, Clone, Serialize)]
+pub struct FillSlippage {
+    pub order_id: String,
+    pub mint: String,
+    pub estimate: ImpactEstimate,
+    pub actual_out: u64,
+    /// Output shortfall against the estimate as a fraction; negative when the fill beat it
+    pub shortfall: f64,
+}
+
+/// Risk limits; sizes are SOL notionals
 #[derive(Debug, Clone)]
 pub struct PositionLimits {
+    /// Largest single order
     pub max_position_size: f64,
     pub max_daily_loss: f64,
+    /// Largest one-interval VaR as a share of portfolio value
     pub max_portfolio_risk: f64,
+    /// Largest return correlation of a new buy with any open position
     pub max_correlation: f64,
+    /// Largest total exposure to one mint
+    pub max_mint_exposure: f64,
+    /// Largest exposure across all positions
+    pub max_total_exposure: f64,
+    /// SOL always left in the wallet for fees and rent
+    pub min_sol_reserve: f64,
+    /// Orders shrunk below this are rejected instead
+    pub min_order_size: f64,
+}
+
+impl PositionLimits {
+    pub fn from_env() -> Self {
+        Self {
+            max_position_size: env_f64("MAX_POSITION_SIZE", 1.0),
+            max_daily_loss: env_f64("MAX_DAILY_LOSS", 5.0),
+            max_portfolio_risk: env_f64("MAX_PORTFOLIO_RISK", 0.02), // 2% max portfolio risk
+            max_correlation: env_f64("MAX_CORRELATION", 0.7),
+            max_mint_exposure: env_f64("MAX_MINT_EXPOSURE", 2.0),
+            max_total_exposure: env_f64("MAX_TOTAL_EXPOSURE", 10.0),
+            min_sol_reserve: env_f64("MIN_SOL_RESERVE", 0.05),
+            min_order_size: env_f64("MIN_ORDER_SIZE", 0.01),
+        }
+    }
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+/// Outcome of risk assessment for one signal
+#[derive(Debug, Clone, Serialize)]
+pub struct RiskDecision {
+    pub approved: bool,
+    /// Rejections and size adjustments, in the order they were applied
+    pub reasons: Vec<String>,
+    /// Order notional in SOL after all caps
+    pub adjusted_size: f64,
+    /// Model that sized the order; `None` when the signal requested a size
+    pub sizing: Option<SizingModel>,
+    /// Estimated price impact at the approved size
+    pub price_impact: Option<ImpactEstimate>,
+}
+
+impl RiskDecision {
+    pub fn new(size: f64) -> Self {
+        Self {
+            approved: true,
+            reasons: Vec::new(),
+            adjusted_size: size,
+            sizing: None,
+            price_impact: None,
+        }
+    }
+
+    pub fn reject(&mut self, reason: impl Into<String>) {
+        self.approved = false;
+        self.reasons.push(reason.into());
+    }
+
+    /// Shrink the order to `limit`, rejecting it if nothing is left
+    pub fn cap(&mut self, limit: f64, what: &str) {
+        if self.adjusted_size <= limit {
+            return;
+        }
+        if limit <= 0.0 {
+            self.reject(format!("{} exhausted", what));
+        } else {
+            self.reasons.push(format!("size {:.4} capped to {:.4} SOL by {}", self.adjusted_size, limit, what));
+            self.adjusted_size = limit;
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Position {
     pub symbol: String,
+    /// Token quantity
     pub size: f64,
     pub entry_price: f64,
     pub current_pnl: f64,
     pub risk_score: f64,
+    pub decimals: u8,
+    /// Latest mark price in SOL, 0 until the first price arrives
+    pub last_price: f64,
+    pub exit_rules: ExitRules,
+    pub exit_state: ExitState,
+    /// Strategy instance that opened the position
+    #[serde(default)]
+    pub strategy_id: Option<String>,
+    /// Wallet holding the tokens; `None` is the default trading wallet
+    #[serde(default)]
+    pub wallet: Option<String>,
 }
 
 impl RiskManager {
-    pub async fn new() -> anyhow::Result<Self> {
+    pub async fn new(
+        cache_manager: CacheManager,
+        circuit_breaker: CircuitBreaker,
+        signal_rx: mpsc::Receiver<SignalMessage>,
+        execution_tx: mpsc::Sender<ExecutionMessage>,
+        command_rx: mpsc::Receiver<RiskCommand>,
+    ) -> anyhow::Result<Self> {
         info!("⚠️ RiskManager initializing...");
 
-        let position_limits = PositionLimits {
-            max_position_size: std::env::var("MAX_POSITION_SIZE")
-                .unwrap_or_else(|_| "1000.0".to_string())
-                .parse()
-                .unwrap_or(1000.0),
-            max_daily_loss: std::env::var("MAX_DAILY_LOSS")
-                .unwrap_or_else(|_| "500.0".to_string())
-                .parse()
-                .unwrap_or(500.0),
-            max_portfolio_risk: 0.02, // 2% max portfolio risk
-            max_correlation: 0.7,     // Max 70% correlation between positions
-        };
+        let position_limits = PositionLimits::from_env();
+
+        let rpc_url = std::env::var("QUICKNODE_RPC_URL")
+            .unwrap_or_else(|_| "https://api.devnet.solana.com".to_string());
+        let rug_config = RugScreenConfig::from_env();
 
         info!("✅ RiskManager initialized with limits: {:?}", position_limits);
+        info!("🧪 Rug screening rules: {:?}", rug_config);
 
         Ok(Self {
+            signal_rx,
+            execution_tx,
+            command_rx,
             position_limits,
             current_positions: HashMap::new(),
+            cache_manager,
+            token_profiler: TokenProfiler::new(rpc_url.clone()),
+            rug_screener: RugScreener::new(rug_config, rpc_url.clone(), wallet_pubkey()),
+            rpc: RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed()),
+            wallet: wallet_pubkey(),
+            circuit_breaker,
+            emergency_config: EmergencyStopConfig::from_env(),
+            pending_exits: HashMap::new(),
+            pending_entries: HashMap::new(),
+            exit_slippage_bps: std::env::var("EXIT_SLIPPAGE_BPS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(500),
+            entry_slippage_bps: std::env::var("ENTRY_SLIPPAGE_BPS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(300),
+            max_price_age_ms: (env_f64("EXIT_MAX_PRICE_AGE_SECS", 60.0) * 1000.0) as i64,
+            stale_prices: HashSet::new(),
+            sizing_config: SizingConfig::from_env(),
+            slippage_config: SlippageConfig::from_env(),
+            jupiter: JupiterClient::from_env(),
+            fill_slippage: VecDeque::new(),
+            reconcile_config: ReconcileConfig::from_env(),
+            paper_trading: std::env::var("TRADING_MODE").map(|m| m != "live").unwrap_or(true),
+            var_config: VarConfig::from_env(),
+            correlation: CorrelationEngine::new(CorrelationConfig::from_env()),
+            strategy_fills: None,
         })
     }
 
+    /// Report fills of strategy-tagged orders back to the strategy engine
+    pub fn with_strategy_fills(mut self, strategy_fills: mpsc::Sender<StrategyFill>) -> Self {
+        self.strategy_fills = Some(strategy_fills);
+        self
+    }
+
+    fn forward_fill(&self, strategy_id: Option<&String>, report: &ExecutionReport) {
+        let (Some(strategy_fills), Some(strategy_id)) = (&self.strategy_fills, strategy_id) else {
+            return;
+        };
+        let fill = StrategyFill {
+            strategy_id: strategy_id.clone(),
+            report: report.clone(),
+        };
+        if strategy_fills.try_send(fill).is_err() {
+            warn!("⚠️ Fill for strategy {} dropped", strategy_id);
+        }
+    }
+
     pub async fn run(&mut self) -> anyhow::Result<()> {
         info!("🚀 Starting RiskManager...");
 
-        // TODO: Start risk monitoring
-        // - Listen for trading signals
-        // - Assess risk for each signal
-        // - Monitor existing positions
-        // - Send approved orders to executor
+        self.restore_positions().await;
+
+        let mut monitor_interval = tokio::time::interval(Duration::from_secs(5));
+        let mut reconcile_interval = tokio::time::interval(self.reconcile_config.interval);
+        // Startup already reconciled
+        reconcile_interval.reset();
 
-        // Placeholder implementation
         loop {
-            tokio::time::sleep(Duration::from_secs(5)).await;
-            self.monitor_positions().await?;
-            debug!("⚠️ RiskManager heartbeat - monitoring {} positions", 
-                   self.current_positions.len());
+            tokio::select! {
+                _ = monitor_interval.tick() => {
+                    self.monitor_positions().await?;
+                    debug!("⚠️ RiskManager heartbeat - monitoring {} positions", 
+                           self.current_positions.len());
+                }
+                _ = reconcile_interval.tick(), if !self.paper_trading => {
+                    if let Err(e) = self.reconcile_positions(self.reconcile_config.adopt).await {
+                        warn!("⚠️ Position reconciliation failed: {}", e);
+                    }
+                }
+                Some(signal) = self.signal_rx.recv() => {
+                    if let Err(e) = self.process_signal(&signal).await {
+                        error!("❌ Signal {} failed: {}", signal.id, e);
+                    }
+                }
+                Some(command) = self.command_rx.recv() => match command {
+                    RiskCommand::EmergencyStop { reply } => match self.emergency_stop().await {
+                        Ok(report) => {
+                            let _ = reply.send(report);
+                        }
+                        Err(e) => error!("❌ Emergency stop failed: {}", e),
+                    },
+                    RiskCommand::PortfolioMetrics { reply } => match self.get_portfolio_metrics().await {
+                        Ok(metrics) => {
+                            let _ = reply.send(metrics);
+                        }
+                        Err(e) => error!("❌ Portfolio metrics failed: {}", e),
+                    },
+                },
+            }
+        }
+    }
+
+    /// Reload persisted positions, then rebuild them from the wallet's balances
+    async fn restore_positions(&mut self) {
+        match self.cache_manager.get_risk_state(POSITIONS_KEY).await {
+            Ok(Some(value)) => match serde_json::from_value::<HashMap<String, Position>>(value) {
+                Ok(positions) => {
+                    info!("🧾 Restored {} positions from cache", positions.len());
+                    self.current_positions = positions;
+                }
+                Err(e) => warn!("⚠️ Ignoring unreadable persisted positions: {}", e),
+            },
+            Ok(None) => {}
+            Err(e) => warn!("⚠️ Failed to load persisted positions: {}", e),
+        }
+
+        if self.paper_trading {
+            return;
+        }
+        // On-chain balances are the source of truth after a restart
+        match self.reconcile_positions(true).await {
+            Ok(report) => info!("🧾 Startup reconciliation: {} discrepancies fixed", report.fixed.len()),
+            Err(e) => warn!("⚠️ Startup reconciliation failed: {}", e),
+        }
+    }
+
+    /// Compare tracked positions with on-chain balances, fixing them if `adopt` is set
+    ///
+    /// Adopted positions are entered at the latest cached price, since the real cost is unknown.
+    /// Only the default wallet is reconciled; strategy wallets may hold tokens the bot never bought.
+    pub async fn reconcile_positions(&mut self, adopt: bool) -> anyhow::Result<ReconcileReport> {
+        let wallet = self.wallet.ok_or_else(|| anyhow::anyhow!("no wallet configured"))?;
+        let balances = reconcile::wallet_balances(&self.rpc, &wallet).await?;
+        let tracked: HashMap<String, Position> = self
+            .current_positions
+            .iter()
+            .filter(|(_, position)| matches!(self.resolve_wallet(position.wallet.as_deref()), Ok(w) if w == wallet))
+            .map(|(mint, position)| (mint.clone(), position.clone()))
+            .collect();
+        let in_flight: HashSet<String> = self
+            .pending_entries
+            .keys()
+            .chain(self.pending_exits.keys())
+            .cloned()
+            .collect();
+
+        let mut report = ReconcileReport {
+            checked_at: chrono::Utc::now().timestamp(),
+            discrepancies: reconcile::compare(
+                &tracked,
+                &balances,
+                &in_flight,
+                self.reconcile_config.tolerance,
+            ),
+            fixed: Vec::new(),
+        };
+
+        for discrepancy in &report.discrepancies {
+            warn!("🧾 Position discrepancy: {:?}", discrepancy);
+            if !adopt {
+                continue;
+            }
+
+            match discrepancy {
+                Discrepancy::Untracked { mint, on_chain, decimals } => {
+                    let price = self.cache_manager.get_price_history(mint, 1).await?.first().map(|(p, _)| *p);
+                    let Some(price) = price else {
+                        warn!("⚠️ Cannot adopt {}: no price available", mint);
+                        continue;
+                    };
+                    self.add_position(Position::new(mint.clone(), *on_chain, price, *decimals, ExitRules::from_env()))
+                        .await?;
+                }
+                Discrepancy::Missing { mint, .. } => {
+                    // Sold outside the bot; the realized PnL is unknown
+                    self.remove_position(mint).await?;
+                }
+                Discrepancy::SizeMismatch { mint, on_chain, .. } => {
+                    if let Some(position) = self.current_positions.get_mut(mint) {
+                        position.size = *on_chain;
+                        position.current_pnl = position.size * (position.last_price - position.entry_price);
+                    }
+                }
+            }
+            report.fixed.push(discrepancy.mint().to_string());
+        }
+
+        if !report.fixed.is_empty() {
+            self.persist_positions().await;
+        }
+        Ok(report)
+    }
+
+    async fn persist_positions(&mut self) {
+        match serde_json::to_value(&self.current_positions) {
+            Ok(value) => {
+                if let Err(e) = self.cache_manager.store_risk_state(POSITIONS_KEY, &value).await {
+                    warn!("⚠️ Failed to persist positions: {}", e);
+                }
+            }
+            Err(e) => error!("❌ Failed to serialize positions: {}", e),
         }
     }
 
     /// Assess risk for incoming trading signal
-    async fn assess_signal_risk(&self, signal: &Value) -> anyhow::Result<bool> {
+    async fn assess_signal_risk(&mut self, signal: &SignalMessage) -> anyhow::Result<RiskDecision> {
         debug!("🔍 Assessing risk for signal: {:?}", signal);
 
-        // TODO: Implement comprehensive risk assessment
-        // - Position size validation
-        // - Portfolio correlation check
-        // - Volatility assessment
-        // - Market conditions analysis
+        let book = self.book_state(signal).await?;
+        let rules = DecisionRules {
+            limits: &self.position_limits,
+            sizing: &self.sizing_config,
+            stop_loss_pct: ExitRules::from_env().stop_loss_pct,
+        };
+        let mut decision = decision::assess(&rules, signal, &book);
+
+        // Checks against market data, only worth their RPC calls for a buy that is still approved
+        if decision.approved && signal.side == TradeSide::Buy && signal.arbitrage.is_none() {
+            self.check_portfolio_risk(signal, &mut decision).await?;
+            self.check_correlation(signal, &mut decision).await?;
 
-        // Basic checks
-        let approved = self.check_position_limits(signal).await? &&
-                      self.check_portfolio_risk(signal).await? &&
-                      self.check_market_conditions().await?;
+            if decision.approved {
+                for reason in self.check_market_conditions(&signal.mint).await? {
+                    decision.reject(reason.to_string());
+                }
+            }
+            self.check_price_impact(signal, &mut decision).await?;
+            decision::check_min_order_size(&self.position_limits, &mut decision);
+        }
 
-        if approved {
-            info!("✅ Signal approved by risk manager");
+        if decision.approved {
+            info!("✅ Signal {} approved by risk manager: {:.4} SOL", signal.id, decision.adjusted_size);
         } else {
-            warn!("❌ Signal rejected by risk manager");
+            warn!("❌ Signal {} rejected by risk manager: {}", signal.id, decision.reasons.join("; "));
+        }
+
+        Ok(decision)
+    }
+
+    /// Book state for the offline rules; balances, slots and history are only fetched for buys
+    async fn book_state(&mut self, signal: &SignalMessage) -> anyhow::Result<BookState> {
+        let position = self.current_positions.get(&signal.mint);
+        let mut book = BookState {
+            mint_exposure: self.mint_exposure(&signal.mint),
+            total_exposure: self.total_exposure(),
+            available_capital: Ok(0.0),
+            halted: None,
+            deadline_passed: false,
+            order_in_flight: self.pending_entries.contains_key(&signal.mint)
+                || self.pending_exits.contains_key(&signal.mint),
+            held_by_other_wallet: position
+                .filter(|p| p.wallet != signal.wallet)
+                .map(|p| p.wallet.clone().unwrap_or_else(|| "default".to_string())),
+            volatility: None,
+        };
+        if signal.side == TradeSide::Sell {
+            return Ok(book);
+        }
+
+        if self.circuit_breaker.is_halted() {
+            book.halted = Some(self.circuit_breaker.halt_reason().unwrap_or_default());
+            return Ok(book);
+        }
+        book.deadline_passed = !self.within_deadline(signal).await;
+        book.available_capital = self
+            .available_capital(signal.wallet.as_deref())
+            .await
+            .map_err(|e| e.to_string());
+        if signal.size_sol.is_none() && self.sizing_config.model == SizingModel::VolatilityTarget {
+            book.volatility = self.signal_volatility(&signal.mint).await?;
         }
+        Ok(book)
+    }
 
-        Ok(approved)
+    /// Whether the chain has not yet moved past the signal's deadline slot
+    ///
+    /// Fails closed when the current slot cannot be fetched.
+    async fn within_deadline(&self, signal: &SignalMessage) -> bool {
+        let Some(deadline) = signal.deadline_slot else { return true };
+        match self.rpc.get_slot().await {
+            Ok(slot) => slot <= deadline,
+            Err(e) => {
+                warn!("⚠️ Slot unavailable for deadline check: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Assess a signal and send the approved order to the executor
+    pub async fn process_signal(&mut self, signal: &SignalMessage) -> anyhow::Result<RiskDecision> {
+        let decision = self.assess_signal_risk(signal).await?;
+        if !decision.approved {
+            return Ok(decision);
+        }
+
+        match (signal.side, &signal.arbitrage) {
+            (TradeSide::Buy, Some(arbitrage)) => self.send_arbitrage(signal, arbitrage, &decision).await?,
+            (TradeSide::Buy, None) => self.send_entry(signal, &decision).await?,
+            (TradeSide::Sell, _) => {
+                if let Some(position) = self.current_positions.get(&signal.mint) {
+                    let fraction = (decision.adjusted_size / position.market_value()).min(1.0);
+                    let trigger = ExitTrigger { reason: ExitReason::Signal, fraction };
+                    self.send_exit(&signal.mint, trigger).await?;
+                }
+            }
+        }
+
+        Ok(decision)
+    }
+
+    /// Send a market buy for the approved size
+    async fn send_entry(&mut self, signal: &SignalMessage, decision: &RiskDecision) -> anyhow::Result<()> {
+        let decimals = self.token_profile(&signal.mint).await?.decimals;
+        let amount = (decision.adjusted_size * LAMPORTS_PER_SOL as f64).floor() as u64;
+
+        let (report_tx, report_rx) = oneshot::channel();
+        self.execution_tx
+            .send(ExecutionMessage::Order(OrderRequest {
+                id: signal.id.clone(),
+                mint: signal.mint.clone(),
+                side: TradeSide::Buy,
+                order_type: OrderType::Market,
+                amount,
+                slippage_bps: self.entry_slippage_bps,
+                priority: ExecutionPriority::Normal,
+                wallet: signal.wallet.clone(),
+                reply: Some(report_tx),
+            }))
+            .await?;
+
+        self.pending_entries.insert(signal.mint.clone(), PendingEntry {
+            decimals,
+            wallet: signal.wallet.clone(),
+            exit_rules: ExitRules::from_env().with_levels(signal.price, signal.target_price, signal.stop_price),
+            strategy_id: signal.strategy_id.clone(),
+            impact: decision.price_impact.clone(),
+            report_rx,
+        });
+        Ok(())
+    }
+
+    /// Send an atomic round trip; its outcome is booked straight to realized PnL
+    async fn send_arbitrage(
+        &mut self,
+        signal: &SignalMessage,
+        arbitrage: &ArbitrageOrder,
+        decision: &RiskDecision,
+    ) -> anyhow::Result<()> {
+        let amount = (decision.adjusted_size * LAMPORTS_PER_SOL as f64).floor() as u64;
+
+        let (report_tx, report_rx) = oneshot::channel();
+        self.execution_tx
+            .send(ExecutionMessage::Order(OrderRequest {
+                id: signal.id.clone(),
+                mint: signal.mint.clone(),
+                side: TradeSide::Buy,
+                order_type: OrderType::Arbitrage(Box::new(arbitrage.clone())),
+                amount,
+                slippage_bps: self.entry_slippage_bps,
+                priority: ExecutionPriority::High,
+                wallet: signal.wallet.clone(),
+                reply: Some(report_tx),
+            }))
+            .await?;
+
+        // Nothing is left open, so the result only has to reach PnL and the strategy
+        let circuit_breaker = self.circuit_breaker.clone();
+        let strategy_fills = self.strategy_fills.clone();
+        let strategy_id = signal.strategy_id.clone();
+        tokio::spawn(async move {
+            let Ok(report) = report_rx.await else { return };
+            if report.success {
+                let pnl = (report.out_amount as f64 - report.in_amount as f64) / LAMPORTS_PER_SOL as f64;
+                info!("🔀 Arbitrage {} on {} closed with {:+.6} SOL", report.order_id, report.mint, pnl);
+                if !report.simulated {
+                    circuit_breaker.record_realized(pnl).await;
+                }
+            } else {
+                warn!("🔀 Arbitrage {} on {} did not land: {}",
+                      report.order_id, report.mint, report.error.as_deref().unwrap_or("unknown"));
+            }
+            if let (Some(strategy_fills), Some(strategy_id)) = (strategy_fills, strategy_id) {
+                let _ = strategy_fills.try_send(StrategyFill { strategy_id, report });
+            }
+        });
+        Ok(())
+    }
+
+    /// Open or add to positions for buy orders the executor has finished
+    async fn collect_entry_fills(&mut self) -> anyhow::Result<()> {
+        let mut finished = Vec::new();
+        for (mint, pending) in &mut self.pending_entries {
+            match pending.report_rx.try_recv() {
+                Ok(report) => finished.push((mint.clone(), Some(report))),
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => finished.push((mint.clone(), None)),
+            }
+        }
+
+        for (mint, report) in finished {
+            let Some(pending) = self.pending_entries.remove(&mint) else { continue };
+            let decimals = pending.decimals;
+            if let Some(report) = &report {
+                self.forward_fill(pending.strategy_id.as_ref(), report);
+            }
+
+            let Some(report) = report.filter(|r| r.success) else {
+                warn!("⚠️ Entry into {} failed", mint);
+                continue;
+            };
+
+            if let Some(estimate) = pending.impact {
+                self.record_fill_slippage(&report, estimate);
+            }
+
+            let quantity = report.out_amount as f64 / 10f64.powi(decimals as i32);
+            if quantity <= 0.0 {
+                continue;
+            }
+            let price = report.in_amount as f64 / LAMPORTS_PER_SOL as f64 / quantity;
+
+            match self.current_positions.get_mut(&mint) {
+                Some(position) => {
+                    // Average into the open position
+                    let cost = position.size * position.entry_price + quantity * price;
+                    position.size += quantity;
+                    position.entry_price = cost / position.size;
+                    info!("📈 Added {} {} @ {} - entry now {}", quantity, mint, price, position.entry_price);
+                }
+                None => {
+                    let mut position = Position::new(mint, quantity, price, decimals, pending.exit_rules);
+                    position.strategy_id = pending.strategy_id;
+                    position.wallet = pending.wallet;
+                    self.add_position(position).await?;
+                }
+            }
+        }
+
+        Ok(())
     }
 
-    /// Check if signal respects position limits
-    async fn check_position_limits(&self, signal: &Value) -> anyhow::Result<bool> {
-        // TODO: Extract signal data and validate against limits
-        // - Check max position size
-        // - Verify available capital
-        // - Validate position concentration
+    /// Keep the estimate next to the fill so impact estimates can be audited
+    fn record_fill_slippage(&mut self, report: &ExecutionReport, estimate: ImpactEstimate) {
+        if estimate.expected_out == 0 {
+            return;
+        }
+        // Scale the estimate to what was actually spent
+        let spent_sol = report.in_amount as f64 / LAMPORTS_PER_SOL as f64;
+        let expected = estimate.expected_out as f64 * spent_sol / estimate.size_sol;
+        let shortfall = 1.0 - report.out_amount as f64 / expected;
+
+        info!("💧 Fill for {} received {:.2}% {} than the {:?} estimate",
+              report.mint, shortfall.abs() * 100.0, if shortfall > 0.0 { "less" } else { "more" }, estimate.source);
 
-        Ok(true) // Placeholder
+        self.fill_slippage.push_back(FillSlippage {
+            order_id: report.order_id.clone(),
+            mint: report.mint.clone(),
+            estimate,
+            actual_out: report.out_amount,
+            shortfall,
+        });
+        while self.fill_slippage.len() > FILL_SLIPPAGE_HISTORY {
+            self.fill_slippage.pop_front();
+        }
+    }
+
+    /// Per-interval return volatility from cached prices, for volatility-target sizing
+    async fn signal_volatility(&mut self, mint: &str) -> anyhow::Result<Option<f64>> {
+        let (interval_ms, lookback) = (self.sizing_config.vol_interval_ms, self.sizing_config.vol_lookback);
+        let history = self.cache_manager.get_price_history(mint, lookback).await?;
+        let returns = var::aligned_log_returns(&[history], interval_ms, lookback.max(0) as usize);
+        Ok(sizing::volatility(&returns[0]))
     }
 
     /// Check portfolio-level risk
-    async fn check_portfolio_risk(&self, signal: &Value) -> anyhow::Result<bool> {
-        // TODO: Calculate portfolio risk metrics
-        // - Total portfolio exposure
-        // - Correlation between positions
-        // - Sector concentration
-        // - VaR (Value at Risk) calculation
+    async fn check_portfolio_risk(&mut self, signal: &SignalMessage, decision: &mut RiskDecision) -> anyhow::Result<()> {
+        if !decision.approved {
+            return Ok(());
+        }
+
+        // Portfolio value = spendable SOL + open exposure
+        let Ok(available) = self.available_capital(signal.wallet.as_deref()).await else {
+            return Ok(());
+        };
+        let portfolio_value = available + self.total_exposure();
+        let confidence = self.var_config.confidence;
 
-        Ok(true) // Placeholder
+        if let Some(estimate) = self
+            .portfolio_var(Some((&signal.mint, decision.adjusted_size)), confidence)
+            .await?
+        {
+            let limit = self.position_limits.max_portfolio_risk * portfolio_value;
+            debug!("📉 Pre-trade VaR {:.4} SOL (CVaR {:.4}) vs limit {:.4} SOL",
+                   estimate.var, estimate.cvar, limit);
+            if estimate.var > limit {
+                decision.reject(format!(
+                    "{:?} VaR {:.4} SOL at {:.0}% exceeds {:.1}% of portfolio ({:.4} SOL)",
+                    estimate.method,
+                    estimate.var,
+                    confidence * 100.0,
+                    self.position_limits.max_portfolio_risk * 100.0,
+                    limit
+                ));
+            }
+        }
+
+        Ok(())
     }
 
-    /// Check current market conditions
-    async fn check_market_conditions(&self) -> anyhow::Result<bool> {
-        // TODO: Assess market conditions
-        // - Volatility levels
-        // - Liquidity conditions
-        // - Market sentiment
-        // - Economic events
+    /// Reject or shrink buys that move with positions already held
+    async fn check_correlation(&mut self, signal: &SignalMessage, decision: &mut RiskDecision) -> anyhow::Result<()> {
+        if !decision.approved {
+            return Ok(());
+        }
+
+        self.refresh_correlation(&signal.mint).await?;
+        let held: Vec<String> = self
+            .current_positions
+            .keys()
+            .filter(|mint| **mint != signal.mint)
+            .cloned()
+            .collect();
+        for mint in &held {
+            self.refresh_correlation(mint).await?;
+        }
+
+        let Some((mint, rho)) = held
+            .iter()
+            .filter_map(|mint| Some((mint, self.correlation.correlation(&signal.mint, mint)?)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+        else {
+            return Ok(());
+        };
 
-        Ok(true) // Placeholder
+        let max = self.position_limits.max_correlation;
+        debug!("🔗 Highest correlation of {} with the book: {:.2} ({})", signal.mint, rho, mint);
+        if rho <= max {
+            return Ok(());
+        }
+
+        if self.correlation.config().downsize && max < 1.0 {
+            // Scale linearly from full size at the limit to nothing at perfect correlation
+            let scale = ((1.0 - rho) / (1.0 - max)).clamp(0.0, 1.0);
+            decision.cap(decision.adjusted_size * scale, &format!("correlation {:.2} with {}", rho, mint));
+        } else {
+            decision.reject(format!("correlation {:.2} with {} exceeds {:.2}", rho, mint, max));
+        }
+
+        Ok(())
+    }
+
+    /// Feed cached prices newer than the last one seen into the correlation engine
+    async fn refresh_correlation(&mut self, mint: &str) -> anyhow::Result<()> {
+        let config = self.correlation.config();
+        let limit = if self.correlation.is_tracked(mint) {
+            // Enough to cover a few monitor cycles of ticks
+            256
+        } else {
+            (config.window * 4).max(config.min_samples) as isize
+        };
+        let since = self.correlation.last_timestamp(mint).unwrap_or(i64::MIN);
+
+        let history = self.cache_manager.get_price_history(mint, limit).await?;
+        for (price, timestamp) in history.into_iter().rev().filter(|(_, ts)| *ts > since) {
+            self.correlation.update(mint, price, timestamp);
+        }
+
+        Ok(())
+    }
+
+    /// Reject or shrink buys that would move the price more than allowed
+    async fn check_price_impact(&mut self, signal: &SignalMessage, decision: &mut RiskDecision) -> anyhow::Result<()> {
+        if !decision.approved {
+            return Ok(());
+        }
+
+        // Only a SOL-quoted pool can price a SOL-denominated buy
+        let pool = match self.token_profile(&signal.mint).await {
+            Ok(profile) => profile
+                .liquidity
+                .filter(|liquidity| liquidity.quote_mint == WSOL_MINT)
+                .map(|liquidity| liquidity.pool),
+            Err(_) => None,
+        };
+
+        let check = slippage::check_buy(
+            &self.slippage_config,
+            &self.jupiter,
+            &self.rpc,
+            &signal.mint,
+            pool.as_deref(),
+            decision.adjusted_size,
+            self.entry_slippage_bps,
+        )
+        .await;
+
+        // Fail closed: an unknown impact is not a small one
+        let check = match check {
+            Ok(check) => check,
+            Err(e) => {
+                decision.reject(format!("price impact unknown: {}", e));
+                return Ok(());
+            }
+        };
+
+        let max = self.slippage_config.max_price_impact;
+        debug!("💧 {:?} impact for {:.4} SOL of {}: {:.2}%",
+               check.requested.source, check.requested.size_sol, signal.mint, check.requested.price_impact * 100.0);
+
+        match check.allowed {
+            Some(allowed) => {
+                decision.cap(allowed.size_sol, &format!("{:.1}% price impact limit", max * 100.0));
+                decision.price_impact = Some(allowed);
+            }
+            None => decision.reject(format!(
+                "price impact {:.2}% exceeds {:.2}% at any size",
+                check.requested.price_impact * 100.0,
+                max * 100.0
+            )),
+        }
+
+        Ok(())
+    }
+
+    /// SOL exposure to one mint
+    fn mint_exposure(&self, mint: &str) -> f64 {
+        self.current_positions.get(mint).map(Position::market_value).unwrap_or(0.0)
+    }
+
+    /// SOL exposure across all positions
+    fn total_exposure(&self) -> f64 {
+        self.current_positions.values().map(Position::market_value).sum()
+    }
+
+    /// Spendable SOL in `wallet` (the default trading wallet if `None`) after the fee reserve
+    async fn available_capital(&self, wallet: Option<&str>) -> anyhow::Result<f64> {
+        let wallet = self.resolve_wallet(wallet)?;
+        let lamports = self.rpc.get_balance(&wallet).await?;
+        Ok(lamports as f64 / LAMPORTS_PER_SOL as f64 - self.position_limits.min_sol_reserve)
+    }
+
+    /// Public key of a signal's or position's wallet, falling back to the default trading wallet
+    fn resolve_wallet(&self, wallet: Option<&str>) -> anyhow::Result<Pubkey> {
+        match wallet {
+            Some(wallet) => Pubkey::from_str(wallet).map_err(|e| anyhow::anyhow!("invalid wallet {}: {}", wallet, e)),
+            None => self.wallet.ok_or_else(|| anyhow::anyhow!("no wallet configured")),
+        }
+    }
+
+    /// Check current market conditions; returns every rug-screening rule the mint fails
+    async fn check_market_conditions(&mut self, mint: &str) -> anyhow::Result<Vec<RejectionReason>> {
+        let report = self.screen_token(mint).await;
+        Ok(report.reasons)
+    }
+
+    /// Screen a mint against the rug-pull and honeypot rules
+    pub async fn screen_token(&mut self, mint: &str) -> ScreeningReport {
+        let profile = match self.token_profile(mint).await {
+            Ok(profile) => profile,
+            Err(e) => {
+                return ScreeningReport {
+                    mint: mint.to_string(),
+                    reasons: vec![RejectionReason::ProfileUnavailable(e.to_string())],
+                };
+            }
+        };
+
+        let report = self.rug_screener.screen(&profile).await;
+        if !report.passed() {
+            debug!("🧪 {} failed {} screening rules", mint, report.reasons.len());
+        }
+        report
+    }
+
+    /// Cached token profile, fetched over RPC on a miss
+    async fn token_profile(&mut self, mint: &str) -> anyhow::Result<TokenProfile> {
+        match self.cache_manager.get_token_profile(mint).await {
+            Ok(Some(profile)) => return Ok(profile),
+            Ok(None) => {}
+            Err(e) => warn!("⚠️ Token profile cache read failed for {}: {}", mint, e),
+        }
+
+        let profile = self.token_profiler.fetch(mint).await?;
+        if let Err(e) = self.cache_manager.store_token_profile(&profile).await {
+            warn!("⚠️ Failed to cache token profile for {}: {}", mint, e);
+        }
+        Ok(profile)
     }
 
     /// Monitor existing positions for risk
     async fn monitor_positions(&mut self) -> anyhow::Result<()> {
+        self.collect_entry_fills().await?;
+        self.collect_exit_fills().await?;
+
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let now = now_ms / 1000;
+        let mut triggered = Vec::new();
+
         for (symbol, position) in &mut self.current_positions {
-            // TODO: Update position PnL
-            // TODO: Check stop-loss conditions
-            // TODO: Assess position risk
-            // TODO: Trigger position adjustments if needed
+            let mut priced_at = None;
+            match self.cache_manager.get_price_history(symbol, 1).await {
+                Ok(latest) => {
+                    if let Some((price, timestamp)) = latest.first() {
+                        self.correlation.update(symbol, *price, *timestamp);
+                        position.last_price = *price;
+                        position.current_pnl = position.size * (price - position.entry_price);
+                        priced_at = Some(*timestamp);
+                    }
+                }
+                Err(e) => warn!("⚠️ No live price for {}: {}", symbol, e),
+            }
+
+            // One exit in flight per position
+            if self.pending_exits.contains_key(symbol) {
+                continue;
+            }
+
+            // A stop evaluated on an old price can fire too late or not at all
+            let fresh = priced_at.is_some_and(|at| now_ms - at <= self.max_price_age_ms);
+            if !fresh {
+                if self.stale_prices.insert(symbol.clone()) {
+                    error!("🚨 No price for {} in the last {}s - exit rules paused (last price at {:?})",
+                           symbol, self.max_price_age_ms / 1000, priced_at);
+                }
+                continue;
+            }
+            if self.stale_prices.remove(symbol) {
+                info!("✅ Prices for {} resumed - exit rules active", symbol);
+            }
+
+            if let Some(trigger) = exits::evaluate(
+                &position.exit_rules,
+                &mut position.exit_state,
+                position.entry_price,
+                position.last_price,
+                now,
+            ) {
+                triggered.push((symbol.clone(), trigger));
+            }
 
             debug!("📊 Monitoring position: {} - PnL: {:.2}", 
                    symbol, position.current_pnl);
         }
 
+        for (symbol, trigger) in triggered {
+            self.send_exit(&symbol, trigger).await?;
+        }
+
+        let unrealized: f64 = self.current_positions.values().map(|p| p.current_pnl).sum();
+        self.stale_prices.retain(|mint| self.current_positions.contains_key(mint));
+        self.circuit_breaker.set_unrealized(unrealized).await;
+        self.correlation.prune(chrono::Utc::now().timestamp_millis());
+        self.persist_positions().await;
+
+        Ok(())
+    }
+
+    /// Send a market sell for part or all of a position
+    async fn send_exit(&mut self, symbol: &str, trigger: ExitTrigger) -> anyhow::Result<()> {
+        let Some(position) = self.current_positions.get(symbol) else {
+            return Ok(());
+        };
+
+        let quantity = position.size * trigger.fraction;
+        let amount = (quantity * 10f64.powi(position.decimals as i32)).floor() as u64;
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let priority = match trigger.reason {
+            ExitReason::StopLoss | ExitReason::TrailingStop => ExecutionPriority::High,
+            ExitReason::TakeProfit(_) | ExitReason::MaxHold | ExitReason::Signal => ExecutionPriority::Normal,
+        };
+
+        info!("🎯 {:?} triggered for {} @ {} - selling {:.0}%",
+              trigger.reason, symbol, position.last_price, trigger.fraction * 100.0);
+
+        let (report_tx, report_rx) = oneshot::channel();
+        self.execution_tx
+            .send(ExecutionMessage::Order(OrderRequest {
+                id: format!("exit-{}-{}", symbol, chrono::Utc::now().timestamp_millis()),
+                mint: symbol.to_string(),
+                side: TradeSide::Sell,
+                order_type: OrderType::Market,
+                amount,
+                slippage_bps: self.exit_slippage_bps,
+                priority,
+                wallet: position.wallet.clone(),
+                reply: Some(report_tx),
+            }))
+            .await?;
+
+        self.pending_exits.insert(symbol.to_string(), PendingExit {
+            reason: trigger.reason,
+            decimals: position.decimals,
+            report_rx,
+        });
+
+        Ok(())
+    }
+
+    /// Book fills of exit orders the executor has finished
+    async fn collect_exit_fills(&mut self) -> anyhow::Result<()> {
+        let mut finished = Vec::new();
+        for (symbol, pending) in &mut self.pending_exits {
+            match pending.report_rx.try_recv() {
+                Ok(report) => finished.push((symbol.clone(), pending.reason, pending.decimals, Some(report))),
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => {
+                    finished.push((symbol.clone(), pending.reason, pending.decimals, None))
+                }
+            }
+        }
+
+        for (symbol, reason, decimals, report) in finished {
+            self.pending_exits.remove(&symbol);
+            if let Some(report) = &report {
+                let strategy_id = self.current_positions.get(&symbol).and_then(|p| p.strategy_id.clone());
+                self.forward_fill(strategy_id.as_ref(), report);
+            }
+
+            let Some(report) = report.filter(|r| r.success) else {
+                warn!("⚠️ {:?} exit for {} failed - will retry", reason, symbol);
+                continue;
+            };
+
+            let quantity = report.in_amount as f64 / 10f64.powi(decimals as i32);
+            if quantity <= 0.0 {
+                continue;
+            }
+            let exit_price = report.out_amount as f64 / LAMPORTS_PER_SOL as f64 / quantity;
+
+            if let ExitReason::TakeProfit(tier) = reason {
+                if let Some(position) = self.current_positions.get_mut(&symbol) {
+                    position.exit_state.tiers_filled = tier + 1;
+                }
+            }
+            self.close_position(&symbol, quantity, exit_price).await?;
+        }
+
         Ok(())
     }
 
     /// Calculate Value at Risk (VaR) for portfolio
-    async fn calculate_var(&self, confidence_level: f64) -> anyhow::Result<f64> {
-        // TODO: Implement VaR calculation
-        // - Historical simulation method
-        // - Monte Carlo simulation
-        // - Parametric method
+    pub async fn calculate_var(&mut self, confidence_level: f64) -> anyhow::Result<Option<VarEstimate>> {
+        self.portfolio_var(None, confidence_level).await
+    }
+
+    /// VaR of the current book, plus `candidate` (mint, SOL size) if given
+    ///
+    /// Mints without enough price history are left out; `None` if nothing is left.
+    async fn portfolio_var(
+        &mut self,
+        candidate: Option<(&str, f64)>,
+        confidence_level: f64,
+    ) -> anyhow::Result<Option<VarEstimate>> {
+        let mut holdings: HashMap<String, f64> = self
+            .current_positions
+            .iter()
+            .map(|(mint, position)| (mint.clone(), position.market_value()))
+            .collect();
+        if let Some((mint, size)) = candidate {
+            *holdings.entry(mint.to_string()).or_default() += size;
+        }
 
-        Ok(0.0) // Placeholder
+        let mut values = Vec::new();
+        let mut histories = Vec::new();
+        for (mint, value) in &holdings {
+            let history = self.cache_manager.get_price_history(mint, self.var_config.lookback).await?;
+            if history.len() > self.var_config.min_samples {
+                values.push(*value);
+                histories.push(history);
+            } else {
+                debug!("📉 {} left out of VaR: {} price samples", mint, history.len());
+            }
+        }
+
+        if values.is_empty() {
+            return Ok(None);
+        }
+
+        let returns = var::aligned_log_returns(
+            &histories,
+            self.var_config.interval_ms,
+            self.var_config.lookback as usize,
+        );
+        if returns[0].len() < self.var_config.min_samples {
+            debug!("📉 VaR skipped: only {} aligned returns", returns[0].len());
+            return Ok(None);
+        }
+
+        Ok(var::estimate(&self.var_config, confidence_level, &values, &returns))
     }
 
-    /// Calculate position correlation
-    async fn calculate_correlation(&self, symbol1: &str, symbol2: &str) -> anyhow::Result<f64> {
-        // TODO: Calculate correlation between positions
-        // - Price correlation
-        // - Return correlation
-        // - Risk factor correlation
+    /// Return correlation matrix of the open positions
+    pub async fn calculate_correlation(&mut self) -> anyhow::Result<CorrelationMatrix> {
+        let mut mints: Vec<String> = self.current_positions.keys().cloned().collect();
+        mints.sort();
+        for mint in &mints {
+            self.refresh_correlation(mint).await?;
+        }
 
-        Ok(0.0) // Placeholder
+        Ok(self.correlation.matrix(&mints))
     }
 
     /// Emergency stop - close all positions
-    async fn emergency_stop(&mut self) -> anyhow::Result<()> {
+    async fn emergency_stop(&mut self) -> anyhow::Result<EmergencyStopReport> {
         warn!("🚨 EMERGENCY STOP TRIGGERED - Closing all positions");
 
-        // TODO: Implement emergency stop
-        // - Close all open positions
-        // - Cancel pending orders
-        // - Notify administrators
-        // - Log emergency event
+        // Block new entries while (and after) we flatten
+        self.circuit_breaker
+            .set_halted(true, Some("emergency stop".to_string()))
+            .await;
 
-        self.current_positions.clear();
-        
-        Ok(())
+        let mut holdings = Vec::new();
+        for (mint, position) in &self.current_positions {
+            let wallet = self.resolve_wallet(position.wallet.as_deref()).map_err(|e| {
+                anyhow::anyhow!("Emergency stop cannot check {} balances: {}", mint, e)
+            })?;
+            holdings.push((mint.clone(), wallet));
+        }
+
+        let report = emergency::liquidate(
+            &self.execution_tx,
+            &self.rpc,
+            holdings,
+            &self.emergency_config,
+        )
+        .await;
+
+        for outcome in &report.closed {
+            let Some(size) = self.current_positions.get(&outcome.mint).map(|p| p.size) else {
+                continue;
+            };
+            let exit_price = if size > 0.0 {
+                outcome.sol_received_lamports as f64 / LAMPORTS_PER_SOL as f64 / size
+            } else {
+                0.0
+            };
+            self.close_position(&outcome.mint, size, exit_price).await?;
+        }
+
+        if report.not_closed.is_empty() {
+            warn!("🚨 Emergency stop complete: {} positions closed, {} orders cancelled",
+                  report.closed.len(), report.cancelled_orders);
+        } else {
+            error!("🚨 Emergency stop incomplete: {} closed, {} still open",
+                   report.closed.len(), report.not_closed.len());
+        }
+        info!("🚨 Emergency stop report: {}", serde_json::to_string(&report)?);
+
+        Ok(report)
     }
 
     /// Add new position to tracking
@@ -200,8 +1210,36 @@ impl RiskManager {
         Ok(())
     }
 
+    /// Close `quantity` tokens of a position at `exit_price` and book the realized PnL
+    pub async fn close_position(&mut self, symbol: &str, quantity: f64, exit_price: f64) -> anyhow::Result<f64> {
+        let position = self
+            .current_positions
+            .get_mut(symbol)
+            .ok_or_else(|| anyhow::anyhow!("No open position in {}", symbol))?;
+
+        let quantity = quantity.min(position.size);
+        let realized = quantity * (exit_price - position.entry_price);
+        // Unrealized PnL shrinks with the position
+        if position.size > 0.0 {
+            position.current_pnl *= 1.0 - quantity / position.size;
+        }
+        position.size -= quantity;
+
+        info!("📉 Closed {} of {} @ {} - realized PnL {:.4} SOL", quantity, symbol, exit_price, realized);
+
+        if position.size <= f64::EPSILON {
+            self.current_positions.remove(symbol);
+        }
+        self.circuit_breaker.record_realized(realized).await;
+
+        Ok(realized)
+    }
+
     /// Get current portfolio metrics
-    pub async fn get_portfolio_metrics(&self) -> anyhow::Result<Value> {
+    pub async fn get_portfolio_metrics(&mut self) -> anyhow::Result<Value> {
+        let var = self.calculate_var(self.var_config.confidence).await?;
+        let correlation = self.calculate_correlation().await?;
+
         let total_positions = self.current_positions.len();
         let total_pnl: f64 = self.current_positions.values()
             .map(|p| p.current_pnl)
@@ -210,8 +1248,13 @@ impl RiskManager {
         let metrics = serde_json::json!({
             "total_positions": total_positions,
             "total_pnl": total_pnl,
+            "total_exposure": self.total_exposure(),
             "max_position_size": self.position_limits.max_position_size,
             "max_daily_loss": self.position_limits.max_daily_loss,
+            "circuit_breaker": self.circuit_breaker.state(),
+            "fill_slippage": self.fill_slippage,
+            "var": var,
+            "correlation": correlation,
             "positions": self.current_positions
         });
 
@@ -219,6 +1262,7 @@ impl RiskManager {
     }
 
     /// Health check for risk manager
+    #[allow(dead_code)]
     pub async fn health_check(&self) -> anyhow::Result<bool> {
         // TODO: Check risk manager health
         // - Verify position tracking
@@ -228,3 +1272,175 @@ impl RiskManager {
         Ok(true)
     }
 }
+
+impl Position {
+    pub fn new(symbol: String, size: f64, entry_price: f64, decimals: u8, exit_rules: ExitRules) -> Self {
+        Self {
+            symbol,
+            size,
+            entry_price,
+            current_pnl: 0.0,
+            risk_score: 0.0,
+            decimals,
+            last_price: entry_price,
+            exit_rules,
+            exit_state: ExitState::new(entry_price),
+            strategy_id: None,
+            wallet: None,
+        }
+    }
+
+    /// Current SOL value of the position
+    pub fn market_value(&self) -> f64 {
+        (self.size * self.entry_price + self.current_pnl).max(0.0)
+    }
+}
+
+fn env_f64(key: &str, default: f64) -> f64 {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Trading wallet public key, if a private key is configured
+fn wallet_pubkey() -> Option<Pubkey> {
+    let private_key = std::env::var("SOLANA_PRIVATE_KEY").ok()?;
+    let bytes = bs58::decode(private_key).into_vec().ok()?;
+    Keypair::try_from(bytes.as_slice()).ok().map(|keypair| keypair.pubkey())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::strategy_engine::{StrategyConfig, StrategySignal};
+    use crate::modules::cache_manager::fake;
+
+    async fn risk_manager() -> (RiskManager, mpsc::Receiver<ExecutionMessage>) {
+        let cache = fake::cache().await;
+        let circuit_breaker = CircuitBreaker::load(cache.clone(), 5.0).await.unwrap();
+        let (_signal_tx, signal_rx) = mpsc::channel(1);
+        let (execution_tx, execution_rx) = mpsc::channel(8);
+        let (_command_tx, command_rx) = mpsc::channel(1);
+        let risk_manager = RiskManager::new(cache, circuit_breaker, signal_rx, execution_tx, command_rx)
+            .await
+            .unwrap();
+        (risk_manager, execution_rx)
+    }
+
+    fn signal(side: TradeSide, size_sol: Option<f64>) -> SignalMessage {
+        let config: StrategyConfig = serde_json::from_value(serde_json::json!({
+            "id": "momentum-1",
+            "kind": "momentum",
+            "capital_sol": 2.0,
+        }))
+        .unwrap();
+        let signal = StrategySignal {
+            mint: "MintA".to_string(),
+            side,
+            size_sol,
+            price: 0.01,
+            confidence: 0.6,
+            expected_return: None,
+            target_price: None,
+            stop_price: None,
+            arbitrage: None,
+            deadline_slot: None,
+            timestamp_ms: 1_700_000_000_000,
+        };
+        SignalMessage::from_strategy(signal, &config, 1, 2.0)
+    }
+
+    async fn open_position(risk_manager: &mut RiskManager) {
+        // 100 tokens with 6 decimals at 0.01 SOL: 1 SOL of exposure
+        let position = Position::new("MintA".to_string(), 100.0, 0.01, 6, ExitRules::from_env());
+        risk_manager.add_position(position).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn sell_signal_becomes_exit_order_for_held_size() {
+        let (mut risk_manager, mut execution_rx) = risk_manager().await;
+        open_position(&mut risk_manager).await;
+
+        let decision = risk_manager.process_signal(&signal(TradeSide::Sell, Some(0.25))).await.unwrap();
+        assert!(decision.approved, "{:?}", decision.reasons);
+        assert_eq!(decision.adjusted_size, 0.25);
+
+        let Ok(ExecutionMessage::Order(order)) = execution_rx.try_recv() else {
+            panic!("no order sent");
+        };
+        assert_eq!(order.mint, "MintA");
+        assert_eq!(order.side, TradeSide::Sell);
+        assert!(matches!(order.order_type, OrderType::Market));
+        // A quarter of the position, in raw token units
+        assert_eq!(order.amount, 25_000_000);
+        assert_eq!(order.priority, ExecutionPriority::Normal);
+        assert_eq!(order.wallet, None);
+        assert!(order.reply.is_some());
+        assert!(risk_manager.pending_exits.contains_key("MintA"));
+    }
+
+    #[tokio::test]
+    async fn oversized_sell_is_capped_and_second_sell_waits_for_the_first() {
+        let (mut risk_manager, mut execution_rx) = risk_manager().await;
+        open_position(&mut risk_manager).await;
+
+        let decision = risk_manager.process_signal(&signal(TradeSide::Sell, Some(5.0))).await.unwrap();
+        assert!(decision.approved);
+        assert_eq!(decision.adjusted_size, 1.0);
+        let Ok(ExecutionMessage::Order(order)) = execution_rx.try_recv() else {
+            panic!("no order sent");
+        };
+        assert_eq!(order.amount, 100_000_000);
+
+        let decision = risk_manager.process_signal(&signal(TradeSide::Sell, None)).await.unwrap();
+        assert!(!decision.approved);
+        assert_eq!(decision.reasons, vec!["order for MintA already in flight".to_string()]);
+        assert!(execution_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn sell_without_position_is_rejected() {
+        let (mut risk_manager, mut execution_rx) = risk_manager().await;
+
+        let decision = risk_manager.process_signal(&signal(TradeSide::Sell, None)).await.unwrap();
+        assert!(!decision.approved);
+        assert_eq!(decision.reasons, vec!["no open position in MintA".to_string()]);
+        assert!(execution_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn buy_is_rejected_while_halted() {
+        let (mut risk_manager, mut execution_rx) = risk_manager().await;
+        risk_manager.circuit_breaker.set_halted(true, Some("test halt".to_string())).await;
+
+        let decision = risk_manager.process_signal(&signal(TradeSide::Buy, Some(0.5))).await.unwrap();
+        assert!(!decision.approved);
+        assert_eq!(decision.reasons, vec!["trading halted: test halt".to_string()]);
+        assert!(execution_rx.try_recv().is_err());
+        assert!(risk_manager.pending_entries.is_empty());
+    }
+
+    #[tokio::test]
+    async fn reconcile_adopts_on_chain_sizes_and_drops_sold_positions() {
+        let (mut risk_manager, _execution_rx) = risk_manager().await;
+        let held = emergency::fake_rpc::Balances::default();
+        held.lock().await.insert("MintA".to_string(), (60_000_000, 6));
+        risk_manager.rpc = emergency::fake_rpc::serve(held).await;
+        risk_manager.wallet = Some(Pubkey::new_unique());
+        open_position(&mut risk_manager).await;
+        let sold = Position::new("MintB".to_string(), 10.0, 0.01, 6, ExitRules::from_env());
+        risk_manager.add_position(sold).await.unwrap();
+
+        let report = risk_manager.reconcile_positions(false).await.unwrap();
+        assert_eq!(report.discrepancies.len(), 2);
+        assert!(report.fixed.is_empty());
+        assert_eq!(risk_manager.current_positions["MintA"].size, 100.0);
+
+        let mut report = risk_manager.reconcile_positions(true).await.unwrap();
+        report.fixed.sort();
+        assert_eq!(report.fixed, vec!["MintA".to_string(), "MintB".to_string()]);
+        assert_eq!(risk_manager.current_positions["MintA"].size, 60.0);
+        assert!(!risk_manager.current_positions.contains_key("MintB"));
+    }
+}
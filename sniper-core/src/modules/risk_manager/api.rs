@@ -0,0 +1,127 @@
+// 🌐 Risk Control API
+// HTTP endpoints for the trading halt, emergency stop and portfolio metrics
+
+use axum::{
+    extract::{Json, State},
+    http::{HeaderMap, StatusCode},
+    response::Json as ResponseJson,
+    routing::{get, post},
+    Router,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::sync::{mpsc, oneshot};
+use tracing::warn;
+
+use super::circuit_breaker::{BreakerState, CircuitBreaker};
+use super::emergency::EmergencyStopReport;
+use super::RiskCommand;
+
+#[derive(Debug, Deserialize)]
+pub struct HaltRequest {
+    pub halted: bool,
+    pub reason: Option<String>,
+}
+
+/// State shared by the risk endpoints
+#[derive(Clone)]
+pub struct RiskApi {
+    circuit_breaker: CircuitBreaker,
+    commands: mpsc::Sender<RiskCommand>,
+    /// Bearer token required by state-changing endpoints
+    admin_token: Option<String>,
+}
+
+impl RiskApi {
+    pub fn new(circuit_breaker: CircuitBreaker, commands: mpsc::Sender<RiskCommand>) -> Self {
+        let admin_token = std::env::var("ADMIN_API_TOKEN").ok().filter(|t| !t.is_empty());
+        if admin_token.is_none() {
+            warn!("🔒 ADMIN_API_TOKEN not set - risk control endpoints are locked");
+        }
+
+        Self {
+            circuit_breaker,
+            commands,
+            admin_token,
+        }
+    }
+
+    /// Routes to be nested under `/api/v1/risk`
+    pub fn routes(self) -> Router {
+        Router::new()
+            .route("/halt", get(get_halt).post(post_halt))
+            .route("/emergency-stop", post(post_emergency_stop))
+            .route("/portfolio", get(get_portfolio))
+            .with_state(self)
+    }
+
+    fn authorize(&self, headers: &HeaderMap) -> Result<(), StatusCode> {
+        let expected = self.admin_token.as_deref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+        let provided = headers
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or(StatusCode::UNAUTHORIZED)?;
+
+        if constant_time_eq(provided.as_bytes(), expected.as_bytes()) {
+            Ok(())
+        } else {
+            warn!("🔒 Rejected risk API request with invalid token");
+            Err(StatusCode::UNAUTHORIZED)
+        }
+    }
+}
+
+async fn get_halt(State(api): State<RiskApi>) -> ResponseJson<BreakerState> {
+    ResponseJson(api.circuit_breaker.state())
+}
+
+async fn post_halt(
+    State(api): State<RiskApi>,
+    headers: HeaderMap,
+    Json(request): Json<HaltRequest>,
+) -> Result<ResponseJson<BreakerState>, StatusCode> {
+    api.authorize(&headers)?;
+    api.circuit_breaker.set_halted(request.halted, request.reason).await;
+    Ok(ResponseJson(api.circuit_breaker.state()))
+}
+
+async fn post_emergency_stop(
+    State(api): State<RiskApi>,
+    headers: HeaderMap,
+) -> Result<ResponseJson<EmergencyStopReport>, StatusCode> {
+    api.authorize(&headers)?;
+
+    let (reply_tx, reply_rx) = oneshot::channel();
+    api.commands
+        .send(RiskCommand::EmergencyStop { reply: reply_tx })
+        .await
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)?;
+
+    reply_rx
+        .await
+        .map(ResponseJson)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+async fn get_portfolio(
+    State(api): State<RiskApi>,
+    headers: HeaderMap,
+) -> Result<ResponseJson<Value>, StatusCode> {
+    api.authorize(&headers)?;
+
+    let (reply_tx, reply_rx) = oneshot::channel();
+    api.commands
+        .send(RiskCommand::PortfolioMetrics { reply: reply_tx })
+        .await
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)?;
+
+    reply_rx
+        .await
+        .map(ResponseJson)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -0,0 +1,277 @@
+// 🛑 Daily Loss Circuit Breaker
+// Per-UTC-day PnL tracking and a persisted trading halt
+
+use chrono::{NaiveDate, Utc};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use crate::modules::cache_manager::CacheManager;
+
+const STATE_KEY: &str = "circuit_breaker";
+
+/// Persisted breaker state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreakerState {
+    /// UTC trading day the PnL figures belong to
+    pub day: NaiveDate,
+    pub realized_pnl: f64,
+    pub unrealized_pnl: f64,
+    /// Mark-to-market PnL of positions already open when the day started
+    #[serde(default)]
+    pub unrealized_at_open: f64,
+    pub halted: bool,
+    pub halt_reason: Option<String>,
+    pub halted_at: Option<i64>,
+    /// Set by a manual reset; the loss limit stays disarmed until the day rolls or losses recover
+    #[serde(default)]
+    pub loss_limit_overridden: bool,
+}
+
+impl BreakerState {
+    fn new(day: NaiveDate) -> Self {
+        Self {
+            day,
+            realized_pnl: 0.0,
+            unrealized_pnl: 0.0,
+            unrealized_at_open: 0.0,
+            halted: false,
+            halt_reason: None,
+            halted_at: None,
+            loss_limit_overridden: false,
+        }
+    }
+
+    /// Realized PnL plus the change in open PnL since the day started
+    pub fn daily_pnl(&self) -> f64 {
+        self.realized_pnl + self.unrealized_pnl - self.unrealized_at_open
+    }
+}
+
+/// Shared handle; clones see the same state
+#[derive(Clone)]
+pub struct CircuitBreaker {
+    state: Arc<RwLock<BreakerState>>,
+    cache_manager: CacheManager,
+    max_daily_loss: f64,
+}
+
+impl CircuitBreaker {
+    /// Restore the last persisted state, so a halt survives restarts
+    pub async fn load(mut cache_manager: CacheManager, max_daily_loss: f64) -> anyhow::Result<Self> {
+        let today = Utc::now().date_naive();
+        let mut state = match cache_manager.get_risk_state(STATE_KEY).await? {
+            Some(value) => serde_json::from_value(value)?,
+            None => BreakerState::new(today),
+        };
+        roll_day(&mut state, today);
+
+        if state.halted {
+            warn!("🛑 Trading halt restored from cache: {}", state.halt_reason.as_deref().unwrap_or("unknown"));
+        }
+
+        Ok(Self {
+            state: Arc::new(RwLock::new(state)),
+            cache_manager,
+            max_daily_loss,
+        })
+    }
+
+    pub fn state(&self) -> BreakerState {
+        let mut state = self.state.write();
+        roll_day(&mut state, Utc::now().date_naive());
+        state.clone()
+    }
+
+    /// New entries are blocked while halted; exits are always allowed
+    pub fn is_halted(&self) -> bool {
+        self.state.read().halted
+    }
+
+    pub fn halt_reason(&self) -> Option<String> {
+        self.state.read().halt_reason.clone()
+    }
+
+    /// Book PnL from a closed position
+    pub async fn record_realized(&self, pnl: f64) {
+        self.update(|state| state.realized_pnl += pnl).await;
+    }
+
+    /// Replace the mark-to-market PnL of open positions
+    pub async fn set_unrealized(&self, pnl: f64) {
+        self.update(|state| state.unrealized_pnl = pnl).await;
+    }
+
+    /// Halt or resume trading manually
+    ///
+    /// A reset holds even while the day's loss is over the limit, until the next day
+    /// or until losses recover below it.
+    pub async fn set_halted(&self, halted: bool, reason: Option<String>) {
+        if halted {
+            let reason = reason.unwrap_or_else(|| "manual halt".to_string());
+            warn!("🛑 Trading halted manually: {}", reason);
+            self.update(|state| {
+                state.loss_limit_overridden = false;
+                halt(state, reason);
+            })
+            .await;
+        } else {
+            info!("✅ Trading halt reset");
+            self.update(|state| {
+                state.halted = false;
+                state.halt_reason = None;
+                state.halted_at = None;
+                state.loss_limit_overridden = true;
+            })
+            .await;
+        }
+    }
+
+    /// Apply a change, trip the breaker if the loss limit is hit, and persist
+    async fn update(&self, change: impl FnOnce(&mut BreakerState)) {
+        let snapshot = {
+            let mut state = self.state.write();
+            roll_day(&mut state, Utc::now().date_naive());
+            let was_halted = state.halted;
+            change(&mut state);
+
+            let daily_pnl = state.daily_pnl();
+            if daily_pnl > -self.max_daily_loss && state.loss_limit_overridden {
+                info!("✅ Daily loss back under the limit - circuit breaker re-armed");
+                state.loss_limit_overridden = false;
+            }
+            if !state.halted && !state.loss_limit_overridden && daily_pnl <= -self.max_daily_loss {
+                halt(&mut state, format!(
+                    "daily loss {:.4} SOL reached limit {:.4} SOL",
+                    -daily_pnl, self.max_daily_loss
+                ));
+            }
+            if state.halted && !was_halted {
+                error!("🛑 CIRCUIT BREAKER TRIPPED - {}", state.halt_reason.as_deref().unwrap_or_default());
+            }
+            state.clone()
+        };
+
+        let mut cache = self.cache_manager.clone();
+        match serde_json::to_value(&snapshot) {
+            Ok(value) => {
+                if let Err(e) = cache.store_risk_state(STATE_KEY, &value).await {
+                    error!("❌ Failed to persist circuit breaker state: {}", e);
+                }
+            }
+            Err(e) => error!("❌ Failed to serialize circuit breaker state: {}", e),
+        }
+    }
+}
+
+/// Start a fresh PnL day at UTC midnight; a halt stays until reset
+///
+/// Open positions carry over at their current mark, so only later moves count against the new day.
+fn roll_day(state: &mut BreakerState, today: NaiveDate) {
+    if state.day != today {
+        info!("📅 New trading day {} - previous day PnL {:.4} SOL", today, state.daily_pnl());
+        state.day = today;
+        state.realized_pnl = 0.0;
+        state.unrealized_at_open = state.unrealized_pnl;
+        state.loss_limit_overridden = false;
+    }
+}
+
+fn halt(state: &mut BreakerState, reason: String) {
+    state.halted = true;
+    state.halt_reason = Some(reason);
+    state.halted_at = Some(Utc::now().timestamp());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::cache_manager::fake;
+
+    async fn breaker() -> CircuitBreaker {
+        CircuitBreaker::load(fake::cache().await, 5.0).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn trips_when_daily_loss_reaches_limit() {
+        let breaker = breaker().await;
+
+        breaker.record_realized(-3.0).await;
+        assert!(!breaker.is_halted());
+
+        breaker.set_unrealized(-2.0).await;
+        assert!(breaker.is_halted());
+        assert_eq!(breaker.halt_reason().unwrap(), "daily loss 5.0000 SOL reached limit 5.0000 SOL");
+    }
+
+    #[tokio::test]
+    async fn reset_holds_while_over_limit_and_rearms_on_recovery() {
+        let breaker = breaker().await;
+        breaker.record_realized(-6.0).await;
+        assert!(breaker.is_halted());
+
+        breaker.set_halted(false, None).await;
+        assert!(!breaker.is_halted());
+
+        // Further losses on the same day do not undo the operator's reset
+        breaker.set_unrealized(-1.0).await;
+        assert!(!breaker.is_halted());
+
+        // Back under the limit re-arms, so the next breach trips again
+        breaker.set_unrealized(2.0).await;
+        assert!(!breaker.state().loss_limit_overridden);
+        breaker.set_unrealized(-1.0).await;
+        assert!(breaker.is_halted());
+    }
+
+    #[tokio::test]
+    async fn manual_halt_survives_a_reload() {
+        let cache = fake::cache().await;
+        let breaker = CircuitBreaker::load(cache.clone(), 5.0).await.unwrap();
+        breaker.record_realized(-1.5).await;
+        breaker.set_halted(true, Some("maintenance".to_string())).await;
+
+        let restored = CircuitBreaker::load(cache, 5.0).await.unwrap();
+        assert!(restored.is_halted());
+        assert_eq!(restored.halt_reason().as_deref(), Some("maintenance"));
+        assert_eq!(restored.state().realized_pnl, -1.5);
+    }
+
+    #[test]
+    fn rollover_rebases_open_pnl() {
+        let yesterday = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+        let mut state = BreakerState::new(yesterday);
+        state.realized_pnl = -2.0;
+        state.unrealized_pnl = -4.0;
+        state.halted = true;
+        state.loss_limit_overridden = true;
+
+        roll_day(&mut state, today);
+
+        assert_eq!(state.day, today);
+        assert_eq!(state.realized_pnl, 0.0);
+        assert_eq!(state.daily_pnl(), 0.0);
+        assert!(!state.loss_limit_overridden);
+        // A halt is only cleared by the operator
+        assert!(state.halted);
+
+        // Closing the carried position books its full loss, of which only 1 SOL is today's
+        state.realized_pnl = -5.0;
+        state.unrealized_pnl = 0.0;
+        assert_eq!(state.daily_pnl(), -1.0);
+    }
+
+    #[test]
+    fn same_day_is_not_rolled() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+        let mut state = BreakerState::new(today);
+        state.realized_pnl = -2.0;
+        state.unrealized_pnl = -1.0;
+
+        roll_day(&mut state, today);
+
+        assert_eq!(state.daily_pnl(), -3.0);
+    }
+}
@@ -0,0 +1,350 @@
+// 🔗 Rolling Correlation
+// Per-mint log returns on a shared time grid and pairwise Pearson correlation
+
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+
+#[derive(Debug, Clone)]
+pub struct CorrelationConfig {
+    /// Return interval prices are bucketed into
+    pub interval_ms: i64,
+    /// Returns kept per mint
+    pub window: usize,
+    /// Pairs with fewer overlapping returns have no correlation
+    pub min_samples: usize,
+    /// Shrink correlated buys instead of rejecting them
+    pub downsize: bool,
+}
+
+impl CorrelationConfig {
+    pub fn from_env() -> Self {
+        let parse = |key: &str, default: i64| {
+            std::env::var(key)
+                .ok()
+                .and_then(|v| v.parse::<i64>().ok())
+                .unwrap_or(default)
+        };
+
+        Self {
+            interval_ms: parse("CORRELATION_INTERVAL_MS", 60_000).max(1),
+            window: parse("CORRELATION_WINDOW", 240).max(2) as usize,
+            min_samples: parse("CORRELATION_MIN_SAMPLES", 30).max(2) as usize,
+            downsize: std::env::var("CORRELATION_DOWNSIZE")
+                .map(|v| !matches!(v.to_lowercase().as_str(), "false" | "0" | "no"))
+                .unwrap_or(true),
+        }
+    }
+}
+
+/// Pairwise correlations; `None` where two mints lack overlapping history
+#[derive(Debug, Clone, Serialize)]
+pub struct CorrelationMatrix {
+    pub mints: Vec<String>,
+    pub values: Vec<Vec<Option<f64>>>,
+}
+
+/// Log returns of one mint, one per consecutive bucket
+#[derive(Debug, Clone)]
+struct ReturnSeries {
+    /// Bucket the newest (still open) price belongs to
+    bucket: i64,
+    /// Close of the previous bucket
+    anchor: f64,
+    /// Latest price in the open bucket
+    close: f64,
+    last_timestamp_ms: i64,
+    /// Closed buckets as (bucket, log return), oldest first
+    returns: VecDeque<(i64, f64)>,
+}
+
+impl ReturnSeries {
+    fn new(bucket: i64, price: f64, timestamp_ms: i64) -> Self {
+        Self {
+            bucket,
+            anchor: price,
+            close: price,
+            last_timestamp_ms: timestamp_ms,
+            returns: VecDeque::new(),
+        }
+    }
+
+    fn push(&mut self, bucket: i64, price: f64, timestamp_ms: i64, window: usize) {
+        if bucket > self.bucket {
+            // Close the open bucket, then forward-fill skipped ones as flat
+            let gap = ((bucket - self.bucket - 1) as usize).min(window);
+            self.returns.push_back((self.bucket, (self.close / self.anchor).ln()));
+            for skipped in bucket - gap as i64..bucket {
+                self.returns.push_back((skipped, 0.0));
+            }
+            while self.returns.len() > window {
+                self.returns.pop_front();
+            }
+            self.anchor = self.close;
+            self.bucket = bucket;
+        }
+        self.close = price;
+        self.last_timestamp_ms = timestamp_ms;
+    }
+
+    /// Returns for buckets `from..=to`; the series holds every bucket in that range
+    fn range(&self, from: i64, to: i64) -> impl Iterator<Item = f64> + '_ {
+        let first = self.returns.front().map(|(b, _)| *b).unwrap_or(0);
+        self.returns
+            .range((from - first) as usize..=(to - first) as usize)
+            .map(|(_, r)| *r)
+    }
+
+    fn span(&self) -> Option<(i64, i64)> {
+        Some((self.returns.front()?.0, self.returns.back()?.0))
+    }
+}
+
+/// Rolling return windows for every tracked mint, fed tick by tick
+#[derive(Debug, Clone)]
+pub struct CorrelationEngine {
+    config: CorrelationConfig,
+    series: HashMap<String, ReturnSeries>,
+}
+
+impl CorrelationEngine {
+    pub fn new(config: CorrelationConfig) -> Self {
+        Self {
+            config,
+            series: HashMap::new(),
+        }
+    }
+
+    pub fn config(&self) -> &CorrelationConfig {
+        &self.config
+    }
+
+    pub fn is_tracked(&self, mint: &str) -> bool {
+        self.series.contains_key(mint)
+    }
+
+    /// Newest timestamp seen for `mint`
+    pub fn last_timestamp(&self, mint: &str) -> Option<i64> {
+        self.series.get(mint).map(|s| s.last_timestamp_ms)
+    }
+
+    /// Feed one price; ticks older than the newest seen are ignored
+    pub fn update(&mut self, mint: &str, price: f64, timestamp_ms: i64) {
+        if price <= 0.0 || !price.is_finite() {
+            return;
+        }
+        let bucket = timestamp_ms / self.config.interval_ms;
+
+        match self.series.get_mut(mint) {
+            Some(series) if timestamp_ms > series.last_timestamp_ms => {
+                series.push(bucket, price, timestamp_ms, self.config.window)
+            }
+            Some(_) => {}
+            None => {
+                self.series.insert(mint.to_string(), ReturnSeries::new(bucket, price, timestamp_ms));
+            }
+        }
+    }
+
+    /// Drop mints not updated within the window
+    pub fn prune(&mut self, now_ms: i64) {
+        let horizon = self.config.interval_ms * self.config.window as i64;
+        self.series.retain(|_, s| now_ms - s.last_timestamp_ms <= horizon);
+    }
+
+    /// Pearson correlation of two mints over their overlapping buckets
+    pub fn correlation(&self, a: &str, b: &str) -> Option<f64> {
+        if a == b {
+            return Some(1.0);
+        }
+        let (x, y) = (self.series.get(a)?, self.series.get(b)?);
+        let (x_from, x_to) = x.span()?;
+        let (y_from, y_to) = y.span()?;
+        let (from, to) = (x_from.max(y_from), x_to.min(y_to));
+        if to < from || ((to - from + 1) as usize) < self.config.min_samples {
+            return None;
+        }
+
+        let n = (to - from + 1) as f64;
+        let (mut sx, mut sy, mut sxx, mut syy, mut sxy) = (0.0, 0.0, 0.0, 0.0, 0.0);
+        for (rx, ry) in x.range(from, to).zip(y.range(from, to)) {
+            sx += rx;
+            sy += ry;
+            sxx += rx * rx;
+            syy += ry * ry;
+            sxy += rx * ry;
+        }
+
+        let cov = sxy - sx * sy / n;
+        let var_x = sxx - sx * sx / n;
+        let var_y = syy - sy * sy / n;
+        if var_x <= f64::EPSILON || var_y <= f64::EPSILON {
+            return None;
+        }
+        Some((cov / (var_x * var_y).sqrt()).clamp(-1.0, 1.0))
+    }
+
+    pub fn matrix(&self, mints: &[String]) -> CorrelationMatrix {
+        let values = mints
+            .iter()
+            .map(|a| mints.iter().map(|b| self.correlation(a, b)).collect())
+            .collect();
+        CorrelationMatrix {
+            mints: mints.to_vec(),
+            values,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTERVAL: i64 = 1_000;
+
+    fn engine() -> CorrelationEngine {
+        CorrelationEngine::new(CorrelationConfig {
+            interval_ms: INTERVAL,
+            window: 100,
+            min_samples: 5,
+            downsize: true,
+        })
+    }
+
+    /// Irregular log returns so the series never has zero variance
+    fn log_returns(count: usize) -> Vec<f64> {
+        (0..count).map(|t| 0.01 * ((t * 7 % 5) as f64 - 2.0)).collect()
+    }
+
+    /// One price per bucket starting at `first_bucket`, following `returns` scaled by `beta`
+    fn feed(engine: &mut CorrelationEngine, mint: &str, first_bucket: i64, start: f64, beta: f64, returns: &[f64]) {
+        let mut price = start;
+        engine.update(mint, price, first_bucket * INTERVAL);
+        for (i, r) in returns.iter().enumerate() {
+            price *= (beta * r).exp();
+            engine.update(mint, price, (first_bucket + i as i64 + 1) * INTERVAL);
+        }
+    }
+
+    fn returns_of(engine: &CorrelationEngine, mint: &str) -> Vec<f64> {
+        engine.series[mint].returns.iter().map(|(_, r)| *r).collect()
+    }
+
+    #[test]
+    fn scaled_and_inverted_series_are_fully_correlated() {
+        let mut engine = engine();
+        let returns = log_returns(20);
+        feed(&mut engine, "a", 0, 1.0, 1.0, &returns);
+        feed(&mut engine, "b", 0, 50.0, 3.0, &returns);
+        feed(&mut engine, "c", 0, 2.0, -1.0, &returns);
+
+        assert!((engine.correlation("a", "b").unwrap() - 1.0).abs() < 1e-9);
+        assert!((engine.correlation("a", "c").unwrap() + 1.0).abs() < 1e-9);
+        assert_eq!(engine.correlation("a", "a"), Some(1.0));
+    }
+
+    #[test]
+    fn correlation_uses_only_overlapping_buckets() {
+        let returns = log_returns(30);
+        let overlapping = |min_samples: usize| {
+            let mut engine = engine();
+            engine.config.min_samples = min_samples;
+            // a closes buckets 0..=29; b joins at bucket 10 and closes 10..=28
+            feed(&mut engine, "a", 0, 1.0, 1.0, &returns);
+            feed(&mut engine, "b", 10, 1.0, 1.0, &returns[10..29]);
+            feed(&mut engine, "c", 500, 1.0, 1.0, &returns);
+            engine
+        };
+
+        assert!(overlapping(19).correlation("a", "b").unwrap() > 0.9);
+        assert_eq!(overlapping(20).correlation("a", "b"), None);
+        // Disjoint histories have nothing to compare
+        assert_eq!(overlapping(2).correlation("a", "c"), None);
+    }
+
+    #[test]
+    fn short_or_flat_history_has_no_correlation() {
+        let mut engine = engine();
+        feed(&mut engine, "a", 0, 1.0, 1.0, &log_returns(3));
+        feed(&mut engine, "b", 0, 1.0, 1.0, &log_returns(3));
+        assert_eq!(engine.correlation("a", "b"), None);
+
+        feed(&mut engine, "x", 100, 1.0, 1.0, &log_returns(20));
+        feed(&mut engine, "flat", 100, 1.0, 0.0, &log_returns(20));
+        assert_eq!(engine.correlation("x", "flat"), None);
+        assert_eq!(engine.correlation("x", "unknown"), None);
+    }
+
+    #[test]
+    fn skipped_buckets_are_forward_filled_as_flat() {
+        let mut engine = engine();
+        for (bucket, price) in [(0, 1.0), (1, 2.0), (2, 4.0), (5, 8.0), (6, 8.0)] {
+            engine.update("a", price, bucket * INTERVAL);
+        }
+        let ln2 = 2f64.ln();
+        let returns = returns_of(&engine, "a");
+        let expected = [0.0, ln2, ln2, 0.0, 0.0, ln2];
+        assert_eq!(returns.len(), expected.len());
+        for (actual, expected) in returns.iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-12);
+        }
+        assert_eq!(engine.series["a"].span(), Some((0, 5)));
+    }
+
+    #[test]
+    fn stale_and_invalid_ticks_are_ignored() {
+        let mut engine = engine();
+        engine.update("a", 1.0, 5 * INTERVAL);
+        engine.update("a", 2.0, 4 * INTERVAL);
+        engine.update("a", 0.0, 6 * INTERVAL);
+        engine.update("a", f64::NAN, 6 * INTERVAL);
+        engine.update("a", -1.0, 6 * INTERVAL);
+        assert_eq!(engine.last_timestamp("a"), Some(5 * INTERVAL));
+
+        engine.update("a", 1.5, 6 * INTERVAL);
+        assert!((returns_of(&engine, "a")[0]).abs() < 1e-12);
+        assert_eq!(engine.series["a"].close, 1.5);
+    }
+
+    #[test]
+    fn window_caps_history_and_prune_drops_idle_mints() {
+        let mut engine = CorrelationEngine::new(CorrelationConfig {
+            interval_ms: INTERVAL,
+            window: 10,
+            min_samples: 5,
+            downsize: true,
+        });
+        feed(&mut engine, "a", 0, 1.0, 1.0, &log_returns(50));
+        assert_eq!(returns_of(&engine, "a").len(), 10);
+
+        // A gap longer than the window leaves a window of flat returns
+        engine.update("a", 1.0, 1_000 * INTERVAL);
+        assert_eq!(returns_of(&engine, "a").len(), 10);
+        assert_eq!(engine.series["a"].span(), Some((990, 999)));
+
+        engine.update("b", 1.0, 1_000 * INTERVAL);
+        engine.prune(1_000 * INTERVAL + 10 * INTERVAL);
+        assert!(engine.is_tracked("a"));
+        engine.prune(1_000 * INTERVAL + 10 * INTERVAL + 1);
+        assert!(!engine.is_tracked("a"));
+        assert!(!engine.is_tracked("b"));
+    }
+
+    #[test]
+    fn matrix_is_symmetric_with_unit_diagonal() {
+        let mut engine = engine();
+        let returns = log_returns(20);
+        feed(&mut engine, "a", 0, 1.0, 1.0, &returns);
+        feed(&mut engine, "b", 0, 1.0, -2.0, &returns);
+        let mints = vec!["a".to_string(), "b".to_string(), "missing".to_string()];
+
+        let matrix = engine.matrix(&mints);
+        assert_eq!(matrix.mints, mints);
+        for i in 0..3 {
+            assert_eq!(matrix.values[i][i], Some(1.0));
+            for j in 0..3 {
+                assert_eq!(matrix.values[i][j], matrix.values[j][i]);
+            }
+        }
+        assert_eq!(matrix.values[0][2], None);
+    }
+}
@@ -0,0 +1,164 @@
+// 🧮 Signal Decision
+// Sizing and limit rules that need no network, shared by the live manager and the backtester
+
+use tracing::debug;
+
+use super::sizing::{self, SizingConfig, SizingInputs};
+use super::{PositionLimits, RiskDecision};
+use crate::modules::data_ingestor::TradeSide;
+use crate::modules::strategy_engine::SignalMessage;
+
+/// Rules a signal is judged by
+#[derive(Debug, Clone, Copy)]
+pub struct DecisionRules<'a> {
+    pub limits: &'a PositionLimits,
+    pub sizing: &'a SizingConfig,
+    /// Loss (fraction) assumed for sizing when the signal sets no stop
+    pub stop_loss_pct: Option<f64>,
+}
+
+/// Book state a signal is judged against
+#[derive(Debug, Clone)]
+pub struct BookState {
+    /// SOL exposure to the signal's mint
+    pub mint_exposure: f64,
+    /// SOL exposure across all positions
+    pub total_exposure: f64,
+    /// Spendable SOL after the fee reserve; only buys read it
+    pub available_capital: Result<f64, String>,
+    /// Why new entries are blocked, if they are
+    pub halted: Option<String>,
+    /// The chain is past the signal's deadline slot, or the slot is unknown
+    pub deadline_passed: bool,
+    /// An order in the mint is already with the executor
+    pub order_in_flight: bool,
+    /// Wallet holding the mint's open position when it is not the signal's
+    pub held_by_other_wallet: Option<String>,
+    /// Per-interval return volatility of the mint, for volatility-target sizing
+    pub volatility: Option<f64>,
+}
+
+/// Size a signal and apply every rule that needs no market data
+///
+/// Approved buys still go through the live manager's VaR, correlation, rug and price impact checks.
+pub fn assess(rules: &DecisionRules, signal: &SignalMessage, book: &BookState) -> RiskDecision {
+    let limits = rules.limits;
+    let mut decision = RiskDecision::new(signal.size_sol.unwrap_or(0.0));
+
+    match signal.side {
+        // Exits only ever reduce risk; just cap them to what is held
+        TradeSide::Sell => {
+            let held = book.mint_exposure;
+            if held <= 0.0 {
+                decision.reject(format!("no open position in {}", signal.mint));
+            } else if book.order_in_flight {
+                decision.reject(format!("order for {} already in flight", signal.mint));
+            } else if signal.size_sol.is_none() {
+                decision.adjusted_size = held;
+            } else {
+                decision.cap(held, "open position size");
+            }
+        }
+        TradeSide::Buy if book.halted.is_some() => {
+            decision.reject(format!("trading halted: {}", book.halted.as_deref().unwrap_or_default()));
+        }
+        TradeSide::Buy if book.deadline_passed => {
+            decision.reject(format!("entry deadline slot {:?} passed", signal.deadline_slot));
+        }
+        // Round trips end flat within one transaction; only the notional needs bounding
+        TradeSide::Buy if signal.arbitrage.is_some() => {
+            if let Some(max_size) = signal.max_size_sol {
+                decision.cap(max_size, "strategy capital allocation");
+            }
+            decision.cap(limits.max_position_size, "per-order notional cap");
+            cap_available(&mut decision, &book.available_capital);
+            if decision.approved && decision.adjusted_size <= 0.0 {
+                decision.reject("arbitrage signal without a size".to_string());
+            }
+        }
+        TradeSide::Buy => {
+            if book.order_in_flight {
+                decision.reject(format!("entry into {} already in flight", signal.mint));
+                return decision;
+            }
+            // Positions are tracked per mint, so one wallet holds each mint
+            if let Some(wallet) = &book.held_by_other_wallet {
+                decision.reject(format!("{} already held by wallet {}", signal.mint, wallet));
+                return decision;
+            }
+
+            if signal.size_sol.is_none() {
+                size_signal(rules, signal, book, &mut decision);
+            }
+            if let Some(max_size) = signal.max_size_sol {
+                decision.cap(max_size, "strategy capital allocation");
+            }
+            if !decision.approved {
+                return decision;
+            }
+            if decision.adjusted_size <= 0.0 || !decision.adjusted_size.is_finite() {
+                decision.reject(format!("invalid order size {}", decision.adjusted_size));
+                return decision;
+            }
+
+            decision.cap(limits.max_position_size, "per-order notional cap");
+            decision.cap(limits.max_mint_exposure - book.mint_exposure, "per-mint concentration cap");
+            decision.cap(limits.max_total_exposure - book.total_exposure, "total exposure cap");
+            // Fail closed: without a balance we cannot know what is affordable
+            cap_available(&mut decision, &book.available_capital);
+            check_min_order_size(limits, &mut decision);
+        }
+    }
+
+    decision
+}
+
+/// Reject an approved order that caps have shrunk below the minimum order size
+pub fn check_min_order_size(limits: &PositionLimits, decision: &mut RiskDecision) {
+    if decision.approved && decision.adjusted_size < limits.min_order_size {
+        decision.reject(format!(
+            "adjusted size {:.4} SOL below minimum order {:.4} SOL",
+            decision.adjusted_size, limits.min_order_size
+        ));
+    }
+}
+
+fn cap_available(decision: &mut RiskDecision, available_capital: &Result<f64, String>) {
+    match available_capital {
+        Ok(available) => decision.cap(*available, "available capital"),
+        Err(e) => decision.reject(format!("wallet balance unavailable: {}", e)),
+    }
+}
+
+/// Size a buy with the configured sizing model
+fn size_signal(rules: &DecisionRules, signal: &SignalMessage, book: &BookState, decision: &mut RiskDecision) {
+    let available = match &book.available_capital {
+        Ok(available) => *available,
+        Err(e) => {
+            decision.reject(format!("wallet balance unavailable: {}", e));
+            return;
+        }
+    };
+
+    let inputs = SizingInputs {
+        equity: available + book.total_exposure,
+        confidence: signal.confidence,
+        expected_return: signal.expected_return,
+        // The strategy's own stop beats the global stop-loss distance
+        expected_loss: signal
+            .stop_price
+            .filter(|stop| *stop > 0.0 && signal.price > *stop)
+            .map(|stop| (signal.price - stop) / signal.price)
+            .or(rules.stop_loss_pct),
+        volatility: book.volatility,
+    };
+
+    match sizing::size(rules.sizing, &inputs) {
+        Ok(size) => {
+            debug!("📐 {:?} sized {} at {:.4} SOL ({:?})", rules.sizing.model, signal.mint, size, inputs);
+            decision.adjusted_size = size;
+            decision.sizing = Some(rules.sizing.model);
+        }
+        Err(e) => decision.reject(format!("sizing failed: {}", e)),
+    }
+}
@@ -0,0 +1,396 @@
+// 🚨 Emergency Liquidation
+// Market-sell every open position until the wallet is flat or a deadline passes
+
+use serde::Serialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_request::TokenAccountsFilter;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, oneshot};
+use tracing::{error, info, warn};
+
+use crate::modules::data_ingestor::TradeSide;
+use crate::modules::executor::{ExecutionMessage, ExecutionPriority, OrderRequest, OrderType};
+
+#[derive(Debug, Clone)]
+pub struct EmergencyStopConfig {
+    /// Slippage accepted on liquidation sells
+    pub slippage_bps: u16,
+    /// Give up on positions still open after this long
+    pub deadline: Duration,
+    /// Pause between liquidation rounds
+    pub retry_delay: Duration,
+}
+
+impl EmergencyStopConfig {
+    pub fn from_env() -> Self {
+        Self {
+            slippage_bps: std::env::var("EMERGENCY_SLIPPAGE_BPS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(3_000),
+            deadline: Duration::from_secs(
+                std::env::var("EMERGENCY_STOP_DEADLINE_SECS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(60),
+            ),
+            retry_delay: Duration::from_millis(
+                std::env::var("EMERGENCY_RETRY_DELAY_MS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(2_000),
+            ),
+        }
+    }
+}
+
+/// What happened to one position during the stop
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LiquidationOutcome {
+    pub mint: String,
+    pub attempts: u32,
+    pub signatures: Vec<String>,
+    /// Lamports received across all sells
+    pub sol_received_lamports: u64,
+    /// Raw token units still in the wallet
+    pub remaining_raw: u64,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EmergencyStopReport {
+    pub started_at: i64,
+    pub finished_at: i64,
+    pub cancelled_orders: usize,
+    pub closed: Vec<LiquidationOutcome>,
+    pub not_closed: Vec<LiquidationOutcome>,
+}
+
+/// Cancel pending orders, then sell each (mint, owning wallet) at Critical priority until flat or the deadline
+pub async fn liquidate(
+    execution_tx: &mpsc::Sender<ExecutionMessage>,
+    rpc: &RpcClient,
+    holdings: Vec<(String, Pubkey)>,
+    config: &EmergencyStopConfig,
+) -> EmergencyStopReport {
+    let mut report = EmergencyStopReport {
+        started_at: chrono::Utc::now().timestamp(),
+        ..Default::default()
+    };
+
+    let (reply_tx, reply_rx) = oneshot::channel();
+    if execution_tx.send(ExecutionMessage::CancelAll { reply: reply_tx }).await.is_ok() {
+        report.cancelled_orders = reply_rx.await.unwrap_or(0);
+    }
+
+    let deadline = Instant::now() + config.deadline;
+    let wallets: HashMap<String, Pubkey> = holdings.iter().cloned().collect();
+    let mut open: HashMap<String, LiquidationOutcome> = holdings
+        .into_iter()
+        .map(|(mint, _)| (mint.clone(), LiquidationOutcome { mint, ..Default::default() }))
+        .collect();
+
+    while !open.is_empty() && Instant::now() < deadline {
+        let mints: Vec<String> = open.keys().cloned().collect();
+        for mint in mints {
+            let outcome = open.get_mut(&mint).unwrap();
+            let wallet = &wallets[&mint];
+
+            let balance = match wallet_token_balance(rpc, wallet, &mint).await {
+                Ok(balance) => balance,
+                Err(e) => {
+                    outcome.last_error = Some(format!("balance lookup failed: {}", e));
+                    continue;
+                }
+            };
+            outcome.remaining_raw = balance;
+
+            if balance == 0 {
+                info!("✅ {} is flat", mint);
+                report.closed.push(open.remove(&mint).unwrap());
+                continue;
+            }
+
+            outcome.attempts += 1;
+            let (reply_tx, reply_rx) = oneshot::channel();
+            let order = OrderRequest {
+                id: format!("emergency-{}-{}", mint, outcome.attempts),
+                mint: mint.clone(),
+                side: TradeSide::Sell,
+                order_type: OrderType::Market,
+                amount: balance,
+                slippage_bps: config.slippage_bps,
+                priority: ExecutionPriority::Critical,
+                wallet: Some(wallet.to_string()),
+                reply: Some(reply_tx),
+            };
+
+            if execution_tx.send(ExecutionMessage::Order(order)).await.is_err() {
+                error!("❌ Executor unavailable - aborting liquidation");
+                report.not_closed.extend(open.into_values());
+                report.finished_at = chrono::Utc::now().timestamp();
+                return report;
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match tokio::time::timeout(remaining, reply_rx).await {
+                Ok(Ok(execution)) if execution.success => {
+                    outcome.sol_received_lamports += execution.out_amount;
+                    outcome.signatures.extend(execution.signature.clone());
+                    outcome.last_error = None;
+                    // Paper trades never move balances, so trust the fill
+                    if execution.simulated {
+                        outcome.remaining_raw = 0;
+                        report.closed.push(open.remove(&mint).unwrap());
+                    }
+                }
+                Ok(Ok(execution)) => outcome.last_error = execution.error,
+                Ok(Err(_)) => outcome.last_error = Some("executor dropped the order".to_string()),
+                Err(_) => outcome.last_error = Some("deadline passed while selling".to_string()),
+            }
+        }
+
+        if !open.is_empty() {
+            tokio::time::sleep(config.retry_delay.min(deadline.saturating_duration_since(Instant::now()))).await;
+        }
+    }
+
+    for outcome in open.values() {
+        warn!("⚠️ {} not closed: {} raw units left ({:?})", outcome.mint, outcome.remaining_raw, outcome.last_error);
+    }
+    report.not_closed.extend(open.into_values());
+    report.finished_at = chrono::Utc::now().timestamp();
+    report
+}
+
+/// Raw token amount `owner` holds of `mint` across all its token accounts
+pub async fn wallet_token_balance(rpc: &RpcClient, owner: &Pubkey, mint: &str) -> anyhow::Result<u64> {
+    let accounts = rpc
+        .get_token_accounts_by_owner(owner, TokenAccountsFilter::Mint(Pubkey::from_str(mint)?))
+        .await?;
+
+    Ok(accounts
+        .iter()
+        .filter_map(|keyed| {
+            let data = serde_json::to_value(&keyed.account.data).ok()?;
+            data["parsed"]["info"]["tokenAmount"]["amount"].as_str()?.parse::<u64>().ok()
+        })
+        .sum())
+}
+
+/// JSON-RPC node serving token balances, shared by risk module tests
+#[cfg(test)]
+pub(super) mod fake_rpc {
+    use axum::{extract::State, routing::post, Json, Router};
+    use serde_json::{json, Value};
+    use solana_client::nonblocking::rpc_client::RpcClient;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+
+    /// Held tokens as mint -> (raw amount, decimals); every owner holds the same
+    pub type Balances = Arc<Mutex<HashMap<String, (u64, u8)>>>;
+
+    pub async fn serve(balances: Balances) -> RpcClient {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let app = Router::new().route("/", post(handle)).with_state(balances);
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        RpcClient::new(url)
+    }
+
+    async fn handle(State(balances): State<Balances>, Json(request): Json<Value>) -> Json<Value> {
+        let filter = &request["params"][1];
+        let accounts: Vec<Value> = balances
+            .lock()
+            .await
+            .iter()
+            .filter(|(mint, _)| match filter["mint"].as_str() {
+                Some(wanted) => wanted == mint.as_str(),
+                // Program filter: everything is a classic SPL token
+                None => filter["programId"] == json!(spl_token::id().to_string()),
+            })
+            .map(|(mint, (amount, decimals))| token_account(mint, *amount, *decimals))
+            .collect();
+
+        Json(json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "result": { "context": { "slot": 1 }, "value": accounts },
+        }))
+    }
+
+    fn token_account(mint: &str, amount: u64, decimals: u8) -> Value {
+        json!({
+            "pubkey": solana_sdk::pubkey::Pubkey::new_unique().to_string(),
+            "account": {
+                "lamports": 2_039_280,
+                "owner": spl_token::id().to_string(),
+                "executable": false,
+                "rentEpoch": 0,
+                "space": 165,
+                "data": {
+                    "program": "spl-token",
+                    "space": 165,
+                    "parsed": {
+                        "type": "account",
+                        "info": {
+                            "mint": mint,
+                            "tokenAmount": {
+                                "amount": amount.to_string(),
+                                "decimals": decimals,
+                                "uiAmount": amount as f64 / 10f64.powi(decimals as i32),
+                                "uiAmountString": "0",
+                            },
+                        },
+                    },
+                },
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::executor::ExecutionReport;
+    use fake_rpc::Balances;
+
+    const MINT: &str = "So11111111111111111111111111111111111111112";
+
+    fn config(deadline_ms: u64) -> EmergencyStopConfig {
+        EmergencyStopConfig {
+            slippage_bps: 3_000,
+            deadline: Duration::from_millis(deadline_ms),
+            retry_delay: Duration::from_millis(10),
+        }
+    }
+
+    fn report(order: &OrderRequest, success: bool, simulated: bool) -> ExecutionReport {
+        ExecutionReport {
+            order_id: order.id.clone(),
+            mint: order.mint.clone(),
+            side: order.side,
+            success,
+            signature: (success && !simulated).then(|| format!("sig-{}", order.id)),
+            in_amount: order.amount,
+            out_amount: if success { 1_000 } else { 0 },
+            error: (!success).then(|| "slippage exceeded".to_string()),
+            latency_ms: 1,
+            simulated,
+        }
+    }
+
+    /// Executor stand-in: fails the first `failures` sells, then fills and empties the wallet
+    fn executor(balances: Balances, failures: u32) -> (mpsc::Sender<ExecutionMessage>, tokio::task::JoinHandle<Vec<u64>>) {
+        let (execution_tx, mut execution_rx) = mpsc::channel(8);
+        let handle = tokio::spawn(async move {
+            let mut amounts = Vec::new();
+            while let Some(message) = execution_rx.recv().await {
+                match message {
+                    ExecutionMessage::CancelAll { reply } => {
+                        let _ = reply.send(2);
+                    }
+                    ExecutionMessage::Order(mut order) => {
+                        assert_eq!(order.side, TradeSide::Sell);
+                        assert_eq!(order.priority, ExecutionPriority::Critical);
+                        amounts.push(order.amount);
+                        let success = amounts.len() as u32 > failures;
+                        if success {
+                            balances.lock().await.insert(order.mint.clone(), (0, 6));
+                        }
+                        let _ = order.reply.take().unwrap().send(report(&order, success, false));
+                    }
+                }
+            }
+            amounts
+        });
+        (execution_tx, handle)
+    }
+
+    fn holding() -> (Balances, Vec<(String, Pubkey)>) {
+        let balances = Balances::default();
+        balances.try_lock().unwrap().insert(MINT.to_string(), (5_000_000, 6));
+        (balances, vec![(MINT.to_string(), Pubkey::new_unique())])
+    }
+
+    #[tokio::test]
+    async fn retries_until_the_wallet_is_flat() {
+        let (balances, holdings) = holding();
+        let rpc = fake_rpc::serve(balances.clone()).await;
+        let (execution_tx, executor) = executor(balances, 2);
+
+        let report = liquidate(&execution_tx, &rpc, holdings, &config(5_000)).await;
+        drop(execution_tx);
+
+        assert_eq!(report.cancelled_orders, 2);
+        assert!(report.not_closed.is_empty());
+        let [closed] = &report.closed[..] else { panic!("{:?}", report) };
+        assert_eq!(closed.attempts, 3);
+        assert_eq!(closed.remaining_raw, 0);
+        assert_eq!(closed.sol_received_lamports, 1_000);
+        assert_eq!(closed.signatures, vec![format!("sig-emergency-{}-3", MINT)]);
+        assert_eq!(closed.last_error, None);
+        // Every attempt sells the full balance read from chain
+        assert_eq!(executor.await.unwrap(), vec![5_000_000; 3]);
+    }
+
+    #[tokio::test]
+    async fn gives_up_at_the_deadline() {
+        let (balances, holdings) = holding();
+        let rpc = fake_rpc::serve(balances.clone()).await;
+        let (execution_tx, executor) = executor(balances, u32::MAX);
+
+        let started = Instant::now();
+        let report = liquidate(&execution_tx, &rpc, holdings, &config(300)).await;
+        drop(execution_tx);
+
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert!(report.closed.is_empty());
+        let [open] = &report.not_closed[..] else { panic!("{:?}", report) };
+        assert!(open.attempts > 1);
+        assert_eq!(open.remaining_raw, 5_000_000);
+        assert_eq!(open.last_error.as_deref(), Some("slippage exceeded"));
+        assert_eq!(executor.await.unwrap().len() as u32, open.attempts);
+    }
+
+    #[tokio::test]
+    async fn paper_fill_counts_as_flat() {
+        let (balances, holdings) = holding();
+        let rpc = fake_rpc::serve(balances).await;
+        let (execution_tx, mut execution_rx) = mpsc::channel(8);
+        tokio::spawn(async move {
+            while let Some(message) = execution_rx.recv().await {
+                match message {
+                    ExecutionMessage::CancelAll { reply } => drop(reply),
+                    ExecutionMessage::Order(mut order) => {
+                        let _ = order.reply.take().unwrap().send(report(&order, true, true));
+                    }
+                }
+            }
+        });
+
+        let report = liquidate(&execution_tx, &rpc, holdings, &config(5_000)).await;
+
+        assert_eq!(report.cancelled_orders, 0);
+        assert_eq!(report.closed.len(), 1);
+        assert_eq!(report.closed[0].attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn aborts_when_the_executor_is_gone() {
+        let (balances, holdings) = holding();
+        let rpc = fake_rpc::serve(balances).await;
+        let (execution_tx, execution_rx) = mpsc::channel(8);
+        drop(execution_rx);
+
+        let report = liquidate(&execution_tx, &rpc, holdings, &config(5_000)).await;
+
+        assert!(report.closed.is_empty());
+        assert_eq!(report.not_closed.len(), 1);
+        assert_eq!(report.not_closed[0].attempts, 1);
+    }
+}
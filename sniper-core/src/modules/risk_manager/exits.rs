@@ -0,0 +1,267 @@
+// 🎯 Position Exit Rules
+// Stop-loss, tiered take-profit, trailing stop and max hold duration
+
+use serde::{Deserialize, Serialize};
+
+/// Sell `sell_fraction` of the remaining position once the gain reaches `gain_pct`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TakeProfitTier {
+    pub gain_pct: f64,
+    pub sell_fraction: f64,
+}
+
+/// Exit parameters carried by every position; percentages are fractions (0.2 = 20%)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExitRules {
+    pub stop_loss_pct: Option<f64>,
+    /// Ascending by `gain_pct`
+    pub take_profit_tiers: Vec<TakeProfitTier>,
+    /// Distance below the high-water price that closes the position
+    pub trailing_stop_pct: Option<f64>,
+    /// Gain required before the trailing stop arms
+    pub trailing_activation_pct: f64,
+    pub max_hold_secs: Option<i64>,
+}
+
+impl ExitRules {
+    pub fn from_env() -> Self {
+        // Unset keeps the default; 0 turns the rule off
+        let optional = |key: &str, default: f64| match std::env::var(key) {
+            Ok(v) => v.parse::<f64>().ok().filter(|v| *v > 0.0),
+            Err(_) => Some(default),
+        };
+
+        let mut take_profit_tiers: Vec<TakeProfitTier> = std::env::var("EXIT_TAKE_PROFIT_TIERS")
+            .unwrap_or_else(|_| "0.5:0.33,1.0:0.5,2.0:1.0".to_string())
+            .split(',')
+            .filter_map(|tier| {
+                let (gain, fraction) = tier.trim().split_once(':')?;
+                Some(TakeProfitTier {
+                    gain_pct: gain.parse().ok()?,
+                    sell_fraction: fraction.parse::<f64>().ok()?.clamp(0.0, 1.0),
+                })
+            })
+            .collect();
+        take_profit_tiers.sort_by(|a, b| a.gain_pct.total_cmp(&b.gain_pct));
+
+        Self {
+            stop_loss_pct: optional("EXIT_STOP_LOSS_PCT", 0.2),
+            take_profit_tiers,
+            trailing_stop_pct: optional("EXIT_TRAILING_STOP_PCT", 0.15),
+            trailing_activation_pct: optional("EXIT_TRAILING_ACTIVATION_PCT", 0.3).unwrap_or(0.0),
+            max_hold_secs: optional("EXIT_MAX_HOLD_SECS", 3600.0).map(|s| s as i64),
+        }
+    }
+
+    /// Replace the stop-loss and take-profit with a signal's own levels, relative to `price`
+    ///
+    /// A target becomes a single tier selling the whole position.
+    pub fn with_levels(mut self, price: f64, target: Option<f64>, stop: Option<f64>) -> Self {
+        if price <= 0.0 {
+            return self;
+        }
+        if let Some(stop) = stop.filter(|stop| *stop > 0.0 && *stop < price) {
+            self.stop_loss_pct = Some((price - stop) / price);
+        }
+        if let Some(target) = target.filter(|target| *target > price) {
+            self.take_profit_tiers = vec![TakeProfitTier {
+                gain_pct: (target - price) / price,
+                sell_fraction: 1.0,
+            }];
+        }
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ExitReason {
+    StopLoss,
+    TakeProfit(usize),
+    TrailingStop,
+    MaxHold,
+    /// Sell signal from a strategy
+    Signal,
+}
+
+/// Exit that should be sent now
+#[derive(Debug, Clone, Copy)]
+pub struct ExitTrigger {
+    pub reason: ExitReason,
+    /// Share of the remaining position to sell
+    pub fraction: f64,
+}
+
+/// Where a position stands relative to its exit rules
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExitState {
+    pub opened_at: i64,
+    pub high_water_price: f64,
+    /// Take-profit tiers already filled
+    pub tiers_filled: usize,
+}
+
+impl ExitState {
+    pub fn new(entry_price: f64) -> Self {
+        Self {
+            opened_at: chrono::Utc::now().timestamp(),
+            high_water_price: entry_price,
+            tiers_filled: 0,
+        }
+    }
+}
+
+/// First rule that fires at `price`; full exits take precedence over partial ones
+pub fn evaluate(
+    rules: &ExitRules,
+    state: &mut ExitState,
+    entry_price: f64,
+    price: f64,
+    now: i64,
+) -> Option<ExitTrigger> {
+    if entry_price <= 0.0 || price <= 0.0 {
+        return None;
+    }
+
+    state.high_water_price = state.high_water_price.max(price);
+    let gain = price / entry_price - 1.0;
+    let full = |reason| Some(ExitTrigger { reason, fraction: 1.0 });
+
+    if rules.stop_loss_pct.is_some_and(|sl| gain <= -sl) {
+        return full(ExitReason::StopLoss);
+    }
+
+    if let Some(trailing) = rules.trailing_stop_pct {
+        let peak_gain = state.high_water_price / entry_price - 1.0;
+        if peak_gain >= rules.trailing_activation_pct
+            && price <= state.high_water_price * (1.0 - trailing)
+        {
+            return full(ExitReason::TrailingStop);
+        }
+    }
+
+    if rules.max_hold_secs.is_some_and(|max| now - state.opened_at >= max) {
+        return full(ExitReason::MaxHold);
+    }
+
+    rules
+        .take_profit_tiers
+        .get(state.tiers_filled)
+        .filter(|tier| gain >= tier.gain_pct)
+        .map(|tier| ExitTrigger {
+            reason: ExitReason::TakeProfit(state.tiers_filled),
+            fraction: tier.sell_fraction,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules() -> ExitRules {
+        ExitRules {
+            stop_loss_pct: Some(0.2),
+            take_profit_tiers: vec![
+                TakeProfitTier { gain_pct: 0.5, sell_fraction: 0.33 },
+                TakeProfitTier { gain_pct: 1.0, sell_fraction: 0.5 },
+                TakeProfitTier { gain_pct: 2.0, sell_fraction: 1.0 },
+            ],
+            trailing_stop_pct: Some(0.15),
+            trailing_activation_pct: 0.3,
+            max_hold_secs: Some(3600),
+        }
+    }
+
+    fn state() -> ExitState {
+        ExitState { opened_at: 0, high_water_price: 1.0, tiers_filled: 0 }
+    }
+
+    fn reason(trigger: Option<ExitTrigger>) -> Option<ExitReason> {
+        trigger.map(|t| t.reason)
+    }
+
+    #[test]
+    fn take_profit_tiers_fire_in_order_as_they_fill() {
+        let rules = ExitRules { trailing_stop_pct: None, ..rules() };
+        let mut state = state();
+
+        assert_eq!(reason(evaluate(&rules, &mut state, 1.0, 1.4, 10)), None);
+
+        let trigger = evaluate(&rules, &mut state, 1.0, 1.5, 10).unwrap();
+        assert_eq!(trigger.reason, ExitReason::TakeProfit(0));
+        assert_eq!(trigger.fraction, 0.33);
+        // Until the fill is booked the same tier keeps firing
+        assert_eq!(reason(evaluate(&rules, &mut state, 1.0, 1.5, 10)), Some(ExitReason::TakeProfit(0)));
+
+        state.tiers_filled = 1;
+        assert_eq!(reason(evaluate(&rules, &mut state, 1.0, 1.9, 10)), None);
+        let trigger = evaluate(&rules, &mut state, 1.0, 2.0, 10).unwrap();
+        assert_eq!(trigger.reason, ExitReason::TakeProfit(1));
+        assert_eq!(trigger.fraction, 0.5);
+
+        // A jump past several tiers still takes the next unfilled one
+        state.tiers_filled = 2;
+        assert_eq!(reason(evaluate(&rules, &mut state, 1.0, 5.0, 10)), Some(ExitReason::TakeProfit(2)));
+
+        state.tiers_filled = 3;
+        assert_eq!(reason(evaluate(&rules, &mut state, 1.0, 5.0, 10)), None);
+    }
+
+    #[test]
+    fn trailing_stop_arms_only_after_the_activation_gain() {
+        let rules = ExitRules { take_profit_tiers: Vec::new(), ..rules() };
+        let mut state = state();
+
+        // Peak of +20% is below the 30% activation: a 15% pullback does nothing
+        assert_eq!(reason(evaluate(&rules, &mut state, 1.0, 1.2, 10)), None);
+        assert_eq!(reason(evaluate(&rules, &mut state, 1.0, 1.02, 10)), None);
+        assert_eq!(state.high_water_price, 1.2);
+
+        // Peak of +40% arms it; the stop sits 15% under the high-water mark
+        assert_eq!(reason(evaluate(&rules, &mut state, 1.0, 1.4, 10)), None);
+        assert_eq!(reason(evaluate(&rules, &mut state, 1.0, 1.2, 10)), None);
+        let trigger = evaluate(&rules, &mut state, 1.0, 1.18, 10).unwrap();
+        assert_eq!(trigger.reason, ExitReason::TrailingStop);
+        assert_eq!(trigger.fraction, 1.0);
+    }
+
+    #[test]
+    fn full_exits_take_precedence() {
+        let rules = rules();
+
+        // Stop-loss beats max hold
+        let mut state = self::state();
+        assert_eq!(reason(evaluate(&rules, &mut state, 1.0, 0.75, 7200)), Some(ExitReason::StopLoss));
+
+        // Trailing stop beats max hold and a take-profit tier still in the money
+        let mut state = ExitState { high_water_price: 2.0, ..self::state() };
+        assert_eq!(reason(evaluate(&rules, &mut state, 1.0, 1.6, 7200)), Some(ExitReason::TrailingStop));
+
+        // Max hold beats a take-profit tier
+        let mut state = self::state();
+        let trigger = evaluate(&rules, &mut state, 1.0, 1.6, 3600).unwrap();
+        assert_eq!(trigger.reason, ExitReason::MaxHold);
+        assert_eq!(trigger.fraction, 1.0);
+        assert_eq!(reason(evaluate(&rules, &mut state, 1.0, 1.6, 3599)), Some(ExitReason::TakeProfit(0)));
+    }
+
+    #[test]
+    fn no_rule_fires_without_valid_prices() {
+        let mut state = state();
+        assert!(evaluate(&rules(), &mut state, 0.0, 0.5, 7200).is_none());
+        assert!(evaluate(&rules(), &mut state, 1.0, 0.0, 7200).is_none());
+    }
+
+    #[test]
+    fn signal_levels_replace_stop_and_tiers() {
+        let rules = rules().with_levels(2.0, Some(3.0), Some(1.5));
+        assert_eq!(rules.stop_loss_pct, Some(0.25));
+        assert_eq!(rules.take_profit_tiers.len(), 1);
+        assert_eq!(rules.take_profit_tiers[0].gain_pct, 0.5);
+        assert_eq!(rules.take_profit_tiers[0].sell_fraction, 1.0);
+
+        // Levels on the wrong side of the price are ignored
+        let unchanged = self::rules().with_levels(2.0, Some(1.0), Some(3.0));
+        assert_eq!(unchanged.stop_loss_pct, Some(0.2));
+        assert_eq!(unchanged.take_profit_tiers.len(), 3);
+    }
+}
@@ -0,0 +1,245 @@
+// 🧾 Position Reconciliation
+// Compare tracked positions with the wallet's on-chain token balances
+
+use serde::Serialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_request::TokenAccountsFilter;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::time::Duration;
+
+use super::Position;
+use crate::modules::data_ingestor::token_profile::TOKEN_2022_PROGRAM;
+use crate::modules::data_ingestor::wallet_tracker::WSOL_MINT;
+
+#[derive(Debug, Clone)]
+pub struct ReconcileConfig {
+    pub interval: Duration,
+    /// Fix discrepancies found by the periodic run instead of only flagging them
+    pub adopt: bool,
+    /// Relative size difference tolerated before a mismatch is flagged
+    pub tolerance: f64,
+}
+
+impl ReconcileConfig {
+    pub fn from_env() -> Self {
+        Self {
+            interval: Duration::from_secs(
+                std::env::var("RECONCILE_INTERVAL_SECS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(60),
+            ),
+            adopt: std::env::var("RECONCILE_ADOPT")
+                .map(|v| matches!(v.to_lowercase().as_str(), "true" | "1" | "yes"))
+                .unwrap_or(false),
+            tolerance: std::env::var("RECONCILE_TOLERANCE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0.01),
+        }
+    }
+}
+
+/// Token held by the wallet, summed over its token accounts
+#[derive(Debug, Clone, Copy)]
+pub struct OnChainBalance {
+    pub raw: u64,
+    pub decimals: u8,
+}
+
+impl OnChainBalance {
+    pub fn quantity(&self) -> f64 {
+        self.raw as f64 / 10f64.powi(self.decimals as i32)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Discrepancy {
+    /// Held on-chain but not tracked
+    Untracked { mint: String, on_chain: f64, decimals: u8 },
+    /// Tracked but no longer held
+    Missing { mint: String, tracked: f64 },
+    /// Held and tracked with different sizes
+    SizeMismatch { mint: String, tracked: f64, on_chain: f64 },
+}
+
+impl Discrepancy {
+    pub fn mint(&self) -> &str {
+        match self {
+            Self::Untracked { mint, .. } | Self::Missing { mint, .. } | Self::SizeMismatch { mint, .. } => mint,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReconcileReport {
+    pub checked_at: i64,
+    pub discrepancies: Vec<Discrepancy>,
+    /// Mints whose tracked position was created, resized or dropped
+    pub fixed: Vec<String>,
+}
+
+/// Balances of every SPL and Token-2022 account owned by `owner`, by mint
+pub async fn wallet_balances(rpc: &RpcClient, owner: &Pubkey) -> anyhow::Result<HashMap<String, OnChainBalance>> {
+    let mut balances: HashMap<String, OnChainBalance> = HashMap::new();
+
+    for program in [spl_token::id(), Pubkey::from_str(TOKEN_2022_PROGRAM)?] {
+        let accounts = rpc
+            .get_token_accounts_by_owner(owner, TokenAccountsFilter::ProgramId(program))
+            .await?;
+
+        for keyed in accounts {
+            let Ok(data) = serde_json::to_value(&keyed.account.data) else { continue };
+            let info = &data["parsed"]["info"];
+            let (Some(mint), Some(raw), Some(decimals)) = (
+                info["mint"].as_str(),
+                info["tokenAmount"]["amount"].as_str().and_then(|a| a.parse::<u64>().ok()),
+                info["tokenAmount"]["decimals"].as_u64(),
+            ) else {
+                continue;
+            };
+
+            let balance = balances.entry(mint.to_string()).or_insert(OnChainBalance {
+                raw: 0,
+                decimals: decimals as u8,
+            });
+            balance.raw += raw;
+        }
+    }
+
+    Ok(balances)
+}
+
+/// Differences between tracked positions and on-chain balances
+///
+/// Mints in `in_flight` have orders outstanding and are skipped; wrapped SOL is not a position.
+pub fn compare(
+    positions: &HashMap<String, Position>,
+    balances: &HashMap<String, OnChainBalance>,
+    in_flight: &HashSet<String>,
+    tolerance: f64,
+) -> Vec<Discrepancy> {
+    let mut discrepancies = Vec::new();
+
+    for (mint, balance) in balances {
+        if mint == WSOL_MINT || balance.raw == 0 || in_flight.contains(mint) {
+            continue;
+        }
+        let on_chain = balance.quantity();
+        match positions.get(mint) {
+            None => discrepancies.push(Discrepancy::Untracked {
+                mint: mint.clone(),
+                on_chain,
+                decimals: balance.decimals,
+            }),
+            Some(position) if (position.size - on_chain).abs() > on_chain.max(position.size) * tolerance => {
+                discrepancies.push(Discrepancy::SizeMismatch {
+                    mint: mint.clone(),
+                    tracked: position.size,
+                    on_chain,
+                })
+            }
+            Some(_) => {}
+        }
+    }
+
+    for (mint, position) in positions {
+        let held = balances.get(mint).map(|b| b.raw).unwrap_or(0);
+        if held == 0 && !in_flight.contains(mint) {
+            discrepancies.push(Discrepancy::Missing {
+                mint: mint.clone(),
+                tracked: position.size,
+            });
+        }
+    }
+
+    discrepancies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::risk_manager::emergency::fake_rpc::{self, Balances};
+    use crate::modules::risk_manager::ExitRules;
+
+    const TOLERANCE: f64 = 0.01;
+
+    fn positions(entries: &[(&str, f64)]) -> HashMap<String, Position> {
+        entries
+            .iter()
+            .map(|(mint, size)| (mint.to_string(), Position::new(mint.to_string(), *size, 0.01, 6, ExitRules::from_env())))
+            .collect()
+    }
+
+    fn balances(entries: &[(&str, u64)]) -> HashMap<String, OnChainBalance> {
+        entries
+            .iter()
+            .map(|(mint, raw)| (mint.to_string(), OnChainBalance { raw: *raw, decimals: 6 }))
+            .collect()
+    }
+
+    #[test]
+    fn matching_books_have_no_discrepancies() {
+        // Within the 1% tolerance
+        let discrepancies = compare(
+            &positions(&[("MintA", 100.0)]),
+            &balances(&[("MintA", 100_500_000)]),
+            &HashSet::new(),
+            TOLERANCE,
+        );
+
+        assert!(discrepancies.is_empty(), "{:?}", discrepancies);
+    }
+
+    #[test]
+    fn untracked_missing_and_resized_positions_are_flagged() {
+        let mut discrepancies = compare(
+            &positions(&[("MintA", 100.0), ("MintB", 50.0)]),
+            &balances(&[("MintA", 80_000_000), ("MintC", 7_000_000)]),
+            &HashSet::new(),
+            TOLERANCE,
+        );
+        discrepancies.sort_by(|a, b| a.mint().cmp(b.mint()));
+
+        assert!(matches!(&discrepancies[0], Discrepancy::SizeMismatch { mint, tracked, on_chain }
+            if mint == "MintA" && *tracked == 100.0 && *on_chain == 80.0));
+        assert!(matches!(&discrepancies[1], Discrepancy::Missing { mint, tracked } if mint == "MintB" && *tracked == 50.0));
+        assert!(matches!(&discrepancies[2], Discrepancy::Untracked { mint, on_chain, decimals: 6 }
+            if mint == "MintC" && *on_chain == 7.0));
+        assert_eq!(discrepancies.len(), 3);
+    }
+
+    #[test]
+    fn in_flight_wsol_and_empty_accounts_are_skipped() {
+        let in_flight: HashSet<String> = ["MintA".to_string(), "MintB".to_string()].into();
+
+        let discrepancies = compare(
+            &positions(&[("MintA", 100.0)]),
+            &balances(&[("MintB", 1_000_000), (WSOL_MINT, 2_000_000_000), ("MintD", 0)]),
+            &in_flight,
+            TOLERANCE,
+        );
+
+        assert!(discrepancies.is_empty(), "{:?}", discrepancies);
+    }
+
+    #[tokio::test]
+    async fn wallet_balances_are_read_per_mint() {
+        let held = Balances::default();
+        held.lock().await.extend([
+            ("MintA".to_string(), (1_500_000, 6)),
+            ("MintB".to_string(), (42, 0)),
+        ]);
+        let rpc = fake_rpc::serve(held).await;
+
+        let balances = wallet_balances(&rpc, &Pubkey::new_unique()).await.unwrap();
+
+        assert_eq!(balances.len(), 2);
+        assert_eq!(balances["MintA"].quantity(), 1.5);
+        assert_eq!(balances["MintB"].raw, 42);
+        assert_eq!(balances["MintB"].decimals, 0);
+    }
+}
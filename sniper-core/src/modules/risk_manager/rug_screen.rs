@@ -0,0 +1,559 @@
+// 🧪 Rug-Pull Screening
+// Token profile rules and buy-then-sell honeypot simulation
+
+use serde::Serialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
+    message::{v0, VersionedMessage},
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::VersionedTransaction,
+};
+use std::fmt;
+use tracing::{debug, warn};
+
+use crate::modules::data_ingestor::wallet_tracker::WSOL_MINT;
+use crate::modules::data_ingestor::TokenProfile;
+use crate::modules::executor::jupiter::{load_lookup_tables, JupiterClient};
+
+/// Compute limit for the combined buy + sell simulation
+const SIMULATION_COMPUTE_UNITS: u32 = 1_400_000;
+/// Keeps both Jupiter routes small enough to fit in one transaction
+const SIMULATION_MAX_ACCOUNTS: u8 = 24;
+
+/// Each rule can be switched off or loosened independently
+#[derive(Debug, Clone)]
+pub struct RugScreenConfig {
+    pub reject_mint_authority: bool,
+    pub reject_freeze_authority: bool,
+    pub reject_mutable_metadata: bool,
+    pub reject_transfer_hook: bool,
+    pub reject_permanent_delegate: bool,
+    /// Highest Token-2022 transfer fee tolerated
+    pub max_transfer_fee_bps: u16,
+    pub max_top1_share: f64,
+    pub max_top10_share: f64,
+    /// Minimum share of LP burned or locked; 0 disables the rule
+    pub min_lp_secured_share: f64,
+    /// Reject pooled mints whose LP state could not be read
+    pub require_liquidity: bool,
+    pub simulate_sell: bool,
+    pub simulation_lamports: u64,
+    pub simulation_slippage_bps: u16,
+    /// Highest tolerated loss on the quoted round trip (fees, taxes, impact)
+    pub max_round_trip_loss: f64,
+}
+
+impl RugScreenConfig {
+    pub fn from_env() -> Self {
+        Self {
+            reject_mint_authority: env_bool("RUG_REJECT_MINT_AUTHORITY", true),
+            reject_freeze_authority: env_bool("RUG_REJECT_FREEZE_AUTHORITY", true),
+            reject_mutable_metadata: env_bool("RUG_REJECT_MUTABLE_METADATA", false),
+            reject_transfer_hook: env_bool("RUG_REJECT_TRANSFER_HOOK", true),
+            reject_permanent_delegate: env_bool("RUG_REJECT_PERMANENT_DELEGATE", true),
+            max_transfer_fee_bps: env_parse("RUG_MAX_TRANSFER_FEE_BPS", 0),
+            max_top1_share: env_parse("RUG_MAX_TOP1_SHARE", 0.2),
+            max_top10_share: env_parse("RUG_MAX_TOP10_SHARE", 0.5),
+            min_lp_secured_share: env_parse("RUG_MIN_LP_BURNED_OR_LOCKED", 0.9),
+            require_liquidity: env_bool("RUG_REQUIRE_LIQUIDITY", true),
+            simulate_sell: env_bool("RUG_SIMULATE_SELL", true),
+            simulation_lamports: env_parse("RUG_SIMULATION_LAMPORTS", 10_000_000),
+            simulation_slippage_bps: env_parse("RUG_SIMULATION_SLIPPAGE_BPS", 1_000),
+            max_round_trip_loss: env_parse("RUG_MAX_ROUND_TRIP_LOSS", 0.1),
+        }
+    }
+}
+
+/// Why a mint was rejected
+#[derive(Debug, Clone, Serialize)]
+pub enum RejectionReason {
+    ProfileUnavailable(String),
+    MintAuthorityActive(String),
+    FreezeAuthorityActive(String),
+    MutableMetadata,
+    MetadataUnknown,
+    TransferFee { bps: u16, max_bps: u16 },
+    TransferHook(String),
+    PermanentDelegate(String),
+    HoldersUnknown,
+    TopHolderConcentration { share: f64, max: f64 },
+    Top10Concentration { share: f64, max: f64 },
+    LiquidityNotSecured { share: f64, min: f64 },
+    LiquidityUnknown,
+    SellSimulationFailed(String),
+    RoundTripLoss { loss: f64, max: f64 },
+}
+
+impl fmt::Display for RejectionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ProfileUnavailable(e) => write!(f, "token profile unavailable: {}", e),
+            Self::MintAuthorityActive(a) => write!(f, "mint authority still active ({})", a),
+            Self::FreezeAuthorityActive(a) => write!(f, "freeze authority still active ({})", a),
+            Self::MutableMetadata => write!(f, "metadata is mutable"),
+            Self::MetadataUnknown => write!(f, "metadata not found"),
+            Self::TransferFee { bps, max_bps } => {
+                write!(f, "transfer fee {} bps exceeds {} bps", bps, max_bps)
+            }
+            Self::TransferHook(program) => write!(f, "transfer hook program {}", program),
+            Self::PermanentDelegate(delegate) => write!(f, "permanent delegate {}", delegate),
+            Self::HoldersUnknown => write!(f, "holder distribution unknown"),
+            Self::TopHolderConcentration { share, max } => write!(
+                f,
+                "top holder owns {:.1}% (max {:.1}%)",
+                share * 100.0,
+                max * 100.0
+            ),
+            Self::Top10Concentration { share, max } => write!(
+                f,
+                "top 10 holders own {:.1}% (max {:.1}%)",
+                share * 100.0,
+                max * 100.0
+            ),
+            Self::LiquidityNotSecured { share, min } => write!(
+                f,
+                "only {:.1}% of LP burned or locked (min {:.1}%)",
+                share * 100.0,
+                min * 100.0
+            ),
+            Self::LiquidityUnknown => write!(f, "LP state unknown"),
+            Self::SellSimulationFailed(e) => write!(f, "buy-then-sell simulation failed: {}", e),
+            Self::RoundTripLoss { loss, max } => write!(
+                f,
+                "round trip loses {:.1}% (max {:.1}%)",
+                loss * 100.0,
+                max * 100.0
+            ),
+        }
+    }
+}
+
+/// Outcome of screening one mint
+#[derive(Debug, Clone, Serialize)]
+pub struct ScreeningReport {
+    pub mint: String,
+    pub reasons: Vec<RejectionReason>,
+}
+
+impl ScreeningReport {
+    pub fn passed(&self) -> bool {
+        self.reasons.is_empty()
+    }
+}
+
+pub struct RugScreener {
+    config: RugScreenConfig,
+    rpc: RpcClient,
+    jupiter: JupiterClient,
+    /// Wallet used as the simulated trader; it must hold enough SOL for the buy
+    wallet: Option<Pubkey>,
+}
+
+impl RugScreener {
+    pub fn new(config: RugScreenConfig, rpc_url: String, wallet: Option<Pubkey>) -> Self {
+        if config.simulate_sell && wallet.is_none() {
+            warn!("🧪 No wallet configured - every mint will fail the buy-then-sell simulation");
+        }
+
+        Self {
+            config,
+            rpc: RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed()),
+            jupiter: JupiterClient::from_env(),
+            wallet,
+        }
+    }
+
+    /// Apply every enabled rule and collect all failures
+    pub async fn screen(&self, profile: &TokenProfile) -> ScreeningReport {
+        let mut reasons = self.check_profile(profile);
+
+        // Skip the RPC round trips when static rules already failed
+        if reasons.is_empty() && self.config.simulate_sell {
+            match self.wallet {
+                Some(wallet) => reasons.extend(self.simulate_round_trip(&profile.mint, &wallet).await),
+                None => reasons.push(RejectionReason::SellSimulationFailed(
+                    "no wallet configured".to_string(),
+                )),
+            }
+        }
+
+        ScreeningReport {
+            mint: profile.mint.clone(),
+            reasons,
+        }
+    }
+
+    /// Static rules over the token profile
+    pub fn check_profile(&self, profile: &TokenProfile) -> Vec<RejectionReason> {
+        let config = &self.config;
+        let mut reasons = Vec::new();
+
+        if config.reject_mint_authority {
+            if let Some(authority) = &profile.mint_authority {
+                reasons.push(RejectionReason::MintAuthorityActive(authority.clone()));
+            }
+        }
+
+        if config.reject_freeze_authority {
+            if let Some(authority) = &profile.freeze_authority {
+                reasons.push(RejectionReason::FreezeAuthorityActive(authority.clone()));
+            }
+        }
+
+        if config.reject_mutable_metadata {
+            match &profile.metadata {
+                Some(metadata) if metadata.is_mutable => reasons.push(RejectionReason::MutableMetadata),
+                Some(_) => {}
+                None => reasons.push(RejectionReason::MetadataUnknown),
+            }
+        }
+
+        if let Some(bps) = profile.extensions.transfer_fee_bps {
+            if bps > config.max_transfer_fee_bps {
+                reasons.push(RejectionReason::TransferFee {
+                    bps,
+                    max_bps: config.max_transfer_fee_bps,
+                });
+            }
+        }
+
+        if config.reject_transfer_hook {
+            if let Some(program) = &profile.extensions.transfer_hook_program {
+                reasons.push(RejectionReason::TransferHook(program.clone()));
+            }
+        }
+
+        if config.reject_permanent_delegate {
+            if let Some(delegate) = &profile.extensions.permanent_delegate {
+                reasons.push(RejectionReason::PermanentDelegate(delegate.clone()));
+            }
+        }
+
+        // Fail closed: concentration we could not measure is not 0%
+        match &profile.holders {
+            None => reasons.push(RejectionReason::HoldersUnknown),
+            Some(holders) => {
+                if holders.top1_share > config.max_top1_share {
+                    reasons.push(RejectionReason::TopHolderConcentration {
+                        share: holders.top1_share,
+                        max: config.max_top1_share,
+                    });
+                }
+
+                if holders.top10_share > config.max_top10_share {
+                    reasons.push(RejectionReason::Top10Concentration {
+                        share: holders.top10_share,
+                        max: config.max_top10_share,
+                    });
+                }
+            }
+        }
+
+        // Bonding-curve tokens have no LP to pull; only pooled liquidity is checked
+        if config.min_lp_secured_share > 0.0 && !profile.on_bonding_curve {
+            match &profile.liquidity {
+                Some(liquidity) => {
+                    let share = liquidity.secured_share();
+                    if share < config.min_lp_secured_share {
+                        reasons.push(RejectionReason::LiquidityNotSecured {
+                            share,
+                            min: config.min_lp_secured_share,
+                        });
+                    }
+                }
+                None if config.require_liquidity => reasons.push(RejectionReason::LiquidityUnknown),
+                None => {}
+            }
+        }
+
+        reasons
+    }
+
+    /// Simulate buying and immediately selling the mint in one transaction
+    async fn simulate_round_trip(&self, mint: &str, wallet: &Pubkey) -> Vec<RejectionReason> {
+        match self.try_round_trip(mint, wallet).await {
+            Ok(reasons) => reasons,
+            Err(e) => vec![RejectionReason::SellSimulationFailed(e.to_string())],
+        }
+    }
+
+    async fn try_round_trip(&self, mint: &str, wallet: &Pubkey) -> anyhow::Result<Vec<RejectionReason>> {
+        let config = &self.config;
+        let buy = self
+            .jupiter
+            .quote(
+                WSOL_MINT,
+                mint,
+                config.simulation_lamports,
+                config.simulation_slippage_bps,
+                Some(SIMULATION_MAX_ACCOUNTS),
+            )
+            .await?;
+        // Sell the guaranteed minimum so the sell leg cannot fail for lack of tokens
+        let sell = self
+            .jupiter
+            .quote(
+                mint,
+                WSOL_MINT,
+                buy.min_out_amount,
+                config.simulation_slippage_bps,
+                Some(SIMULATION_MAX_ACCOUNTS),
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!("no sell route: {}", e))?;
+
+        let buy_ixs = self.jupiter.swap_instructions(&buy, wallet, None).await?;
+        let sell_ixs = self.jupiter.swap_instructions(&sell, wallet, None).await?;
+
+        let mut instructions =
+            vec![ComputeBudgetInstruction::set_compute_unit_limit(SIMULATION_COMPUTE_UNITS)];
+        instructions.extend(buy_ixs.core_instructions());
+        instructions.extend(sell_ixs.core_instructions());
+
+        let mut table_keys = buy_ixs.lookup_tables.clone();
+        for key in sell_ixs.lookup_tables {
+            if !table_keys.contains(&key) {
+                table_keys.push(key);
+            }
+        }
+        let tables = load_lookup_tables(&self.rpc, &table_keys).await?;
+
+        // The blockhash is replaced by the RPC node during simulation
+        let message = v0::Message::try_compile(wallet, &instructions, &tables, Hash::default())?;
+        let num_signatures = message.header.num_required_signatures as usize;
+        let transaction = VersionedTransaction {
+            signatures: vec![Signature::default(); num_signatures],
+            message: VersionedMessage::V0(message),
+        };
+
+        let result = self
+            .rpc
+            .simulate_transaction_with_config(
+                &transaction,
+                RpcSimulateTransactionConfig {
+                    sig_verify: false,
+                    replace_recent_blockhash: true,
+                    commitment: Some(CommitmentConfig::processed()),
+                    ..Default::default()
+                },
+            )
+            .await?
+            .value;
+
+        if let Some(err) = result.err {
+            let last_log = result
+                .logs
+                .as_ref()
+                .and_then(|logs| logs.iter().rev().find(|l| l.contains("failed") || l.contains("Error")))
+                .cloned()
+                .unwrap_or_default();
+            return Ok(vec![RejectionReason::SellSimulationFailed(format!("{:?} {}", err, last_log))]);
+        }
+
+        let loss = 1.0 - sell.out_amount as f64 / buy.in_amount as f64;
+        debug!("🧪 {} round trip simulated: {:.2}% loss", mint, loss * 100.0);
+
+        Ok(if loss > config.max_round_trip_loss {
+            vec![RejectionReason::RoundTripLoss {
+                loss,
+                max: config.max_round_trip_loss,
+            }]
+        } else {
+            Vec::new()
+        })
+    }
+}
+
+fn env_bool(key: &str, default: bool) -> bool {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+fn env_parse<T: std::str::FromStr>(key: &str, default: T) -> T {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::data_ingestor::token_profile::{HolderDistribution, LiquidityProfile, TokenExtensions, TokenMetadata};
+
+    /// Nothing listens here, so every RPC and Jupiter call fails fast
+    const UNREACHABLE: &str = "http://127.0.0.1:1";
+
+    fn config() -> RugScreenConfig {
+        RugScreenConfig {
+            reject_mint_authority: true,
+            reject_freeze_authority: true,
+            reject_mutable_metadata: true,
+            reject_transfer_hook: true,
+            reject_permanent_delegate: true,
+            max_transfer_fee_bps: 0,
+            max_top1_share: 0.2,
+            max_top10_share: 0.5,
+            min_lp_secured_share: 0.9,
+            require_liquidity: true,
+            simulate_sell: false,
+            simulation_lamports: 10_000_000,
+            simulation_slippage_bps: 1_000,
+            max_round_trip_loss: 0.1,
+        }
+    }
+
+    fn screener(config: RugScreenConfig, wallet: Option<Pubkey>) -> RugScreener {
+        RugScreener {
+            config,
+            rpc: RpcClient::new(UNREACHABLE.to_string()),
+            jupiter: JupiterClient::new(UNREACHABLE.to_string()),
+            wallet,
+        }
+    }
+
+    /// Profile that passes every static rule
+    fn clean_profile() -> TokenProfile {
+        TokenProfile {
+            mint: "MintA".to_string(),
+            token_program: spl_token::id().to_string(),
+            supply: 1_000_000_000,
+            decimals: 6,
+            mint_authority: None,
+            freeze_authority: None,
+            metadata: Some(TokenMetadata {
+                name: "Token".to_string(),
+                symbol: "TKN".to_string(),
+                uri: String::new(),
+                update_authority: "Authority".to_string(),
+                is_mutable: false,
+            }),
+            extensions: TokenExtensions::default(),
+            holders: Some(HolderDistribution {
+                top_holders: Vec::new(),
+                top1_share: 0.05,
+                top10_share: 0.25,
+            }),
+            liquidity: Some(LiquidityProfile {
+                pool: "Pool".to_string(),
+                quote_mint: WSOL_MINT.to_string(),
+                lp_mint: "LpMint".to_string(),
+                lp_supply: 1_000,
+                lp_reserve: 1_000,
+                burned_share: 1.0,
+                locked_share: 0.0,
+            }),
+            on_bonding_curve: false,
+            fetched_at: 0,
+        }
+    }
+
+    #[test]
+    fn clean_profile_passes_static_rules() {
+        assert!(screener(config(), None).check_profile(&clean_profile()).is_empty());
+    }
+
+    #[test]
+    fn every_failing_rule_is_reported() {
+        let mut profile = clean_profile();
+        profile.mint_authority = Some("Minter".to_string());
+        profile.freeze_authority = Some("Freezer".to_string());
+        profile.extensions.transfer_fee_bps = Some(500);
+        profile.extensions.permanent_delegate = Some("Delegate".to_string());
+        profile.holders.as_mut().unwrap().top1_share = 0.4;
+
+        let reasons = screener(config(), None).check_profile(&profile);
+
+        assert_eq!(reasons.len(), 5);
+        assert!(matches!(reasons[0], RejectionReason::MintAuthorityActive(_)));
+        assert!(matches!(reasons[1], RejectionReason::FreezeAuthorityActive(_)));
+        assert!(matches!(reasons[2], RejectionReason::TransferFee { bps: 500, max_bps: 0 }));
+        assert!(matches!(reasons[3], RejectionReason::PermanentDelegate(_)));
+        assert!(matches!(reasons[4], RejectionReason::TopHolderConcentration { .. }));
+    }
+
+    #[test]
+    fn disabled_rules_are_skipped() {
+        let mut profile = clean_profile();
+        profile.mint_authority = Some("Minter".to_string());
+        profile.metadata.as_mut().unwrap().is_mutable = true;
+
+        let config = RugScreenConfig {
+            reject_mint_authority: false,
+            reject_mutable_metadata: false,
+            ..config()
+        };
+        assert!(screener(config, None).check_profile(&profile).is_empty());
+    }
+
+    #[test]
+    fn unknown_holders_and_liquidity_fail_closed() {
+        let mut profile = clean_profile();
+        profile.holders = None;
+        profile.liquidity = None;
+        profile.metadata = None;
+
+        let reasons = screener(config(), None).check_profile(&profile);
+
+        assert!(matches!(reasons[0], RejectionReason::MetadataUnknown));
+        assert!(matches!(reasons[1], RejectionReason::HoldersUnknown));
+        assert!(matches!(reasons[2], RejectionReason::LiquidityUnknown));
+    }
+
+    #[test]
+    fn bonding_curve_tokens_skip_the_lp_rule() {
+        let mut profile = clean_profile();
+        profile.liquidity = None;
+        profile.on_bonding_curve = true;
+
+        assert!(screener(config(), None).check_profile(&profile).is_empty());
+    }
+
+    #[test]
+    fn partly_secured_liquidity_is_rejected() {
+        let mut profile = clean_profile();
+        let liquidity = profile.liquidity.as_mut().unwrap();
+        liquidity.burned_share = 0.5;
+        liquidity.locked_share = 0.25;
+
+        let reasons = screener(config(), None).check_profile(&profile);
+        assert!(matches!(reasons[..], [RejectionReason::LiquidityNotSecured { share, min }] if share == 0.75 && min == 0.9));
+    }
+
+    #[tokio::test]
+    async fn simulation_without_wallet_fails_closed() {
+        let config = RugScreenConfig { simulate_sell: true, ..config() };
+
+        let report = screener(config, None).screen(&clean_profile()).await;
+
+        assert!(!report.passed());
+        assert!(matches!(&report.reasons[..], [RejectionReason::SellSimulationFailed(e)] if e == "no wallet configured"));
+    }
+
+    #[tokio::test]
+    async fn unreachable_quote_fails_closed() {
+        let config = RugScreenConfig { simulate_sell: true, ..config() };
+
+        let report = screener(config, Some(Pubkey::new_unique())).screen(&clean_profile()).await;
+
+        assert!(matches!(&report.reasons[..], [RejectionReason::SellSimulationFailed(_)]));
+    }
+
+    #[tokio::test]
+    async fn static_failures_skip_the_simulation() {
+        let config = RugScreenConfig { simulate_sell: true, ..config() };
+        let mut profile = clean_profile();
+        profile.freeze_authority = Some("Freezer".to_string());
+
+        let report = screener(config, Some(Pubkey::new_unique())).screen(&profile).await;
+
+        assert!(matches!(&report.reasons[..], [RejectionReason::FreezeAuthorityActive(_)]));
+    }
+}
@@ -0,0 +1,235 @@
+// 📐 Position Sizing
+// Order notional from signal confidence and edge
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum SizingModel {
+    /// Same SOL notional for every trade
+    FixedNotional,
+    /// Fixed share of equity
+    FixedFractional,
+    /// Fraction of the Kelly bet for the signal's win probability and payoff
+    Kelly,
+    /// Size so the position's expected volatility is a fixed share of equity
+    VolatilityTarget,
+}
+
+impl SizingModel {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "fixed_notional" | "fixed" => Some(Self::FixedNotional),
+            "fixed_fractional" | "fractional" => Some(Self::FixedFractional),
+            "kelly" => Some(Self::Kelly),
+            "volatility_target" | "vol_target" | "inverse_vol" => Some(Self::VolatilityTarget),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SizingConfig {
+    pub model: SizingModel,
+    /// SOL per trade for `FixedNotional`
+    pub fixed_notional: f64,
+    /// Share of equity per trade for `FixedFractional`
+    pub equity_fraction: f64,
+    /// Share of the full Kelly bet actually taken
+    pub kelly_fraction: f64,
+    /// Per-interval volatility budget as a share of equity
+    pub target_volatility: f64,
+    /// Return interval and samples used to measure volatility
+    pub vol_interval_ms: i64,
+    pub vol_lookback: isize,
+}
+
+impl SizingConfig {
+    pub fn from_env() -> Self {
+        let parse = |key: &str, default: f64| {
+            std::env::var(key)
+                .ok()
+                .and_then(|v| v.parse::<f64>().ok())
+                .unwrap_or(default)
+        };
+
+        Self {
+            model: std::env::var("SIZING_MODEL")
+                .ok()
+                .and_then(|m| SizingModel::parse(&m))
+                .unwrap_or(SizingModel::FixedFractional),
+            fixed_notional: parse("SIZING_FIXED_NOTIONAL", 0.1),
+            equity_fraction: parse("SIZING_EQUITY_FRACTION", 0.02),
+            kelly_fraction: parse("SIZING_KELLY_FRACTION", 0.25).clamp(0.0, 1.0),
+            target_volatility: parse("SIZING_TARGET_VOLATILITY", 0.002),
+            vol_interval_ms: parse("SIZING_VOL_INTERVAL_MS", 60_000.0) as i64,
+            vol_lookback: parse("SIZING_VOL_LOOKBACK", 500.0) as isize,
+        }
+    }
+}
+
+/// What a sizing model needs to know about the trade
+#[derive(Debug, Clone)]
+pub struct SizingInputs {
+    /// Spendable SOL plus open exposure
+    pub equity: f64,
+    /// Win probability, 0..1
+    pub confidence: f64,
+    /// Expected gain (fraction) when the trade works out
+    pub expected_return: Option<f64>,
+    /// Loss (fraction) taken when it does not, i.e. the stop-loss distance
+    pub expected_loss: Option<f64>,
+    /// Per-interval standard deviation of log returns
+    pub volatility: Option<f64>,
+}
+
+/// Order notional in SOL before `PositionLimits` are applied
+///
+/// Fixed and volatility models are scaled by confidence; Kelly uses it as the win probability.
+pub fn size(config: &SizingConfig, inputs: &SizingInputs) -> anyhow::Result<f64> {
+    let confidence = inputs.confidence.clamp(0.0, 1.0);
+    if inputs.equity <= 0.0 {
+        anyhow::bail!("no equity to size against");
+    }
+
+    let size = match config.model {
+        SizingModel::FixedNotional => config.fixed_notional * confidence,
+        SizingModel::FixedFractional => inputs.equity * config.equity_fraction * confidence,
+        SizingModel::Kelly => {
+            let gain = inputs
+                .expected_return
+                .filter(|r| *r > 0.0)
+                .ok_or_else(|| anyhow::anyhow!("Kelly sizing needs a positive expected return"))?;
+            let loss = inputs
+                .expected_loss
+                .filter(|l| *l > 0.0)
+                .ok_or_else(|| anyhow::anyhow!("Kelly sizing needs a stop-loss distance"))?;
+
+            // f* = p/a - q/b with gain b and loss a per unit staked
+            let kelly = confidence / loss - (1.0 - confidence) / gain;
+            if kelly <= 0.0 {
+                anyhow::bail!("no edge: Kelly fraction {:.3} at confidence {:.2}", kelly, confidence);
+            }
+            inputs.equity * (kelly * config.kelly_fraction).min(1.0)
+        }
+        SizingModel::VolatilityTarget => {
+            let volatility = inputs
+                .volatility
+                .filter(|v| *v > 0.0)
+                .ok_or_else(|| anyhow::anyhow!("volatility target sizing needs price history"))?;
+            inputs.equity * config.target_volatility / volatility * confidence
+        }
+    };
+
+    Ok(size.max(0.0))
+}
+
+/// Sample standard deviation of `returns`
+pub fn volatility(returns: &[f64]) -> Option<f64> {
+    if returns.len() < 2 {
+        return None;
+    }
+    let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+    let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+    Some(variance.sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(model: SizingModel) -> SizingConfig {
+        SizingConfig {
+            model,
+            fixed_notional: 0.1,
+            equity_fraction: 0.02,
+            kelly_fraction: 0.25,
+            target_volatility: 0.002,
+            vol_interval_ms: 60_000,
+            vol_lookback: 500,
+        }
+    }
+
+    fn inputs(confidence: f64) -> SizingInputs {
+        SizingInputs {
+            equity: 10.0,
+            confidence,
+            expected_return: Some(0.5),
+            expected_loss: Some(0.5),
+            volatility: Some(0.02),
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn kelly_without_edge_is_an_error() {
+        // Even odds at a coin flip: f* = 0.5/0.5 - 0.5/0.5 = 0
+        let err = size(&config(SizingModel::Kelly), &inputs(0.5)).unwrap_err();
+        assert!(err.to_string().starts_with("no edge"), "{}", err);
+
+        let err = size(&config(SizingModel::Kelly), &inputs(0.4)).unwrap_err();
+        assert!(err.to_string().starts_with("no edge"), "{}", err);
+    }
+
+    #[test]
+    fn kelly_takes_the_configured_fraction_of_the_full_bet() {
+        // f* = 0.55/0.5 - 0.45/0.5 = 0.2; a quarter of it on 10 SOL
+        assert_close(size(&config(SizingModel::Kelly), &inputs(0.55)).unwrap(), 0.5);
+
+        let mut lopsided = inputs(0.9);
+        lopsided.expected_loss = Some(0.01);
+        // f* = 89.8, capped at the whole equity
+        assert_close(size(&config(SizingModel::Kelly), &lopsided).unwrap(), 10.0);
+    }
+
+    #[test]
+    fn kelly_needs_gain_and_loss() {
+        let mut no_gain = inputs(0.6);
+        no_gain.expected_return = None;
+        assert!(size(&config(SizingModel::Kelly), &no_gain).is_err());
+
+        let mut no_stop = inputs(0.6);
+        no_stop.expected_loss = Some(0.0);
+        assert!(size(&config(SizingModel::Kelly), &no_stop).is_err());
+    }
+
+    #[test]
+    fn volatility_target_scales_inversely_with_volatility() {
+        let config = config(SizingModel::VolatilityTarget);
+        // 10 SOL * 0.002 / 0.02
+        assert_close(size(&config, &inputs(1.0)).unwrap(), 1.0);
+        assert_close(size(&config, &inputs(0.5)).unwrap(), 0.5);
+
+        let mut calmer = inputs(1.0);
+        calmer.volatility = Some(0.01);
+        assert_close(size(&config, &calmer).unwrap(), 2.0);
+
+        let mut unknown = inputs(1.0);
+        unknown.volatility = None;
+        assert!(size(&config, &unknown).is_err());
+    }
+
+    #[test]
+    fn fixed_models_scale_with_confidence() {
+        assert_close(size(&config(SizingModel::FixedNotional), &inputs(0.5)).unwrap(), 0.05);
+        assert_close(size(&config(SizingModel::FixedFractional), &inputs(0.5)).unwrap(), 0.1);
+        // Confidence is clamped to 0..1
+        assert_close(size(&config(SizingModel::FixedNotional), &inputs(3.0)).unwrap(), 0.1);
+    }
+
+    #[test]
+    fn no_equity_is_an_error() {
+        let mut broke = inputs(1.0);
+        broke.equity = 0.0;
+        assert!(size(&config(SizingModel::FixedFractional), &broke).is_err());
+    }
+
+    #[test]
+    fn volatility_is_the_sample_standard_deviation() {
+        assert_eq!(volatility(&[0.01]), None);
+        // Mean 0, squared deviations 4e-4 over n - 1 = 3
+        assert_close(volatility(&[0.01, -0.01, 0.01, -0.01]).unwrap(), (4e-4f64 / 3.0).sqrt());
+    }
+}
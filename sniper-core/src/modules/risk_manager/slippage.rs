@@ -0,0 +1,332 @@
+// 💧 Price Impact Check
+// Pre-trade impact estimates from Jupiter quotes or Raydium pool reserves
+
+use serde::Serialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey};
+use std::str::FromStr;
+use tracing::debug;
+
+use crate::modules::data_ingestor::wallet_tracker::WSOL_MINT;
+use crate::modules::data_ingestor::RaydiumAmmPool;
+use crate::modules::executor::jupiter::JupiterClient;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ImpactSource {
+    Jupiter,
+    Pool,
+}
+
+#[derive(Debug, Clone)]
+pub struct SlippageConfig {
+    /// Largest accepted price impact as a fraction (0.03 = 3%)
+    pub max_price_impact: f64,
+    /// Sources tried in order until one answers
+    pub sources: Vec<ImpactSource>,
+    /// Quotes spent searching for the largest allowed size
+    pub search_steps: u32,
+}
+
+impl SlippageConfig {
+    pub fn from_env() -> Self {
+        let sources: Vec<ImpactSource> = std::env::var("PRICE_IMPACT_SOURCES")
+            .unwrap_or_else(|_| "jupiter,pool".to_string())
+            .split(',')
+            .filter_map(|source| match source.trim().to_lowercase().as_str() {
+                "jupiter" => Some(ImpactSource::Jupiter),
+                "pool" => Some(ImpactSource::Pool),
+                _ => None,
+            })
+            .collect();
+
+        Self {
+            max_price_impact: std::env::var("MAX_PRICE_IMPACT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0.03),
+            sources: if sources.is_empty() { vec![ImpactSource::Jupiter] } else { sources },
+            search_steps: std::env::var("PRICE_IMPACT_SEARCH_STEPS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(6),
+        }
+    }
+}
+
+/// Expected result of buying `size_sol` of a token
+#[derive(Debug, Clone, Serialize)]
+pub struct ImpactEstimate {
+    pub source: ImpactSource,
+    pub size_sol: f64,
+    pub price_impact: f64,
+    /// Raw token units expected for the size
+    pub expected_out: u64,
+}
+
+/// Impact at the requested size and at the size that should be sent
+#[derive(Debug, Clone)]
+pub struct ImpactCheck {
+    pub requested: ImpactEstimate,
+    /// Largest size within the impact limit; `None` if nothing fits
+    pub allowed: Option<ImpactEstimate>,
+}
+
+/// Estimate the impact of buying `size_sol` of `mint`, shrinking the size if it is too large
+///
+/// `pool` is the mint's SOL-quoted Raydium pool, used by the pool source.
+pub async fn check_buy(
+    config: &SlippageConfig,
+    jupiter: &JupiterClient,
+    rpc: &RpcClient,
+    mint: &str,
+    pool: Option<&str>,
+    size_sol: f64,
+    slippage_bps: u16,
+) -> anyhow::Result<ImpactCheck> {
+    let mut last_error = anyhow::anyhow!("no price impact source configured");
+
+    for source in &config.sources {
+        let result = match source {
+            ImpactSource::Jupiter => check_with_jupiter(config, jupiter, mint, size_sol, slippage_bps).await,
+            ImpactSource::Pool => match pool {
+                Some(pool) => check_with_pool(config, rpc, mint, pool, size_sol).await,
+                None => Err(anyhow::anyhow!("no Raydium pool known for {}", mint)),
+            },
+        };
+
+        match result {
+            Ok(check) => return Ok(check),
+            Err(e) => {
+                debug!("💧 {:?} impact estimate failed for {}: {}", source, mint, e);
+                last_error = e;
+            }
+        }
+    }
+
+    Err(last_error)
+}
+
+async fn check_with_jupiter(
+    config: &SlippageConfig,
+    jupiter: &JupiterClient,
+    mint: &str,
+    size_sol: f64,
+    slippage_bps: u16,
+) -> anyhow::Result<ImpactCheck> {
+    let quote = |size: f64| async move {
+        let lamports = (size * LAMPORTS_PER_SOL as f64).floor() as u64;
+        let quote = jupiter.quote(WSOL_MINT, mint, lamports, slippage_bps, None).await?;
+        anyhow::Ok(ImpactEstimate {
+            source: ImpactSource::Jupiter,
+            size_sol: size,
+            price_impact: quote.price_impact,
+            expected_out: quote.out_amount,
+        })
+    };
+
+    shrink_to_limit(config, size_sol, quote).await
+}
+
+/// Estimate `size_sol` with `estimate`, bisecting for the largest size within the limit if it is over
+async fn shrink_to_limit<F, Fut>(config: &SlippageConfig, size_sol: f64, estimate: F) -> anyhow::Result<ImpactCheck>
+where
+    F: Fn(f64) -> Fut,
+    Fut: std::future::Future<Output = anyhow::Result<ImpactEstimate>>,
+{
+    let requested = estimate(size_sol).await?;
+    if requested.price_impact <= config.max_price_impact {
+        return Ok(ImpactCheck { allowed: Some(requested.clone()), requested });
+    }
+
+    // Impact grows with size, so bisect for the largest size within the limit
+    let (mut low, mut high) = (0.0, size_sol);
+    let mut allowed = None;
+    for _ in 0..config.search_steps {
+        let mid = (low + high) / 2.0;
+        match estimate(mid).await {
+            Ok(estimate) if estimate.price_impact <= config.max_price_impact => {
+                low = mid;
+                allowed = Some(estimate);
+            }
+            Ok(_) => high = mid,
+            Err(e) => {
+                debug!("💧 Quote at {:.4} SOL failed: {}", mid, e);
+                break;
+            }
+        }
+    }
+
+    Ok(ImpactCheck { requested, allowed })
+}
+
+async fn check_with_pool(
+    config: &SlippageConfig,
+    rpc: &RpcClient,
+    mint: &str,
+    pool: &str,
+    size_sol: f64,
+) -> anyhow::Result<ImpactCheck> {
+    let reserves = PoolReserves::fetch(rpc, mint, pool).await?;
+    Ok(reserves.check(config, size_sol))
+}
+
+/// Constant-product reserves of a SOL pool, in raw units
+struct PoolReserves {
+    sol: f64,
+    token: f64,
+    fee: f64,
+}
+
+impl PoolReserves {
+    async fn fetch(rpc: &RpcClient, mint: &str, pool: &str) -> anyhow::Result<Self> {
+        let data = rpc.get_account_data(&Pubkey::from_str(pool)?).await?;
+        let pool = RaydiumAmmPool::decode(pool, &data)
+            .ok_or_else(|| anyhow::anyhow!("{} is not a Raydium AMM pool", pool))?;
+        if pool.other_mint(mint) != Some(WSOL_MINT) {
+            anyhow::bail!("pool {} does not pair {} with SOL", pool.address, mint);
+        }
+
+        let (sol_vault, token_vault) = if pool.base_mint == mint {
+            (&pool.quote_vault, &pool.base_vault)
+        } else {
+            (&pool.base_vault, &pool.quote_vault)
+        };
+        let balance = |vault: &str| {
+            let vault = Pubkey::from_str(vault);
+            async move {
+                let amount = rpc.get_token_account_balance(&vault?).await?.amount;
+                anyhow::Ok(amount.parse::<f64>()?)
+            }
+        };
+
+        let reserves = Self {
+            sol: balance(sol_vault).await?,
+            token: balance(token_vault).await?,
+            fee: pool.trade_fee,
+        };
+        if reserves.sol <= 0.0 || reserves.token <= 0.0 {
+            anyhow::bail!("pool {} is empty", pool.address);
+        }
+        Ok(reserves)
+    }
+
+    /// Impact of `size_sol`, with the closed-form largest size if it is over the limit
+    fn check(&self, config: &SlippageConfig, size_sol: f64) -> ImpactCheck {
+        let requested = self.buy(size_sol);
+        if requested.price_impact <= config.max_price_impact {
+            return ImpactCheck { allowed: Some(requested.clone()), requested };
+        }
+
+        let max_size = self.max_buy(config.max_price_impact);
+        ImpactCheck {
+            requested,
+            allowed: (max_size > 0.0).then(|| self.buy(max_size)),
+        }
+    }
+
+    /// Impact excludes the swap fee, matching Jupiter's figure
+    fn buy(&self, size_sol: f64) -> ImpactEstimate {
+        let net_in = size_sol * LAMPORTS_PER_SOL as f64 * (1.0 - self.fee);
+        ImpactEstimate {
+            source: ImpactSource::Pool,
+            size_sol,
+            price_impact: net_in / (self.sol + net_in),
+            expected_out: (self.token * net_in / (self.sol + net_in)).floor() as u64,
+        }
+    }
+
+    /// Largest buy in SOL whose impact stays within `max_impact`
+    fn max_buy(&self, max_impact: f64) -> f64 {
+        if max_impact <= 0.0 || max_impact >= 1.0 {
+            return 0.0;
+        }
+        self.sol * max_impact / ((1.0 - max_impact) * (1.0 - self.fee)) / LAMPORTS_PER_SOL as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(max_price_impact: f64) -> SlippageConfig {
+        SlippageConfig {
+            max_price_impact,
+            sources: vec![ImpactSource::Jupiter],
+            search_steps: 20,
+        }
+    }
+
+    /// 100 SOL against 1M tokens, no fee
+    fn reserves() -> PoolReserves {
+        PoolReserves {
+            sol: 100.0 * LAMPORTS_PER_SOL as f64,
+            token: 1_000_000.0,
+            fee: 0.0,
+        }
+    }
+
+    /// Quote source whose impact is 1% per SOL
+    async fn linear_quote(size: f64) -> anyhow::Result<ImpactEstimate> {
+        Ok(ImpactEstimate {
+            source: ImpactSource::Jupiter,
+            size_sol: size,
+            price_impact: size / 100.0,
+            expected_out: (size * 1_000.0) as u64,
+        })
+    }
+
+    #[test]
+    fn pool_buy_within_limit_is_allowed_as_is() {
+        let check = reserves().check(&config(0.03), 1.0);
+
+        // 1 SOL into 100 SOL moves the price by 1/101
+        assert!((check.requested.price_impact - 1.0 / 101.0).abs() < 1e-12);
+        assert_eq!(check.allowed.unwrap().size_sol, 1.0);
+    }
+
+    #[test]
+    fn pool_buy_over_limit_shrinks_to_the_limit() {
+        let check = reserves().check(&config(0.05), 20.0);
+        assert!(check.requested.price_impact > 0.05);
+
+        let allowed = check.allowed.unwrap();
+        // x / (100 + x) = 0.05 at x = 100 / 19
+        assert!((allowed.size_sol - 100.0 / 19.0).abs() < 1e-9);
+        assert!((allowed.price_impact - 0.05).abs() < 1e-9);
+        assert!(allowed.expected_out < check.requested.expected_out);
+    }
+
+    #[test]
+    fn pool_fee_lowers_the_allowed_size() {
+        let with_fee = PoolReserves { fee: 0.0025, ..reserves() };
+
+        assert!(with_fee.max_buy(0.05) > reserves().max_buy(0.05));
+        assert!((with_fee.buy(with_fee.max_buy(0.05)).price_impact - 0.05).abs() < 1e-9);
+        assert_eq!(reserves().max_buy(0.0), 0.0);
+    }
+
+    #[tokio::test]
+    async fn quote_within_limit_is_not_searched() {
+        let check = shrink_to_limit(&config(0.03), 2.0, linear_quote).await.unwrap();
+
+        assert_eq!(check.requested.size_sol, 2.0);
+        assert_eq!(check.allowed.unwrap().size_sol, 2.0);
+    }
+
+    #[tokio::test]
+    async fn quote_over_limit_bisects_below_the_limit() {
+        let check = shrink_to_limit(&config(0.03), 10.0, linear_quote).await.unwrap();
+        assert_eq!(check.requested.price_impact, 0.1);
+
+        let allowed = check.allowed.unwrap();
+        assert!(allowed.price_impact <= 0.03);
+        assert!(allowed.size_sol > 2.99 && allowed.size_sol <= 3.0);
+    }
+
+    #[tokio::test]
+    async fn nothing_allowed_when_every_size_is_over() {
+        let check = shrink_to_limit(&config(0.0), 10.0, linear_quote).await.unwrap();
+
+        assert!(check.allowed.is_none());
+    }
+}
@@ -0,0 +1,435 @@
+// 📉 Value at Risk
+// Historical, Monte Carlo and parametric VaR with Expected Shortfall
+
+use rand::Rng;
+use rand_distr::StandardNormal;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum VarMethod {
+    Historical,
+    MonteCarlo,
+    Parametric,
+}
+
+impl VarMethod {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "historical" => Some(Self::Historical),
+            "montecarlo" | "monte_carlo" | "mc" => Some(Self::MonteCarlo),
+            "parametric" | "variance_covariance" => Some(Self::Parametric),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VarConfig {
+    pub method: VarMethod,
+    pub confidence: f64,
+    /// Price samples fetched per mint
+    pub lookback: isize,
+    /// Return interval prices are aligned to
+    pub interval_ms: i64,
+    /// Mints with fewer aligned returns are left out of the estimate
+    pub min_samples: usize,
+    pub simulations: usize,
+}
+
+impl VarConfig {
+    pub fn from_env() -> Self {
+        let parse = |key: &str, default: f64| {
+            std::env::var(key)
+                .ok()
+                .and_then(|v| v.parse::<f64>().ok())
+                .unwrap_or(default)
+        };
+
+        Self {
+            method: std::env::var("VAR_METHOD")
+                .ok()
+                .and_then(|m| VarMethod::parse(&m))
+                .unwrap_or(VarMethod::Historical),
+            confidence: parse("VAR_CONFIDENCE", 0.95).clamp(0.5, 0.9999),
+            lookback: parse("VAR_LOOKBACK_SAMPLES", 1000.0) as isize,
+            interval_ms: parse("VAR_RETURN_INTERVAL_MS", 60_000.0) as i64,
+            min_samples: parse("VAR_MIN_SAMPLES", 30.0) as usize,
+            simulations: parse("VAR_MC_SIMULATIONS", 10_000.0) as usize,
+        }
+    }
+}
+
+/// One-interval loss estimate in SOL (positive = loss)
+#[derive(Debug, Clone, Serialize)]
+pub struct VarEstimate {
+    pub method: VarMethod,
+    pub confidence: f64,
+    pub var: f64,
+    /// Expected Shortfall: mean loss beyond the VaR
+    pub cvar: f64,
+    pub samples: usize,
+}
+
+/// Estimate VaR for holdings worth `values` SOL with aligned per-asset log `returns`
+pub fn estimate(config: &VarConfig, confidence: f64, values: &[f64], returns: &[Vec<f64>]) -> Option<VarEstimate> {
+    let samples = returns.first()?.len();
+    if samples < 2 || values.len() != returns.len() {
+        return None;
+    }
+
+    let (var, cvar) = match config.method {
+        VarMethod::Historical => {
+            let pnl: Vec<f64> = (0..samples)
+                .map(|t| portfolio_pnl(values, returns.iter().map(|r| r[t])))
+                .collect();
+            tail_loss(pnl, confidence)
+        }
+        VarMethod::Parametric => parametric(values, returns, confidence),
+        VarMethod::MonteCarlo => monte_carlo(values, returns, confidence, config.simulations)?,
+    };
+
+    Some(VarEstimate {
+        method: config.method,
+        confidence,
+        var: var.max(0.0),
+        cvar: cvar.max(0.0),
+        samples,
+    })
+}
+
+/// Bucket each price series (`(price, timestamp_ms)`, any order) onto a shared
+/// time grid, forward-fill gaps, and return per-asset log returns of equal length
+pub fn aligned_log_returns(histories: &[Vec<(f64, i64)>], interval_ms: i64, max_returns: usize) -> Vec<Vec<f64>> {
+    let interval_ms = interval_ms.max(1);
+    let mut bucketed = Vec::with_capacity(histories.len());
+
+    for history in histories {
+        let mut points: Vec<(i64, f64)> = history
+            .iter()
+            .filter(|(price, _)| *price > 0.0 && price.is_finite())
+            .map(|(price, ts)| (ts / interval_ms, *price))
+            .collect();
+        if points.is_empty() {
+            return vec![Vec::new(); histories.len()];
+        }
+        points.sort_by_key(|(bucket, _)| *bucket);
+        bucketed.push(points);
+    }
+
+    let start = bucketed.iter().map(|p| p[0].0).max().unwrap_or(0);
+    let end = bucketed.iter().map(|p| p[p.len() - 1].0).min().unwrap_or(0);
+    if end <= start {
+        return vec![Vec::new(); histories.len()];
+    }
+    let start = start.max(end - max_returns as i64);
+
+    bucketed
+        .iter()
+        .map(|points| {
+            // Last price at or before each bucket
+            let mut cursor = 0;
+            let mut last = points[0].1;
+            let mut prices = Vec::with_capacity((end - start + 1) as usize);
+            for bucket in start..=end {
+                while cursor < points.len() && points[cursor].0 <= bucket {
+                    last = points[cursor].1;
+                    cursor += 1;
+                }
+                prices.push(last);
+            }
+            prices.windows(2).map(|w| (w[1] / w[0]).ln()).collect()
+        })
+        .collect()
+}
+
+fn portfolio_pnl(values: &[f64], returns: impl Iterator<Item = f64>) -> f64 {
+    values.iter().zip(returns).map(|(v, r)| v * (r.exp() - 1.0)).sum()
+}
+
+/// VaR and CVaR from a PnL distribution
+fn tail_loss(mut pnl: Vec<f64>, confidence: f64) -> (f64, f64) {
+    pnl.sort_by(|a, b| a.total_cmp(b));
+    let index = (((1.0 - confidence) * pnl.len() as f64).floor() as usize).min(pnl.len() - 1);
+    let tail = &pnl[..=index];
+    (-pnl[index], -tail.iter().sum::<f64>() / tail.len() as f64)
+}
+
+fn parametric(values: &[f64], returns: &[Vec<f64>], confidence: f64) -> (f64, f64) {
+    let means = means(returns);
+    let cov = covariance(returns, &means);
+
+    let mean: f64 = values.iter().zip(&means).map(|(v, m)| v * m).sum();
+    let mut variance = 0.0;
+    for i in 0..values.len() {
+        for j in 0..values.len() {
+            variance += values[i] * values[j] * cov[i][j];
+        }
+    }
+    let sigma = variance.max(0.0).sqrt();
+    let z = inverse_normal_cdf(confidence);
+    let density = (-0.5 * z * z).exp() / (2.0 * std::f64::consts::PI).sqrt();
+
+    (z * sigma - mean, sigma * density / (1.0 - confidence) - mean)
+}
+
+fn monte_carlo(values: &[f64], returns: &[Vec<f64>], confidence: f64, simulations: usize) -> Option<(f64, f64)> {
+    let means = means(returns);
+    let cholesky = cholesky(&covariance(returns, &means))?;
+    let n = values.len();
+    let mut rng = rand::thread_rng();
+
+    let pnl: Vec<f64> = (0..simulations.max(100))
+        .map(|_| {
+            let shocks: Vec<f64> = (0..n).map(|_| rng.sample(StandardNormal)).collect();
+            let simulated = (0..n).map(|i| {
+                means[i] + (0..=i).map(|k| cholesky[i][k] * shocks[k]).sum::<f64>()
+            });
+            portfolio_pnl(values, simulated)
+        })
+        .collect();
+
+    Some(tail_loss(pnl, confidence))
+}
+
+fn means(returns: &[Vec<f64>]) -> Vec<f64> {
+    returns
+        .iter()
+        .map(|r| r.iter().sum::<f64>() / r.len() as f64)
+        .collect()
+}
+
+fn covariance(returns: &[Vec<f64>], means: &[f64]) -> Vec<Vec<f64>> {
+    let n = returns.len();
+    let samples = returns[0].len();
+    let mut cov = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        for j in 0..=i {
+            let c = (0..samples)
+                .map(|t| (returns[i][t] - means[i]) * (returns[j][t] - means[j]))
+                .sum::<f64>()
+                / (samples - 1) as f64;
+            cov[i][j] = c;
+            cov[j][i] = c;
+        }
+    }
+    cov
+}
+
+/// Lower-triangular factor; a small ridge keeps near-singular books decomposable
+fn cholesky(matrix: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let n = matrix.len();
+    let mut lower = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        for j in 0..=i {
+            let sum: f64 = (0..j).map(|k| lower[i][k] * lower[j][k]).sum();
+            if i == j {
+                let diagonal = matrix[i][i] + 1e-12 - sum;
+                if diagonal <= 0.0 {
+                    return None;
+                }
+                lower[i][j] = diagonal.sqrt();
+            } else {
+                lower[i][j] = (matrix[i][j] - sum) / lower[j][j];
+            }
+        }
+    }
+    Some(lower)
+}
+
+/// Acklam's rational approximation of the standard normal quantile
+fn inverse_normal_cdf(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e+01, 2.209460984245205e+02, -2.759285104469687e+02,
+        1.38357751867269e+02, -3.066479806614716e+01, 2.506628277459239e+00,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e+01, 1.615858368580409e+02, -1.556989798598866e+02,
+        6.680131188771972e+01, -1.328068155288572e+01,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-03, -3.223964580411365e-01, -2.400758277161838e+00,
+        -2.549732539343734e+00, 4.374664141464968e+00, 2.938163982698783e+00,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-03, 3.224671290700398e-01, 2.445134137142996e+00,
+        3.754408661907416e+00,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+
+    if p < P_LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - P_LOW {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(method: VarMethod) -> VarConfig {
+        VarConfig {
+            method,
+            confidence: 0.95,
+            lookback: 1000,
+            interval_ms: 60_000,
+            min_samples: 2,
+            simulations: 200_000,
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!((actual - expected).abs() <= tolerance, "expected {} got {}", expected, actual);
+    }
+
+    /// Alternating returns: mean 0.001, sample deviation just over 0.02
+    fn returns(samples: usize) -> Vec<f64> {
+        (0..samples).map(|t| if t % 2 == 0 { 0.021 } else { -0.019 }).collect()
+    }
+
+    #[test]
+    fn inverse_normal_cdf_matches_reference_quantiles() {
+        let reference = [
+            (0.001, -3.090232306),
+            (0.01, -2.326347874),
+            (0.05, -1.644853627),
+            (0.5, 0.0),
+            (0.9, 1.281551566),
+            (0.95, 1.644853627),
+            (0.975, 1.959963985),
+            (0.99, 2.326347874),
+            (0.9999, 3.719016485),
+        ];
+        for (p, z) in reference {
+            assert_close(inverse_normal_cdf(p), z, 1e-6);
+        }
+    }
+
+    #[test]
+    fn historical_tail_takes_the_floor_quantile() {
+        // Losses of 10 down to gains of 89
+        let pnl: Vec<f64> = (-10..90).rev().map(f64::from).collect();
+        let (var, cvar) = tail_loss(pnl, 0.95);
+        // floor(5% of 100) = index 5 of the sorted PnL: the sixth worst
+        assert_eq!(var, 5.0);
+        assert_eq!(cvar, (10.0 + 9.0 + 8.0 + 7.0 + 6.0 + 5.0) / 6.0);
+
+        // Too few samples for the quantile falls back to the worst outcome
+        let (var, cvar) = tail_loss(vec![3.0, -2.0, 1.0, -4.0], 0.95);
+        assert_eq!((var, cvar), (4.0, 4.0));
+    }
+
+    #[test]
+    fn historical_estimate_revalues_holdings_with_each_return() {
+        let returns = vec![vec![(0.9f64).ln(), (1.1f64).ln(), (1.05f64).ln(), (0.95f64).ln()]];
+        let estimate = estimate(&config(VarMethod::Historical), 0.75, &[2.0], &returns).unwrap();
+        // PnL of 2 SOL: -0.2, +0.2, +0.1, -0.1; index floor(0.25 * 4) = 1
+        assert_close(estimate.var, 0.1, 1e-12);
+        assert_close(estimate.cvar, 0.15, 1e-12);
+        assert_eq!(estimate.samples, 4);
+    }
+
+    #[test]
+    fn cholesky_factors_positive_definite_matrix() {
+        let matrix = vec![
+            vec![4.0, 12.0, -16.0],
+            vec![12.0, 37.0, -43.0],
+            vec![-16.0, -43.0, 98.0],
+        ];
+        let lower = cholesky(&matrix).unwrap();
+        let expected = [[2.0, 0.0, 0.0], [6.0, 1.0, 0.0], [-8.0, 5.0, 3.0]];
+        for i in 0..3 {
+            for j in 0..3 {
+                assert_close(lower[i][j], expected[i][j], 1e-9);
+                let product: f64 = (0..3).map(|k| lower[i][k] * lower[j][k]).sum();
+                assert_close(product, matrix[i][j], 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn cholesky_handles_singular_psd_and_rejects_indefinite() {
+        // Perfectly correlated assets: positive semi-definite, kept decomposable by the ridge
+        let singular = vec![vec![1.0, 1.0], vec![1.0, 1.0]];
+        let lower = cholesky(&singular).unwrap();
+        assert_close(lower[0][0], 1.0, 1e-9);
+        assert_close(lower[1][0], 1.0, 1e-9);
+
+        let indefinite = vec![vec![1.0, 2.0], vec![2.0, 1.0]];
+        assert!(cholesky(&indefinite).is_none());
+    }
+
+    #[test]
+    fn parametric_matches_the_normal_formula() {
+        let series = returns(100);
+        let mean = series.iter().sum::<f64>() / 100.0;
+        let sigma = (series.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / 99.0).sqrt();
+
+        let estimate = estimate(&config(VarMethod::Parametric), 0.95, &[3.0], &[series]).unwrap();
+        let z = 1.644853627;
+        assert_close(estimate.var, 3.0 * (z * sigma - mean), 1e-6);
+        // Expected Shortfall of a normal: sigma * phi(z) / (1 - confidence) - mean
+        let phi = (-0.5 * z * z).exp() / (2.0 * std::f64::consts::PI).sqrt();
+        assert_close(estimate.cvar, 3.0 * (sigma * phi / 0.05 - mean), 1e-6);
+        assert!(estimate.cvar > estimate.var);
+    }
+
+    #[test]
+    fn parametric_nets_anti_correlated_assets() {
+        let a = returns(100);
+        // Same distribution, shifted by one sample: perfectly anti-correlated with `a`
+        let b: Vec<f64> = (1..=100).map(|t| if t % 2 == 0 { 0.021 } else { -0.019 }).collect();
+        let single = estimate(&config(VarMethod::Parametric), 0.95, &[2.0], std::slice::from_ref(&a)).unwrap();
+        let hedged = estimate(&config(VarMethod::Parametric), 0.95, &[1.0, 1.0], &[a, b]).unwrap();
+        assert!(hedged.var < single.var * 0.1);
+    }
+
+    #[test]
+    fn monte_carlo_converges_to_parametric() {
+        let series = returns(200);
+        let parametric = estimate(&config(VarMethod::Parametric), 0.95, &[1.0], std::slice::from_ref(&series)).unwrap();
+        let simulated = estimate(&config(VarMethod::MonteCarlo), 0.95, &[1.0], &[series]).unwrap();
+        // Simulated log returns revalue through exp, so allow a few percent
+        assert_close(simulated.var, parametric.var, parametric.var * 0.05);
+    }
+
+    #[test]
+    fn estimate_needs_two_aligned_samples_per_holding() {
+        let config = config(VarMethod::Historical);
+        assert!(estimate(&config, 0.95, &[1.0], &[vec![0.01]]).is_none());
+        assert!(estimate(&config, 0.95, &[1.0, 1.0], &[vec![0.01, 0.02]]).is_none());
+        assert!(estimate(&config, 0.95, &[], &[]).is_none());
+    }
+
+    #[test]
+    fn aligned_returns_forward_fill_a_shared_grid() {
+        // Asset a prices every minute; asset b misses minute 2 and starts a minute later
+        let a = vec![(1.0, 0), (2.0, 60_000), (4.0, 120_000), (8.0, 180_000)];
+        let b = vec![(5.0, 60_500), (10.0, 180_000)];
+        let returns = aligned_log_returns(&[a, b], 60_000, 100);
+
+        let ln2 = 2f64.ln();
+        assert_eq!(returns.len(), 2);
+        // Shared buckets 1..=3: a is 2, 4, 8; b is 5, 5 (filled), 10
+        assert_eq!(returns[0].len(), 2);
+        assert_close(returns[0][0], ln2, 1e-12);
+        assert_close(returns[0][1], ln2, 1e-12);
+        assert_close(returns[1][0], 0.0, 1e-12);
+        assert_close(returns[1][1], ln2, 1e-12);
+
+        // Capped to the most recent returns
+        let capped = aligned_log_returns(&[vec![(1.0, 0), (2.0, 60_000), (4.0, 120_000)]], 60_000, 1);
+        assert_eq!(capped[0].len(), 1);
+        assert_close(capped[0][0], ln2, 1e-12);
+    }
+}
@@ -1,50 +1,225 @@
 // 🧠 Strategy Engine Module
 // AI-powered trading strategy and signal generation
 
+pub mod arbitrage;
+pub mod copy_trader;
+pub mod indicators;
+pub mod launch_sniper;
+pub mod mean_reversion;
+pub mod momentum;
+pub mod registry;
+pub mod strategy;
+pub mod volume;
+
 use tokio::sync::mpsc;
 use tracing::{info, error, debug, warn};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::time::Duration;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use std::sync::Arc;
+
+use super::data_ingestor::{MarketDataMessage, MarketEvent, TradeSide};
+use super::executor::arbitrage::ArbitrageOrder;
+use super::executor::ExecutionReport;
+
+#[allow(unused_imports)]
+pub use copy_trader::{CopySizing, CopyTrader, CopyTraderParams, LeaderConfig, LeaderStats};
+pub use indicators::{IndicatorConfig, IndicatorEngine, IndicatorSnapshot, SharedIndicators};
+pub use registry::{StrategyConfig, StrategyRegistry};
+pub use strategy::{Strategy, StrategySignal};
+#[allow(unused_imports)]
+pub use volume::{VolumeAnalyzer, VolumeFilter, VolumeParams, VolumeSnapshot};
+
+/// Registry with every strategy kind shipped in this crate
+pub fn builtin_registry() -> StrategyRegistry {
+    let mut registry = StrategyRegistry::default();
+    registry.register("momentum", momentum::build);
+    registry.register("mean_reversion", mean_reversion::build);
+    registry.register("arbitrage", arbitrage::build);
+    registry.register("launch_sniper", launch_sniper::build);
+    registry.register("copy_trader", copy_trader::build);
+    registry
+}
+
+/// Inputs queued per strategy task
+const STRATEGY_QUEUE_SIZE: usize = 1024;
+
+/// Trade intent sent from the strategy engine to the risk manager
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignalMessage {
+    pub id: String,
+    pub mint: String,
+    pub side: TradeSide,
+    /// Requested order notional in SOL; sized by the risk manager when absent
+    #[serde(default)]
+    pub size_sol: Option<f64>,
+    /// Reference price in SOL per token
+    pub price: f64,
+    /// Probability the trade works out, 0..1
+    pub confidence: f64,
+    /// Expected gain (fraction) if it does, used by Kelly sizing
+    #[serde(default)]
+    pub expected_return: Option<f64>,
+    /// Strategy's profit target and invalidation level, SOL per token
+    #[serde(default)]
+    pub target_price: Option<f64>,
+    #[serde(default)]
+    pub stop_price: Option<f64>,
+    /// Two-leg round trip; the order is sent as one atomic transaction
+    #[serde(default)]
+    pub arbitrage: Option<ArbitrageOrder>,
+    /// Buys are rejected once the chain is past this slot
+    #[serde(default)]
+    pub deadline_slot: Option<u64>,
+    pub timestamp_ms: i64,
+    /// Strategy instance that emitted the signal
+    #[serde(default)]
+    pub strategy_id: Option<String>,
+    /// Wallet the emitting strategy trades from
+    #[serde(default)]
+    pub wallet: Option<String>,
+    /// Capital the emitting strategy has left to deploy, in SOL
+    #[serde(default)]
+    pub max_size_sol: Option<f64>,
+}
+
+impl SignalMessage {
+    /// Tag a strategy's signal with its instance, wallet and the capital it has left (`remaining`, SOL)
+    pub fn from_strategy(signal: StrategySignal, config: &StrategyConfig, sequence: u64, remaining: f64) -> Self {
+        Self {
+            id: format!("{}-{}-{}", config.id, signal.timestamp_ms, sequence),
+            mint: signal.mint,
+            side: signal.side,
+            size_sol: signal.size_sol,
+            price: signal.price,
+            confidence: signal.confidence,
+            expected_return: signal.expected_return,
+            target_price: signal.target_price,
+            stop_price: signal.stop_price,
+            arbitrage: signal.arbitrage,
+            deadline_slot: signal.deadline_slot,
+            timestamp_ms: signal.timestamp_ms,
+            strategy_id: Some(config.id.clone()),
+            wallet: config.wallet.clone(),
+            max_size_sol: (signal.side == TradeSide::Buy).then_some(remaining),
+        }
+    }
+}
+
+/// Finished order routed back to the strategy that asked for it
+#[derive(Debug, Clone)]
+pub struct StrategyFill {
+    pub strategy_id: String,
+    pub report: ExecutionReport,
+}
+
+enum StrategyInput {
+    Event(Arc<MarketEvent>),
+    Fill(ExecutionReport),
+}
 
 pub struct StrategyEngine {
-    // TODO: Add channels when ready
-    // market_data_rx: mpsc::Receiver<MarketDataMessage>,
-    // signal_tx: mpsc::Sender<SignalMessage>,
+    market_data_rx: mpsc::Receiver<MarketEvent>,
+    signal_tx: mpsc::Sender<SignalMessage>,
+    fill_tx: mpsc::Sender<StrategyFill>,
+    fill_rx: mpsc::Receiver<StrategyFill>,
+    registry: StrategyRegistry,
+    configs: Vec<StrategyConfig>,
 }
 
 impl StrategyEngine {
-    pub async fn new() -> anyhow::Result<Self> {
+    pub async fn new(
+        market_data_rx: mpsc::Receiver<MarketEvent>,
+        signal_tx: mpsc::Sender<SignalMessage>,
+    ) -> anyhow::Result<Self> {
         info!("🧠 StrategyEngine initializing...");
 
-        // TODO: Initialize AI models and strategies
-        // - Load pre-trained models
-        // - Initialize technical indicators
-        // - Setup strategy parameters
+        let configs = registry::load_configs()?;
+        let (fill_tx, fill_rx) = mpsc::channel(STRATEGY_QUEUE_SIZE);
 
-        info!("✅ StrategyEngine initialized");
+        let registry = builtin_registry();
+
+        info!("✅ StrategyEngine initialized with {} strategy configs", configs.len());
 
         Ok(Self {
-            // market_data_rx,
-            // signal_tx,
+            market_data_rx,
+            signal_tx,
+            fill_tx,
+            fill_rx,
+            registry,
+            configs,
         })
     }
 
+    /// Registry to add strategy kinds to before `run`
+    #[allow(dead_code)]
+    pub fn registry_mut(&mut self) -> &mut StrategyRegistry {
+        &mut self.registry
+    }
+
+    /// Where order outcomes for tagged signals should be sent
+    pub fn fill_sender(&self) -> mpsc::Sender<StrategyFill> {
+        self.fill_tx.clone()
+    }
+
     pub async fn run(&mut self) -> anyhow::Result<()> {
         info!("🚀 Starting StrategyEngine...");
 
-        // TODO: Start strategy processing
-        // - Listen for market data
-        // - Analyze patterns
-        // - Generate trading signals
+        let mut strategies: Vec<(String, mpsc::Sender<StrategyInput>, mpsc::Sender<ExecutionReport>)> = Vec::new();
+        for config in self.configs.iter().filter(|c| c.enabled) {
+            let strategy = match self.registry.build(config) {
+                Ok(strategy) => strategy,
+                Err(e) => {
+                    error!("❌ Strategy {} not started: {}", config.id, e);
+                    continue;
+                }
+            };
+            info!("🧩 Strategy {} ({}) started with {:.4} SOL - params {}",
+                  config.id, config.kind, config.capital_sol, strategy.params());
+
+            // Fills get their own queue so a backlog of events never holds them up
+            let (input_tx, input_rx) = mpsc::channel(STRATEGY_QUEUE_SIZE);
+            let (report_tx, report_rx) = mpsc::channel(STRATEGY_QUEUE_SIZE);
+            tokio::spawn(run_strategy(config.clone(), strategy, input_rx, report_rx, self.signal_tx.clone()));
+            strategies.push((config.id.clone(), input_tx, report_tx));
+        }
+
+        if strategies.is_empty() {
+            warn!("🧠 No strategies enabled - registered kinds: {:?}", self.registry.kinds());
+        }
 
-        // Placeholder implementation
         loop {
-            tokio::time::sleep(Duration::from_secs(10)).await;
-            debug!("🧠 StrategyEngine heartbeat - ready for implementation");
+            tokio::select! {
+                event = self.market_data_rx.recv() => {
+                    let Some(event) = event else {
+                        info!("🧠 Market data channel closed - StrategyEngine stopping");
+                        return Ok(());
+                    };
+                    let event = Arc::new(event);
+                    // A slow strategy drops events rather than stalling the others
+                    for (id, input_tx, _) in &strategies {
+                        if input_tx.try_send(StrategyInput::Event(event.clone())).is_err() {
+                            debug!("🧠 Strategy {} lagging - event dropped", id);
+                        }
+                    }
+                }
+                Some(fill) = self.fill_rx.recv() => {
+                    // Never wait on a strategy here: this path carries the risk manager's reports
+                    match strategies.iter().find(|(id, _, _)| *id == fill.strategy_id) {
+                        Some((id, _, report_tx)) => {
+                            if let Err(e) = report_tx.try_send(fill.report) {
+                                warn!("⚠️ Strategy {} fill queue unavailable - fill for order {} dropped", id, e.into_inner().order_id);
+                            }
+                        }
+                        None => debug!("🧠 Fill for unknown strategy {}", fill.strategy_id),
+                    }
+                }
+            }
         }
     }
 
     /// Analyze market data and generate trading signals
+    #[allow(dead_code)]
     async fn analyze_market_data(&self, data: Value) -> anyhow::Result<()> {
         debug!("📈 Analyzing market data: {:?}", data);
 
@@ -58,7 +233,8 @@ impl StrategyEngine {
     }
 
     /// Generate trading signal based on analysis
-    async fn generate_signal(&self, analysis: Value) -> anyhow::Result<()> {
+    #[allow(dead_code)]
+    async fn generate_signal(&self, _analysis: Value) -> anyhow::Result<()> {
         debug!("🎯 Generating trading signal from analysis");
 
         // TODO: Create trading signal
@@ -70,48 +246,9 @@ impl StrategyEngine {
         Ok(())
     }
 
-    /// Momentum strategy - detect price momentum
-    async fn momentum_strategy(&self, price_data: &[f64]) -> anyhow::Result<f64> {
-        // TODO: Implement momentum calculation
-        // - Calculate moving averages
-        // - Detect trend direction
-        // - Measure momentum strength
-
-        Ok(0.0) // Placeholder confidence score
-    }
-
-    /// Mean reversion strategy - detect overbought/oversold conditions
-    async fn mean_reversion_strategy(&self, price_data: &[f64]) -> anyhow::Result<f64> {
-        // TODO: Implement mean reversion
-        // - Calculate RSI
-        // - Detect support/resistance levels
-        // - Identify reversal patterns
-
-        Ok(0.0) // Placeholder confidence score
-    }
-
-    /// Arbitrage strategy - detect price differences across DEXs
-    async fn arbitrage_strategy(&self, dex_prices: &[(String, f64)]) -> anyhow::Result<f64> {
-        // TODO: Implement arbitrage detection
-        // - Compare prices across DEXs
-        // - Calculate profit potential
-        // - Account for fees and slippage
-
-        Ok(0.0) // Placeholder confidence score
-    }
-
-    /// Volume analysis - analyze trading volume patterns
-    async fn volume_analysis(&self, volume_data: &[f64]) -> anyhow::Result<f64> {
-        // TODO: Implement volume analysis
-        // - Detect volume spikes
-        // - Analyze volume-price relationship
-        // - Identify accumulation/distribution
-
-        Ok(0.0) // Placeholder confidence score
-    }
-
     /// Risk assessment for generated signals
-    async fn assess_signal_risk(&self, signal: &Value) -> anyhow::Result<f64> {
+    #[allow(dead_code)]
+    async fn assess_signal_risk(&self, _signal: &Value) -> anyhow::Result<f64> {
         // TODO: Implement risk assessment
         // - Volatility analysis
         // - Correlation analysis
@@ -120,17 +257,8 @@ impl StrategyEngine {
         Ok(0.5) // Placeholder risk score
     }
 
-    /// Backtest strategy performance
-    async fn backtest_strategy(&self, historical_data: &[Value]) -> anyhow::Result<f64> {
-        // TODO: Implement backtesting
-        // - Simulate trades on historical data
-        // - Calculate performance metrics
-        // - Optimize strategy parameters
-
-        Ok(0.0) // Placeholder performance score
-    }
-
     /// Health check for strategy engine
+    #[allow(dead_code)]
     pub async fn health_check(&self) -> anyhow::Result<bool> {
         // TODO: Check strategy engine health
         // - Verify model loading
@@ -140,3 +268,69 @@ impl StrategyEngine {
         Ok(true)
     }
 }
+
+/// Feed one strategy its inputs and forward its signals, tagged and bounded by its capital
+async fn run_strategy(
+    config: StrategyConfig,
+    mut strategy: Box<dyn Strategy>,
+    mut input_rx: mpsc::Receiver<StrategyInput>,
+    mut report_rx: mpsc::Receiver<ExecutionReport>,
+    signal_tx: mpsc::Sender<SignalMessage>,
+) {
+    let mut deployed_sol = 0.0;
+    let mut sequence = 0u64;
+
+    loop {
+        // Fills first, so position state is current before the next event
+        let input = tokio::select! {
+            biased;
+            Some(report) = report_rx.recv() => StrategyInput::Fill(report),
+            input = input_rx.recv() => match input {
+                Some(input) => input,
+                None => return,
+            },
+        };
+
+        let result = match &input {
+            StrategyInput::Event(event) => match &event.event {
+                MarketDataMessage::PriceTick(tick) => strategy.on_tick(tick).await,
+                _ => strategy.on_event(event).await,
+            },
+            StrategyInput::Fill(report) => {
+                if report.success {
+                    match report.side {
+                        TradeSide::Buy => deployed_sol += report.in_amount as f64 / LAMPORTS_PER_SOL as f64,
+                        TradeSide::Sell => {
+                            deployed_sol = (deployed_sol - report.out_amount as f64 / LAMPORTS_PER_SOL as f64).max(0.0)
+                        }
+                    }
+                }
+                strategy.on_fill(report).await
+            }
+        };
+
+        let signals = match result {
+            Ok(signals) => signals,
+            Err(e) => {
+                warn!("⚠️ Strategy {} failed: {}", config.id, e);
+                continue;
+            }
+        };
+
+        for signal in signals {
+            let remaining = (config.capital_sol - deployed_sol).max(0.0);
+            if signal.side == TradeSide::Buy && remaining <= 0.0 {
+                debug!("🧠 Strategy {} allocation exhausted - buy of {} dropped", config.id, signal.mint);
+                continue;
+            }
+
+            sequence += 1;
+            let message = SignalMessage::from_strategy(signal, &config, sequence, remaining);
+
+            if signal_tx.send(message).await.is_err() {
+                info!("🧠 Signal channel closed - strategy {} stopping", config.id);
+                return;
+            }
+        }
+    }
+}
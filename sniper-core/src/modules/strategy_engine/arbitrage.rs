@@ -0,0 +1,454 @@
+// 🔀 Cross-DEX Arbitrage Strategy
+// Same-pair price gaps between Raydium, Orca, Meteora and Jupiter routes
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use std::collections::HashMap;
+use tracing::{debug, info};
+
+use super::registry::StrategyConfig;
+use super::strategy::{Strategy, StrategySignal};
+use crate::modules::data_ingestor::wallet_tracker::WSOL_MINT;
+use crate::modules::data_ingestor::{PriceTick, TradeSide};
+use crate::modules::executor::arbitrage::ArbitrageOrder;
+use crate::modules::executor::jupiter::JupiterClient;
+
+/// (√5 - 1) / 2, the golden-section step
+const GOLDEN_RATIO: f64 = 0.618_033_988_749_895;
+
+/// Where a leg may be routed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Venue {
+    Raydium,
+    Orca,
+    Meteora,
+    /// Any Jupiter route, including multi-hop
+    Jupiter,
+}
+
+impl Venue {
+    /// Jupiter DEX labels covering the venue's pool programs
+    pub fn dexes(self) -> Vec<String> {
+        let labels: &[&str] = match self {
+            Self::Raydium => &["Raydium", "Raydium CLMM", "Raydium CP"],
+            Self::Orca => &["Whirlpool", "Orca V2"],
+            Self::Meteora => &["Meteora", "Meteora DLMM"],
+            Self::Jupiter => &[],
+        };
+        labels.iter().map(|l| l.to_string()).collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArbitrageParams {
+    /// Mints scanned when their price ticks arrive
+    pub mints: Vec<String>,
+    pub venues: Vec<Venue>,
+    /// Size used to find the cheapest and richest venue
+    pub probe_size_sol: f64,
+    /// Bounds of the optimal size search
+    pub min_size_sol: f64,
+    pub max_size_sol: f64,
+    /// Golden-section iterations, two quotes each
+    pub search_steps: u32,
+    pub slippage_bps: u16,
+    /// Slippage expected between quote and landing, charged against profit
+    pub slippage_buffer_bps: u16,
+    pub priority_fee_lamports: u64,
+    pub jito_tip_lamports: u64,
+    pub min_profit_sol: f64,
+    /// Per-mint pause between scans
+    pub scan_interval_ms: i64,
+    pub max_accounts: u8,
+}
+
+impl Default for ArbitrageParams {
+    fn default() -> Self {
+        Self {
+            mints: Vec::new(),
+            venues: vec![Venue::Raydium, Venue::Orca, Venue::Meteora, Venue::Jupiter],
+            probe_size_sol: 0.1,
+            min_size_sol: 0.05,
+            max_size_sol: 2.0,
+            search_steps: 6,
+            slippage_bps: 50,
+            slippage_buffer_bps: 20,
+            priority_fee_lamports: 200_000,
+            jito_tip_lamports: 100_000,
+            min_profit_sol: 0.002,
+            scan_interval_ms: 2_000,
+            max_accounts: 28,
+        }
+    }
+}
+
+/// Round trip priced at one size, all amounts in lamports
+#[derive(Debug, Clone, Serialize)]
+pub struct ArbitrageOpportunity {
+    pub mint: String,
+    pub buy_venue: Venue,
+    pub sell_venue: Venue,
+    pub size_lamports: u64,
+    pub tokens: u64,
+    /// Quoted SOL back from the sell leg, after swap fees
+    pub gross_out: u64,
+    /// Base fee, priority fee and Jito tip
+    pub fees: u64,
+    /// Slippage buffer charged on the sell leg
+    pub slippage: u64,
+    pub net_profit: i64,
+}
+
+pub struct ArbitrageStrategy {
+    params: ArbitrageParams,
+    jupiter: JupiterClient,
+    last_scan: HashMap<String, i64>,
+}
+
+/// `StrategyFactory` for kind `arbitrage`
+pub fn build(config: &StrategyConfig) -> anyhow::Result<Box<dyn Strategy>> {
+    let params: ArbitrageParams = config.parse_params()?;
+    if params.venues.len() < 2 {
+        anyhow::bail!("arbitrage needs at least two venues");
+    }
+    if params.min_size_sol <= 0.0 || params.min_size_sol > params.max_size_sol {
+        anyhow::bail!("arbitrage size bounds must satisfy 0 < min_size_sol <= max_size_sol");
+    }
+    Ok(Box::new(ArbitrageStrategy::new(params, JupiterClient::from_env())))
+}
+
+impl ArbitrageStrategy {
+    pub fn new(params: ArbitrageParams, jupiter: JupiterClient) -> Self {
+        Self {
+            params,
+            jupiter,
+            last_scan: HashMap::new(),
+        }
+    }
+
+    fn fees(&self) -> u64 {
+        self.order(Venue::Jupiter, Venue::Jupiter).cost_lamports()
+    }
+
+    fn order(&self, buy: Venue, sell: Venue) -> ArbitrageOrder {
+        ArbitrageOrder {
+            buy_venue: format!("{:?}", buy),
+            sell_venue: format!("{:?}", sell),
+            buy_dexes: buy.dexes(),
+            sell_dexes: sell.dexes(),
+            min_profit_lamports: (self.params.min_profit_sol * LAMPORTS_PER_SOL as f64) as u64,
+            priority_fee_lamports: self.params.priority_fee_lamports,
+            jito_tip_lamports: self.params.jito_tip_lamports,
+            max_accounts: self.params.max_accounts,
+        }
+    }
+
+    async fn buy_quote(&self, mint: &str, venue: Venue, lamports: u64) -> Option<u64> {
+        let quote = self
+            .jupiter
+            .quote_on_dexes(WSOL_MINT, mint, lamports, self.params.slippage_bps, Some(self.params.max_accounts), &venue.dexes())
+            .await;
+        match quote {
+            Ok(quote) => Some(quote.out_amount),
+            Err(e) => {
+                debug!("🔀 No {:?} buy route for {}: {}", venue, mint, e);
+                None
+            }
+        }
+    }
+
+    async fn sell_quote(&self, mint: &str, venue: Venue, tokens: u64) -> Option<u64> {
+        let quote = self
+            .jupiter
+            .quote_on_dexes(mint, WSOL_MINT, tokens, self.params.slippage_bps, Some(self.params.max_accounts), &venue.dexes())
+            .await;
+        match quote {
+            Ok(quote) => Some(quote.out_amount),
+            Err(e) => {
+                debug!("🔀 No {:?} sell route for {}: {}", venue, mint, e);
+                None
+            }
+        }
+    }
+
+    /// Price the round trip buy-on-`buy`, sell-on-`sell` at `size_sol`
+    async fn evaluate(&self, mint: &str, buy: Venue, sell: Venue, size_sol: f64) -> Option<ArbitrageOpportunity> {
+        let size_lamports = (size_sol * LAMPORTS_PER_SOL as f64) as u64;
+        let tokens = self.buy_quote(mint, buy, size_lamports).await?;
+        let gross_out = self.sell_quote(mint, sell, tokens).await?;
+
+        let fees = self.fees();
+        let slippage = gross_out * self.params.slippage_buffer_bps as u64 / 10_000;
+        Some(ArbitrageOpportunity {
+            mint: mint.to_string(),
+            buy_venue: buy,
+            sell_venue: sell,
+            size_lamports,
+            tokens,
+            gross_out,
+            fees,
+            slippage,
+            net_profit: gross_out as i64 - size_lamports as i64 - fees as i64 - slippage as i64,
+        })
+    }
+
+    /// Cheapest venue to buy on and richest other venue to sell on, at the probe size
+    async fn best_pair(&self, mint: &str) -> Option<(Venue, Venue)> {
+        let probe = (self.params.probe_size_sol * LAMPORTS_PER_SOL as f64) as u64;
+
+        let mut best_buy: Option<(Venue, u64)> = None;
+        for venue in &self.params.venues {
+            if let Some(tokens) = self.buy_quote(mint, *venue, probe).await {
+                if best_buy.is_none_or(|(_, best)| tokens > best) {
+                    best_buy = Some((*venue, tokens));
+                }
+            }
+        }
+        let (buy_venue, tokens) = best_buy?;
+
+        let mut best_sell: Option<(Venue, u64)> = None;
+        for venue in self.params.venues.iter().filter(|v| **v != buy_venue) {
+            if let Some(lamports) = self.sell_quote(mint, *venue, tokens).await {
+                if best_sell.is_none_or(|(_, best)| lamports > best) {
+                    best_sell = Some((*venue, lamports));
+                }
+            }
+        }
+        let (sell_venue, lamports) = best_sell?;
+
+        // No gross edge at the probe size means impact only makes larger sizes worse
+        (lamports > probe).then_some((buy_venue, sell_venue))
+    }
+
+    /// Most profitable size for the best venue pair, by golden-section search
+    ///
+    /// Profit is concave in size: fixed fees favour larger trades until impact takes over.
+    pub async fn scan(&self, mint: &str) -> Option<ArbitrageOpportunity> {
+        let (buy, sell) = self.best_pair(mint).await?;
+        let mut best: Option<ArbitrageOpportunity> = None;
+        let mut consider = |candidate: Option<ArbitrageOpportunity>| {
+            let profit = candidate.as_ref().map(|c| c.net_profit).unwrap_or(i64::MIN);
+            if let Some(candidate) = candidate.filter(|_| best.as_ref().is_none_or(|b| profit > b.net_profit)) {
+                best = Some(candidate);
+            }
+            profit
+        };
+
+        let (mut low, mut high) = (self.params.min_size_sol, self.params.max_size_sol);
+        let mut left = high - GOLDEN_RATIO * (high - low);
+        let mut right = low + GOLDEN_RATIO * (high - low);
+        let mut left_profit = consider(self.evaluate(mint, buy, sell, left).await);
+        let mut right_profit = consider(self.evaluate(mint, buy, sell, right).await);
+
+        for _ in 0..self.params.search_steps {
+            if left_profit >= right_profit {
+                high = right;
+                right = left;
+                right_profit = left_profit;
+                left = high - GOLDEN_RATIO * (high - low);
+                left_profit = consider(self.evaluate(mint, buy, sell, left).await);
+            } else {
+                low = left;
+                left = right;
+                left_profit = right_profit;
+                right = low + GOLDEN_RATIO * (high - low);
+                right_profit = consider(self.evaluate(mint, buy, sell, right).await);
+            }
+        }
+
+        best
+    }
+}
+
+#[async_trait]
+impl Strategy for ArbitrageStrategy {
+    async fn on_tick(&mut self, tick: &PriceTick) -> anyhow::Result<Vec<StrategySignal>> {
+        if !self.params.mints.contains(&tick.mint) {
+            return Ok(Vec::new());
+        }
+        let last = self.last_scan.get(&tick.mint).copied().unwrap_or(i64::MIN);
+        if tick.timestamp_ms.saturating_sub(last) < self.params.scan_interval_ms {
+            return Ok(Vec::new());
+        }
+        self.last_scan.insert(tick.mint.clone(), tick.timestamp_ms);
+
+        let min_profit = (self.params.min_profit_sol * LAMPORTS_PER_SOL as f64) as i64;
+        let Some(opportunity) = self.scan(&tick.mint).await.filter(|o| o.net_profit >= min_profit) else {
+            return Ok(Vec::new());
+        };
+
+        info!("🔀 {} arbitrage: buy {:?} sell {:?} at {:.4} SOL for {} lamports net",
+              opportunity.mint, opportunity.buy_venue, opportunity.sell_venue,
+              opportunity.size_lamports as f64 / LAMPORTS_PER_SOL as f64, opportunity.net_profit);
+
+        let size_sol = opportunity.size_lamports as f64 / LAMPORTS_PER_SOL as f64;
+        Ok(vec![StrategySignal {
+            mint: opportunity.mint.clone(),
+            side: TradeSide::Buy,
+            size_sol: Some(size_sol),
+            price: tick.price,
+            // Quoted edges are firm; the executor re-quotes and reverts if the gap closed
+            confidence: 0.9,
+            expected_return: Some(opportunity.net_profit as f64 / opportunity.size_lamports as f64),
+            target_price: None,
+            stop_price: None,
+            arbitrage: Some(self.order(opportunity.buy_venue, opportunity.sell_venue)),
+            deadline_slot: None,
+            timestamp_ms: tick.timestamp_ms,
+        }])
+    }
+
+    fn params(&self) -> Value {
+        serde_json::to_value(&self.params).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::executor::arbitrage::BASE_FEE_LAMPORTS;
+    use axum::{extract::Query, routing::get, Json, Router};
+    use serde_json::json;
+
+    const MINT: &str = "MINT";
+    const DEPTH: u128 = 100 * LAMPORTS_PER_SOL as u128;
+
+    /// Constant-product pool per venue as (SOL reserve, token reserve), keyed by its first DEX label
+    fn pool(dexes: Option<&str>, cheap_buy: f64, rich_sell: f64) -> (u128, u128) {
+        let tokens = match dexes.and_then(|d| d.split(',').next()) {
+            Some("Raydium") => DEPTH as f64 * cheap_buy,
+            Some("Whirlpool") => DEPTH as f64 / rich_sell,
+            _ => DEPTH as f64,
+        };
+        (DEPTH, tokens as u128)
+    }
+
+    fn swap_out(reserve_in: u128, reserve_out: u128, amount: u128) -> u128 {
+        reserve_out * amount / (reserve_in + amount)
+    }
+
+    /// Fake Jupiter quoting Raydium `edge` cheap to buy and Orca `edge` rich to sell
+    async fn jupiter(edge: f64) -> JupiterClient {
+        let quote = move |Query(query): Query<HashMap<String, String>>| async move {
+            let amount: u128 = query["amount"].parse().unwrap();
+            let (sol, tokens) = pool(query.get("dexes").map(String::as_str), 1.0 + edge, 1.0 + edge);
+            let out = if query["inputMint"] == WSOL_MINT {
+                swap_out(sol, tokens, amount)
+            } else {
+                swap_out(tokens, sol, amount)
+            };
+            Json(json!({
+                "inAmount": amount.to_string(),
+                "outAmount": out.to_string(),
+                "otherAmountThreshold": out.to_string(),
+                "priceImpactPct": "0",
+            }))
+        };
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, Router::new().route("/quote", get(quote))).await });
+        JupiterClient::new(url)
+    }
+
+    fn params() -> ArbitrageParams {
+        ArbitrageParams {
+            mints: vec![MINT.to_string()],
+            venues: vec![Venue::Meteora, Venue::Orca, Venue::Raydium],
+            ..ArbitrageParams::default()
+        }
+    }
+
+    /// Net profit of the Raydium -> Orca round trip, priced straight off the pools
+    fn round_trip(edge: f64, size_lamports: u64, params: &ArbitrageParams) -> i64 {
+        let (sol, tokens) = pool(Some("Raydium"), 1.0 + edge, 1.0 + edge);
+        let bought = swap_out(sol, tokens, size_lamports as u128);
+        let (sol, tokens) = pool(Some("Whirlpool"), 1.0 + edge, 1.0 + edge);
+        let gross = swap_out(tokens, sol, bought) as u64;
+        let fees = BASE_FEE_LAMPORTS + params.priority_fee_lamports + params.jito_tip_lamports;
+        gross as i64 - size_lamports as i64 - fees as i64 - (gross * params.slippage_buffer_bps as u64 / 10_000) as i64
+    }
+
+    #[tokio::test]
+    async fn best_pair_buys_cheapest_and_sells_richest() {
+        let strategy = ArbitrageStrategy::new(params(), jupiter(0.01).await);
+        assert_eq!(strategy.best_pair(MINT).await, Some((Venue::Raydium, Venue::Orca)));
+
+        // Identical pools have no gross edge at the probe size
+        let strategy = ArbitrageStrategy::new(params(), jupiter(0.0).await);
+        assert_eq!(strategy.best_pair(MINT).await, None);
+    }
+
+    #[tokio::test]
+    async fn scan_finds_the_most_profitable_size_net_of_costs() {
+        let params = ArbitrageParams {
+            search_steps: 20,
+            ..params()
+        };
+        let strategy = ArbitrageStrategy::new(params.clone(), jupiter(0.02).await);
+        let opportunity = strategy.scan(MINT).await.unwrap();
+        assert_eq!((opportunity.buy_venue, opportunity.sell_venue), (Venue::Raydium, Venue::Orca));
+
+        // Fees and the slippage buffer all come off the quoted edge
+        assert_eq!(opportunity.fees, BASE_FEE_LAMPORTS + 200_000 + 100_000);
+        assert_eq!(opportunity.slippage, opportunity.gross_out * 20 / 10_000);
+        assert_eq!(
+            opportunity.net_profit,
+            opportunity.gross_out as i64
+                - opportunity.size_lamports as i64
+                - opportunity.fees as i64
+                - opportunity.slippage as i64
+        );
+        assert_eq!(opportunity.net_profit, round_trip(0.02, opportunity.size_lamports, &params));
+
+        // Within a hair of the best size on a fine grid across the bounds
+        let (best_size, best_profit) = (50..=2_000)
+            .map(|milli| milli * LAMPORTS_PER_SOL / 1_000)
+            .map(|size| (size, round_trip(0.02, size, &params)))
+            .max_by_key(|(_, profit)| *profit)
+            .unwrap();
+        assert!(best_size > params.min_size_sol as u64 * LAMPORTS_PER_SOL && best_size < 2 * LAMPORTS_PER_SOL);
+        assert!(opportunity.net_profit >= best_profit - best_profit / 1_000);
+        assert!((opportunity.size_lamports as f64 - best_size as f64).abs() < 0.01 * LAMPORTS_PER_SOL as f64);
+    }
+
+    #[tokio::test]
+    async fn edge_smaller_than_costs_emits_nothing() {
+        // A 0.3% gap on 100 SOL pools never covers the tip, priority fee and buffer
+        let mut strategy = ArbitrageStrategy::new(params(), jupiter(0.003).await);
+        let opportunity = strategy.scan(MINT).await.unwrap();
+        assert!(opportunity.gross_out > opportunity.size_lamports);
+        assert!(opportunity.net_profit < 0);
+
+        let tick = PriceTick {
+            mint: MINT.to_string(),
+            price: 0.01,
+            timestamp_ms: 1_000,
+        };
+        assert!(strategy.on_tick(&tick).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn profitable_gap_signals_a_sized_arbitrage_buy() {
+        let mut strategy = ArbitrageStrategy::new(params(), jupiter(0.02).await);
+        let tick = PriceTick {
+            mint: MINT.to_string(),
+            price: 0.01,
+            timestamp_ms: 1_000,
+        };
+        let signals = strategy.on_tick(&tick).await.unwrap();
+        assert_eq!(signals.len(), 1);
+        let signal = &signals[0];
+        assert_eq!(signal.side, TradeSide::Buy);
+        let order = signal.arbitrage.as_ref().unwrap();
+        assert_eq!((order.buy_venue.as_str(), order.sell_venue.as_str()), ("Raydium", "Orca"));
+        assert!(signal.size_sol.unwrap() >= 0.05 && signal.size_sol.unwrap() <= 2.0);
+
+        // Rescans of the mint wait out the scan interval
+        let soon = PriceTick { timestamp_ms: 2_000, ..tick };
+        assert!(strategy.on_tick(&soon).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,488 @@
+// 👥 Copy Trader Strategy
+// Mirror the buys and sells of tracked leader wallets
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use std::collections::HashMap;
+use tracing::{debug, info};
+
+use super::registry::StrategyConfig;
+use super::strategy::{Strategy, StrategySignal};
+use crate::modules::data_ingestor::{MarketDataMessage, MarketEvent, PriceTick, TradeSide, WalletActivity};
+use crate::modules::executor::ExecutionReport;
+
+/// A copied buy that never filled stops blocking its mint after this long
+const UNFILLED_TIMEOUT_MS: i64 = 60_000;
+
+/// How a leader's trade is turned into our order size
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "mode")]
+pub enum CopySizing {
+    /// Same notional for every copied buy
+    Fixed { size_sol: f64 },
+    /// Fraction of the SOL the leader spent
+    Proportional { ratio: f64 },
+}
+
+impl CopySizing {
+    fn size(&self, leader_sol: f64) -> f64 {
+        match self {
+            Self::Fixed { size_sol } => *size_sol,
+            Self::Proportional { ratio } => leader_sol * ratio,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaderConfig {
+    pub wallet: String,
+    /// Name used in logs and stats; the wallet address when absent
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Overrides for this leader; the strategy defaults apply when absent
+    #[serde(default)]
+    pub sizing: Option<CopySizing>,
+    #[serde(default)]
+    pub max_size_sol: Option<f64>,
+    #[serde(default)]
+    pub max_delay_ms: Option<i64>,
+    #[serde(default)]
+    pub max_delay_slots: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CopyTraderParams {
+    pub leaders: Vec<LeaderConfig>,
+    pub sizing: CopySizing,
+    /// Cap on any single copied buy
+    pub max_size_sol: f64,
+    /// Copied buys that would come out smaller are skipped
+    pub min_size_sol: f64,
+    /// Leader trades smaller than this are ignored
+    pub min_leader_sol: f64,
+    /// Oldest a leader trade may be, from block time to our signal
+    pub max_delay_ms: i64,
+    /// Slots after the leader's trade our buy may still land in
+    pub max_delay_slots: u64,
+    /// Sell the same fraction the leader sells
+    pub follow_exits: bool,
+    pub confidence: f64,
+}
+
+impl Default for CopyTraderParams {
+    fn default() -> Self {
+        Self {
+            leaders: Vec::new(),
+            sizing: CopySizing::Fixed { size_sol: 0.1 },
+            max_size_sol: 1.0,
+            min_size_sol: 0.01,
+            min_leader_sol: 0.1,
+            max_delay_ms: 3_000,
+            max_delay_slots: 4,
+            follow_exits: true,
+            confidence: 0.7,
+        }
+    }
+}
+
+/// Realized results of the trades copied from one leader
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LeaderStats {
+    pub copied_buys: u64,
+    pub copied_sells: u64,
+    pub skipped: u64,
+    pub closed_trades: u64,
+    pub winning_trades: u64,
+    pub realized_pnl_sol: f64,
+}
+
+/// Our holding in a mint, owned by the leader it was copied from
+struct CopyPosition {
+    leader: String,
+    /// Raw token units and lamports spent, from our own fills
+    tokens_raw: u64,
+    cost_lamports: u64,
+    /// Tokens the leader bought while we followed, to turn their sells into fractions
+    leader_tokens: f64,
+    opened_ms: i64,
+}
+
+pub struct CopyTrader {
+    params: CopyTraderParams,
+    leaders: HashMap<String, LeaderConfig>,
+    positions: HashMap<String, CopyPosition>,
+    stats: HashMap<String, LeaderStats>,
+}
+
+/// `StrategyFactory` for kind `copy_trader`
+pub fn build(config: &StrategyConfig) -> anyhow::Result<Box<dyn Strategy>> {
+    let params: CopyTraderParams = config.parse_params()?;
+    if params.leaders.is_empty() {
+        anyhow::bail!("copy_trader needs at least one leader");
+    }
+    if params.max_size_sol <= 0.0 {
+        anyhow::bail!("copy_trader max_size_sol must be positive");
+    }
+    Ok(Box::new(CopyTrader::new(params)))
+}
+
+impl CopyTrader {
+    pub fn new(params: CopyTraderParams) -> Self {
+        let leaders = params.leaders.iter().map(|l| (l.wallet.clone(), l.clone())).collect();
+        Self {
+            params,
+            leaders,
+            positions: HashMap::new(),
+            stats: HashMap::new(),
+        }
+    }
+
+    /// Per-leader results so far, keyed by leader label
+    #[allow(dead_code)]
+    pub fn leader_stats(&self) -> &HashMap<String, LeaderStats> {
+        &self.stats
+    }
+
+    fn label(&self, wallet: &str) -> String {
+        self.leaders
+            .get(wallet)
+            .and_then(|l| l.label.clone())
+            .unwrap_or_else(|| wallet.to_string())
+    }
+
+    fn stats_mut(&mut self, wallet: &str) -> &mut LeaderStats {
+        let label = self.label(wallet);
+        self.stats.entry(label).or_default()
+    }
+
+    fn on_buy(&mut self, activity: &WalletActivity, delay_ms: i64, now_ms: i64) -> Option<StrategySignal> {
+        let leader = self.leaders.get(&activity.wallet)?;
+        let params = &self.params;
+
+        let max_delay_ms = leader.max_delay_ms.unwrap_or(params.max_delay_ms);
+        let max_size = leader.max_size_sol.unwrap_or(params.max_size_sol).min(params.max_size_sol);
+        let size = leader.sizing.unwrap_or(params.sizing).size(activity.sol_amount).min(max_size);
+        let deadline_slot = activity.slot + leader.max_delay_slots.unwrap_or(params.max_delay_slots);
+
+        let skip = if activity.sol_amount < params.min_leader_sol {
+            Some(format!("leader trade {:.4} SOL too small", activity.sol_amount))
+        } else if delay_ms > max_delay_ms {
+            Some(format!("{} ms behind the leader", delay_ms))
+        } else if size < params.min_size_sol {
+            Some(format!("copied size {:.4} SOL below minimum", size))
+        } else {
+            // One leader owns each mint so exits and PnL stay attributable
+            self.positions
+                .get(&activity.mint)
+                .filter(|p| p.leader != activity.wallet)
+                .filter(|p| p.tokens_raw > 0 || now_ms - p.opened_ms < UNFILLED_TIMEOUT_MS)
+                .map(|p| format!("already held from {}", self.label(&p.leader)))
+        };
+        if let Some(reason) = skip {
+            debug!("👥 Not copying {} buy of {}: {}", self.label(&activity.wallet), activity.mint, reason);
+            self.stats_mut(&activity.wallet).skipped += 1;
+            return None;
+        }
+
+        let price = activity.sol_amount / activity.token_amount;
+        let position = self.positions.entry(activity.mint.clone()).or_insert_with(|| CopyPosition {
+            leader: activity.wallet.clone(),
+            tokens_raw: 0,
+            cost_lamports: 0,
+            leader_tokens: 0.0,
+            opened_ms: now_ms,
+        });
+        if position.leader != activity.wallet {
+            // Stale claim whose buy never filled
+            *position = CopyPosition {
+                leader: activity.wallet.clone(),
+                tokens_raw: 0,
+                cost_lamports: 0,
+                leader_tokens: 0.0,
+                opened_ms: now_ms,
+            };
+        }
+        position.leader_tokens += activity.token_amount;
+
+        info!("👥 Copying {} buy of {}: {:.4} SOL (leader {:.4} SOL, {} ms behind)",
+              self.label(&activity.wallet), activity.mint, size, activity.sol_amount, delay_ms);
+        self.stats_mut(&activity.wallet).copied_buys += 1;
+
+        Some(StrategySignal {
+            mint: activity.mint.clone(),
+            side: TradeSide::Buy,
+            size_sol: Some(size),
+            price,
+            confidence: self.params.confidence,
+            expected_return: None,
+            target_price: None,
+            stop_price: None,
+            arbitrage: None,
+            deadline_slot: Some(deadline_slot),
+            timestamp_ms: now_ms,
+        })
+    }
+
+    fn on_sell(&mut self, activity: &WalletActivity, now_ms: i64) -> Option<StrategySignal> {
+        if !self.params.follow_exits {
+            return None;
+        }
+        let position = self.positions.get_mut(&activity.mint).filter(|p| p.leader == activity.wallet)?;
+        if position.tokens_raw == 0 {
+            // Our copy never filled; nothing to exit
+            self.positions.remove(&activity.mint);
+            return None;
+        }
+
+        // Sells of tokens bought before we followed count as a full exit
+        let fraction = if position.leader_tokens > 0.0 {
+            (activity.token_amount / position.leader_tokens).min(1.0)
+        } else {
+            1.0
+        };
+        position.leader_tokens = (position.leader_tokens - activity.token_amount).max(0.0);
+
+        let price = activity.sol_amount / activity.token_amount;
+        let held_sol = position.tokens_raw as f64 / 10f64.powi(activity.decimals as i32) * price;
+        let size_sol = (fraction < 0.99).then_some(held_sol * fraction);
+
+        info!("👥 {} sold {:.0}% of {} - following", self.label(&activity.wallet), fraction * 100.0, activity.mint);
+        self.stats_mut(&activity.wallet).copied_sells += 1;
+
+        Some(StrategySignal {
+            mint: activity.mint.clone(),
+            side: TradeSide::Sell,
+            size_sol,
+            price,
+            confidence: self.params.confidence,
+            expected_return: None,
+            target_price: None,
+            stop_price: None,
+            arbitrage: None,
+            deadline_slot: None,
+            timestamp_ms: now_ms,
+        })
+    }
+}
+
+#[async_trait]
+impl Strategy for CopyTrader {
+    async fn on_tick(&mut self, _tick: &PriceTick) -> anyhow::Result<Vec<StrategySignal>> {
+        Ok(Vec::new())
+    }
+
+    async fn on_event(&mut self, event: &MarketEvent) -> anyhow::Result<Vec<StrategySignal>> {
+        let MarketDataMessage::WalletActivity(activity) = &event.event else {
+            return Ok(Vec::new());
+        };
+        if activity.token_amount <= 0.0 {
+            return Ok(Vec::new());
+        }
+
+        // Measured on event time so replays see the same delays as live trading
+        let delay_ms = event.latency_us.unwrap_or(0) / 1000;
+        let now_ms = event.received_at_us / 1000;
+
+        let signal = match activity.side {
+            TradeSide::Buy => self.on_buy(activity, delay_ms, now_ms),
+            TradeSide::Sell => self.on_sell(activity, now_ms),
+        };
+        Ok(signal.into_iter().collect())
+    }
+
+    async fn on_fill(&mut self, report: &ExecutionReport) -> anyhow::Result<Vec<StrategySignal>> {
+        let Some(position) = self.positions.get_mut(&report.mint) else {
+            return Ok(Vec::new());
+        };
+        if !report.success {
+            return Ok(Vec::new());
+        }
+
+        match report.side {
+            TradeSide::Buy => {
+                position.tokens_raw += report.out_amount;
+                position.cost_lamports += report.in_amount;
+            }
+            // Sells also arrive from the risk manager's own exits
+            TradeSide::Sell => {
+                let sold = report.in_amount.min(position.tokens_raw);
+                let cost = if position.tokens_raw > 0 {
+                    (position.cost_lamports as u128 * sold as u128 / position.tokens_raw as u128) as u64
+                } else {
+                    0
+                };
+                position.tokens_raw -= sold;
+                position.cost_lamports -= cost;
+                let pnl = (report.out_amount as f64 - cost as f64) / LAMPORTS_PER_SOL as f64;
+
+                let leader = position.leader.clone();
+                if position.tokens_raw == 0 {
+                    self.positions.remove(&report.mint);
+                }
+
+                let label = self.label(&leader);
+                let stats = self.stats_mut(&leader);
+                stats.realized_pnl_sol += pnl;
+                stats.closed_trades += 1;
+                if pnl > 0.0 {
+                    stats.winning_trades += 1;
+                }
+                info!("👥 {} copy of {} realized {:+.6} SOL - leader total {:+.6} SOL over {} trades ({} won)",
+                      label, report.mint, pnl, stats.realized_pnl_sol, stats.closed_trades, stats.winning_trades);
+            }
+        }
+        Ok(Vec::new())
+    }
+
+    fn params(&self) -> Value {
+        serde_json::to_value(&self.params).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALPHA: &str = "AlphaWallet";
+    const BETA: &str = "BetaWallet";
+    const MINT: &str = "MINT";
+
+    fn params() -> CopyTraderParams {
+        CopyTraderParams {
+            leaders: vec![
+                LeaderConfig {
+                    wallet: ALPHA.to_string(),
+                    label: Some("alpha".to_string()),
+                    sizing: None,
+                    max_size_sol: None,
+                    max_delay_ms: None,
+                    max_delay_slots: None,
+                },
+                LeaderConfig {
+                    wallet: BETA.to_string(),
+                    label: None,
+                    sizing: Some(CopySizing::Proportional { ratio: 0.5 }),
+                    max_size_sol: Some(0.3),
+                    max_delay_ms: None,
+                    max_delay_slots: Some(2),
+                },
+            ],
+            ..CopyTraderParams::default()
+        }
+    }
+
+    /// Leader swap of `tokens` (6 decimals) for `sol`, seen `delay_ms` after it landed
+    fn activity(wallet: &str, side: TradeSide, tokens: f64, sol: f64, delay_ms: i64, at_ms: i64) -> MarketEvent {
+        MarketEvent {
+            source: "test".to_string(),
+            received_at_us: at_ms * 1000,
+            latency_us: Some(delay_ms * 1000),
+            event: MarketDataMessage::WalletActivity(WalletActivity {
+                wallet: wallet.to_string(),
+                signature: "sig".to_string(),
+                slot: 100,
+                mint: MINT.to_string(),
+                side,
+                token_amount: tokens,
+                token_amount_raw: (tokens * 1e6) as i128,
+                decimals: 6,
+                sol_amount: sol,
+                block_time: None,
+            }),
+        }
+    }
+
+    fn fill(side: TradeSide, in_amount: u64, out_amount: u64) -> ExecutionReport {
+        ExecutionReport {
+            order_id: "order".to_string(),
+            mint: MINT.to_string(),
+            side,
+            success: true,
+            signature: None,
+            in_amount,
+            out_amount,
+            error: None,
+            latency_ms: 0,
+            simulated: false,
+        }
+    }
+
+    async fn signals(trader: &mut CopyTrader, event: MarketEvent) -> Vec<StrategySignal> {
+        trader.on_event(&event).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn attributes_realized_pnl_to_the_leader_that_opened_the_position() {
+        let mut trader = CopyTrader::new(params());
+
+        // Alpha buys 10 tokens at 0.1 SOL; we copy the fixed 0.1 SOL
+        let buy = signals(&mut trader, activity(ALPHA, TradeSide::Buy, 10.0, 1.0, 500, 1_000)).await;
+        assert_eq!(buy.len(), 1);
+        assert_eq!((buy[0].side, buy[0].size_sol, buy[0].deadline_slot), (TradeSide::Buy, Some(0.1), Some(104)));
+        trader.on_fill(&fill(TradeSide::Buy, 100_000_000, 1_000_000)).await.unwrap();
+
+        // Beta cannot take over a mint Alpha owns
+        assert!(signals(&mut trader, activity(BETA, TradeSide::Buy, 5.0, 0.5, 0, 2_000)).await.is_empty());
+        // Beta's sells are not ours to follow either
+        assert!(signals(&mut trader, activity(BETA, TradeSide::Sell, 5.0, 0.6, 0, 2_500)).await.is_empty());
+
+        // Alpha sells half at 0.12: we sell half of our one token
+        let sell = signals(&mut trader, activity(ALPHA, TradeSide::Sell, 5.0, 0.6, 0, 3_000)).await;
+        assert_eq!(sell.len(), 1);
+        assert_eq!(sell[0].side, TradeSide::Sell);
+        assert!((sell[0].size_sol.unwrap() - 0.06).abs() < 1e-9);
+        trader.on_fill(&fill(TradeSide::Sell, 500_000, 60_000_000)).await.unwrap();
+
+        // The risk manager stops out the rest at a loss; still Alpha's trade
+        trader.on_fill(&fill(TradeSide::Sell, 500_000, 40_000_000)).await.unwrap();
+
+        let stats = trader.leader_stats();
+        let alpha = &stats["alpha"];
+        assert_eq!((alpha.copied_buys, alpha.copied_sells), (1, 1));
+        assert_eq!((alpha.closed_trades, alpha.winning_trades), (2, 1));
+        assert!(alpha.realized_pnl_sol.abs() < 1e-9);
+        // Unlabelled leaders are keyed by wallet
+        let beta = &stats[BETA];
+        assert_eq!((beta.copied_buys, beta.skipped, beta.closed_trades), (0, 1, 0));
+        assert!(trader.positions.is_empty());
+
+        // Flat again, so Beta may open the mint, sized half its spend under its own cap
+        let buy = signals(&mut trader, activity(BETA, TradeSide::Buy, 10.0, 1.0, 0, 4_000)).await;
+        assert_eq!((buy[0].size_sol, buy[0].deadline_slot), (Some(0.3), Some(102)));
+        assert_eq!(trader.positions[MINT].leader, BETA);
+    }
+
+    #[tokio::test]
+    async fn skips_small_late_and_undersized_leader_trades() {
+        let mut trader = CopyTrader::new(CopyTraderParams {
+            sizing: CopySizing::Proportional { ratio: 0.05 },
+            ..params()
+        });
+        // Under min_leader_sol
+        assert!(signals(&mut trader, activity(ALPHA, TradeSide::Buy, 1.0, 0.05, 0, 1_000)).await.is_empty());
+        // Past max_delay_ms
+        assert!(signals(&mut trader, activity(ALPHA, TradeSide::Buy, 10.0, 1.0, 3_001, 1_000)).await.is_empty());
+        // 5% of 0.15 SOL is under min_size_sol
+        assert!(signals(&mut trader, activity(ALPHA, TradeSide::Buy, 10.0, 0.15, 0, 1_000)).await.is_empty());
+        assert_eq!(trader.leader_stats()["alpha"].skipped, 3);
+        assert!(trader.positions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn unfilled_copy_releases_the_mint_after_the_timeout() {
+        let mut trader = CopyTrader::new(params());
+        assert_eq!(signals(&mut trader, activity(ALPHA, TradeSide::Buy, 10.0, 1.0, 0, 1_000)).await.len(), 1);
+
+        // No fill yet: the claim holds within the timeout, then lapses
+        assert!(signals(&mut trader, activity(BETA, TradeSide::Buy, 10.0, 1.0, 0, 30_000)).await.is_empty());
+        let late = 1_000 + UNFILLED_TIMEOUT_MS;
+        assert_eq!(signals(&mut trader, activity(BETA, TradeSide::Buy, 10.0, 1.0, 0, late)).await.len(), 1);
+        assert_eq!(trader.positions[MINT].leader, BETA);
+
+        // Alpha's exit of a copy that never filled has nothing to sell
+        assert!(signals(&mut trader, activity(ALPHA, TradeSide::Sell, 10.0, 1.0, 0, late)).await.is_empty());
+    }
+}
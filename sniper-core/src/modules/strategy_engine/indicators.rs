@@ -0,0 +1,967 @@
+// 📈 Technical Indicators
+// Streaming SMA, EMA, RSI, MACD, Bollinger, ATR, VWAP and OBV, O(1) per update
+// Non-finite inputs are ignored so one bad print cannot poison a running sum
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+use crate::modules::data_ingestor::candles::Candle;
+
+/// Simple moving average over the last `period` values
+#[derive(Debug, Clone)]
+pub struct Sma {
+    period: usize,
+    window: VecDeque<f64>,
+    sum: f64,
+}
+
+impl Sma {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: period.max(1),
+            window: VecDeque::with_capacity(period.max(1)),
+            sum: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, value: f64) -> Option<f64> {
+        if !value.is_finite() {
+            return self.value();
+        }
+        self.window.push_back(value);
+        self.sum += value;
+        if self.window.len() > self.period {
+            self.sum -= self.window.pop_front().unwrap_or_default();
+        }
+        self.value()
+    }
+
+    pub fn value(&self) -> Option<f64> {
+        (self.window.len() == self.period).then(|| self.sum / self.period as f64)
+    }
+}
+
+/// Exponential moving average, seeded with the SMA of the first `period` values
+#[derive(Debug, Clone)]
+pub struct Ema {
+    alpha: f64,
+    seed: Sma,
+    value: Option<f64>,
+}
+
+impl Ema {
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        Self {
+            alpha: 2.0 / (period as f64 + 1.0),
+            seed: Sma::new(period),
+            value: None,
+        }
+    }
+
+    pub fn update(&mut self, value: f64) -> Option<f64> {
+        if !value.is_finite() {
+            return self.value;
+        }
+        self.value = match self.value {
+            Some(previous) => Some(previous + self.alpha * (value - previous)),
+            None => self.seed.update(value),
+        };
+        self.value
+    }
+
+    pub fn value(&self) -> Option<f64> {
+        self.value
+    }
+}
+
+/// Wilder's smoothing: seeded with a plain average, then `(prev * (n - 1) + x) / n`
+#[derive(Debug, Clone)]
+struct WilderAverage {
+    period: usize,
+    seed: Sma,
+    value: Option<f64>,
+}
+
+impl WilderAverage {
+    fn new(period: usize) -> Self {
+        Self {
+            period: period.max(1),
+            seed: Sma::new(period),
+            value: None,
+        }
+    }
+
+    fn update(&mut self, value: f64) -> Option<f64> {
+        let n = self.period as f64;
+        self.value = match self.value {
+            Some(previous) => Some((previous * (n - 1.0) + value) / n),
+            None => self.seed.update(value),
+        };
+        self.value
+    }
+}
+
+/// Relative Strength Index with Wilder smoothing, 0..100
+#[derive(Debug, Clone)]
+pub struct Rsi {
+    previous: Option<f64>,
+    gains: WilderAverage,
+    losses: WilderAverage,
+    value: Option<f64>,
+}
+
+impl Rsi {
+    pub fn new(period: usize) -> Self {
+        Self {
+            previous: None,
+            gains: WilderAverage::new(period),
+            losses: WilderAverage::new(period),
+            value: None,
+        }
+    }
+
+    pub fn update(&mut self, close: f64) -> Option<f64> {
+        if !close.is_finite() {
+            return self.value;
+        }
+        let previous = self.previous.replace(close)?;
+        let change = close - previous;
+        let gain = self.gains.update(change.max(0.0));
+        let loss = self.losses.update((-change).max(0.0));
+
+        self.value = gain.zip(loss).map(|(gain, loss)| {
+            if loss > 0.0 {
+                100.0 - 100.0 / (1.0 + gain / loss)
+            } else {
+                100.0
+            }
+        });
+        self.value
+    }
+
+    pub fn value(&self) -> Option<f64> {
+        self.value
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct MacdValue {
+    pub macd: f64,
+    pub signal: f64,
+    pub histogram: f64,
+}
+
+/// Moving Average Convergence Divergence
+#[derive(Debug, Clone)]
+pub struct Macd {
+    fast: Ema,
+    slow: Ema,
+    signal: Ema,
+    value: Option<MacdValue>,
+}
+
+impl Macd {
+    pub fn new(fast: usize, slow: usize, signal: usize) -> Self {
+        Self {
+            fast: Ema::new(fast),
+            slow: Ema::new(slow),
+            signal: Ema::new(signal),
+            value: None,
+        }
+    }
+
+    pub fn update(&mut self, close: f64) -> Option<MacdValue> {
+        if !close.is_finite() {
+            return self.value;
+        }
+        let fast = self.fast.update(close);
+        let slow = self.slow.update(close);
+        let (Some(fast), Some(slow)) = (fast, slow) else {
+            return None;
+        };
+
+        let macd = fast - slow;
+        self.value = self.signal.update(macd).map(|signal| MacdValue {
+            macd,
+            signal,
+            histogram: macd - signal,
+        });
+        self.value
+    }
+
+    pub fn value(&self) -> Option<MacdValue> {
+        self.value
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct BollingerValue {
+    pub middle: f64,
+    pub upper: f64,
+    pub lower: f64,
+    /// Band width relative to the middle band
+    pub bandwidth: f64,
+}
+
+/// Bollinger Bands: SMA ± `k` population standard deviations
+#[derive(Debug, Clone)]
+pub struct Bollinger {
+    period: usize,
+    k: f64,
+    window: VecDeque<f64>,
+    sum: f64,
+    sum_sq: f64,
+}
+
+impl Bollinger {
+    pub fn new(period: usize, k: f64) -> Self {
+        Self {
+            period: period.max(1),
+            k,
+            window: VecDeque::with_capacity(period.max(1)),
+            sum: 0.0,
+            sum_sq: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, close: f64) -> Option<BollingerValue> {
+        if !close.is_finite() {
+            return self.value();
+        }
+        self.window.push_back(close);
+        self.sum += close;
+        self.sum_sq += close * close;
+        if self.window.len() > self.period {
+            let old = self.window.pop_front().unwrap_or_default();
+            self.sum -= old;
+            self.sum_sq -= old * old;
+        }
+        self.value()
+    }
+
+    pub fn value(&self) -> Option<BollingerValue> {
+        if self.window.len() < self.period {
+            return None;
+        }
+        let n = self.period as f64;
+        let middle = self.sum / n;
+        let deviation = (self.sum_sq / n - middle * middle).max(0.0).sqrt();
+        Some(BollingerValue {
+            middle,
+            upper: middle + self.k * deviation,
+            lower: middle - self.k * deviation,
+            bandwidth: if middle != 0.0 { 2.0 * self.k * deviation / middle } else { 0.0 },
+        })
+    }
+}
+
+/// Average True Range with Wilder smoothing
+#[derive(Debug, Clone)]
+pub struct Atr {
+    previous_close: Option<f64>,
+    average: WilderAverage,
+    value: Option<f64>,
+}
+
+impl Atr {
+    pub fn new(period: usize) -> Self {
+        Self {
+            previous_close: None,
+            average: WilderAverage::new(period),
+            value: None,
+        }
+    }
+
+    pub fn update(&mut self, candle: &Candle) -> Option<f64> {
+        if !(candle.high.is_finite() && candle.low.is_finite() && candle.close.is_finite()) {
+            return self.value;
+        }
+        let range = candle.high - candle.low;
+        let true_range = match self.previous_close.replace(candle.close) {
+            Some(previous) => range.max((candle.high - previous).abs()).max((candle.low - previous).abs()),
+            None => range,
+        };
+        self.value = self.average.update(true_range);
+        self.value
+    }
+
+    pub fn value(&self) -> Option<f64> {
+        self.value
+    }
+}
+
+/// Volume-weighted average price, reset at the start of every session
+#[derive(Debug, Clone)]
+pub struct Vwap {
+    session_ms: i64,
+    session: Option<i64>,
+    price_volume: f64,
+    volume: f64,
+}
+
+impl Vwap {
+    pub fn new(session_ms: i64) -> Self {
+        Self {
+            session_ms: session_ms.max(1),
+            session: None,
+            price_volume: 0.0,
+            volume: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, candle: &Candle) -> Option<f64> {
+        if !(candle.typical_price().is_finite() && candle.volume.is_finite()) {
+            return self.value();
+        }
+        let session = candle.start_ms.div_euclid(self.session_ms);
+        if self.session != Some(session) {
+            self.session = Some(session);
+            self.price_volume = 0.0;
+            self.volume = 0.0;
+        }
+        self.price_volume += candle.typical_price() * candle.volume;
+        self.volume += candle.volume;
+        self.value()
+    }
+
+    pub fn value(&self) -> Option<f64> {
+        (self.volume > 0.0).then(|| self.price_volume / self.volume)
+    }
+}
+
+/// On-Balance Volume
+#[derive(Debug, Clone, Default)]
+pub struct Obv {
+    previous_close: Option<f64>,
+    value: f64,
+}
+
+impl Obv {
+    pub fn update(&mut self, candle: &Candle) -> f64 {
+        if !(candle.close.is_finite() && candle.volume.is_finite()) {
+            return self.value;
+        }
+        if let Some(previous) = self.previous_close {
+            if candle.close > previous {
+                self.value += candle.volume;
+            } else if candle.close < previous {
+                self.value -= candle.volume;
+            }
+        }
+        self.previous_close = Some(candle.close);
+        self.value
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+}
+
+/// Periods for a full indicator set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IndicatorConfig {
+    pub sma_period: usize,
+    pub ema_period: usize,
+    pub rsi_period: usize,
+    pub macd_fast: usize,
+    pub macd_slow: usize,
+    pub macd_signal: usize,
+    pub bollinger_period: usize,
+    pub bollinger_k: f64,
+    pub atr_period: usize,
+    pub vwap_session_ms: i64,
+}
+
+impl Default for IndicatorConfig {
+    fn default() -> Self {
+        Self {
+            sma_period: 20,
+            ema_period: 20,
+            rsi_period: 14,
+            macd_fast: 12,
+            macd_slow: 26,
+            macd_signal: 9,
+            bollinger_period: 20,
+            bollinger_k: 2.0,
+            atr_period: 14,
+            vwap_session_ms: 86_400_000,
+        }
+    }
+}
+
+/// Latest values of one mint's indicators; `None` until warmed up
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IndicatorSnapshot {
+    pub close: Option<f64>,
+    pub sma: Option<f64>,
+    pub ema: Option<f64>,
+    pub rsi: Option<f64>,
+    pub macd: Option<MacdValue>,
+    pub bollinger: Option<BollingerValue>,
+    pub atr: Option<f64>,
+    pub vwap: Option<f64>,
+    pub obv: f64,
+}
+
+/// Every indicator for one mint, fed closed candles
+#[derive(Debug, Clone)]
+pub struct IndicatorSet {
+    sma: Sma,
+    ema: Ema,
+    rsi: Rsi,
+    macd: Macd,
+    bollinger: Bollinger,
+    atr: Atr,
+    vwap: Vwap,
+    obv: Obv,
+    close: Option<f64>,
+}
+
+impl IndicatorSet {
+    pub fn new(config: &IndicatorConfig) -> Self {
+        Self {
+            sma: Sma::new(config.sma_period),
+            ema: Ema::new(config.ema_period),
+            rsi: Rsi::new(config.rsi_period),
+            macd: Macd::new(config.macd_fast, config.macd_slow, config.macd_signal),
+            bollinger: Bollinger::new(config.bollinger_period, config.bollinger_k),
+            atr: Atr::new(config.atr_period),
+            vwap: Vwap::new(config.vwap_session_ms),
+            obv: Obv::default(),
+            close: None,
+        }
+    }
+
+    pub fn update(&mut self, candle: &Candle) {
+        if candle.close.is_finite() {
+            self.close = Some(candle.close);
+        }
+        self.sma.update(candle.close);
+        self.ema.update(candle.close);
+        self.rsi.update(candle.close);
+        self.macd.update(candle.close);
+        self.bollinger.update(candle.close);
+        self.atr.update(candle);
+        self.vwap.update(candle);
+        self.obv.update(candle);
+    }
+
+    pub fn snapshot(&self) -> IndicatorSnapshot {
+        IndicatorSnapshot {
+            close: self.close,
+            sma: self.sma.value(),
+            ema: self.ema.value(),
+            rsi: self.rsi.value(),
+            macd: self.macd.value(),
+            bollinger: self.bollinger.value(),
+            atr: self.atr.value(),
+            vwap: self.vwap.value(),
+            obv: self.obv.value(),
+        }
+    }
+}
+
+/// Indicator sets shared between the candle builder and the HTTP API
+pub type SharedIndicators = Arc<RwLock<IndicatorEngine>>;
+
+/// Live indicators per mint and candle interval, fed every candle the builder closes
+#[derive(Debug, Clone)]
+pub struct IndicatorEngine {
+    config: IndicatorConfig,
+    sets: HashMap<(String, i64), IndicatorSet>,
+}
+
+impl IndicatorEngine {
+    pub fn new(config: IndicatorConfig) -> Self {
+        Self {
+            config,
+            sets: HashMap::new(),
+        }
+    }
+
+    pub fn shared(config: IndicatorConfig) -> SharedIndicators {
+        Arc::new(RwLock::new(Self::new(config)))
+    }
+
+    /// Fold a closed candle into its mint's set for that interval
+    pub fn update(&mut self, candle: &Candle) {
+        self.sets
+            .entry((candle.mint.clone(), candle.interval_ms))
+            .or_insert_with(|| IndicatorSet::new(&self.config))
+            .update(candle);
+    }
+
+    /// Current values for `mint` at `interval_ms`; `None` before its first closed candle
+    pub fn snapshot(&self, mint: &str, interval_ms: i64) -> Option<IndicatorSnapshot> {
+        self.sets.get(&(mint.to_string(), interval_ms)).map(IndicatorSet::snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wilder's RSI worked example closes
+    const CLOSES: [f64; 33] = [
+        44.34, 44.09, 44.15, 43.61, 44.33, 44.83, 45.10, 45.42, 45.84, 46.08, 45.89, 46.03, 45.61,
+        46.28, 46.28, 46.00, 46.03, 46.41, 46.22, 45.64, 46.21, 46.25, 45.71, 46.45, 45.78, 45.35,
+        44.03, 44.18, 44.22, 44.57, 43.42, 42.66, 43.13,
+    ];
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "expected {} got {}",
+            expected,
+            actual
+        );
+    }
+
+    fn candle(start_ms: i64, high: f64, low: f64, close: f64, volume: f64) -> Candle {
+        Candle {
+            mint: "MINT".to_string(),
+            start_ms,
+            interval_ms: 60_000,
+            open: close,
+            high,
+            low,
+            close,
+            volume,
+            trades: 1,
+        }
+    }
+
+    #[test]
+    fn sma_averages_the_last_period_values() {
+        let mut sma = Sma::new(3);
+        assert_eq!(sma.update(1.0), None);
+        assert_eq!(sma.update(2.0), None);
+        assert_close(sma.update(3.0).unwrap(), 2.0, 1e-12);
+        assert_close(sma.update(7.0).unwrap(), 4.0, 1e-12);
+    }
+
+    #[test]
+    fn ema_matches_reference_series() {
+        let mut ema = Ema::new(10);
+        let values: Vec<Option<f64>> = CLOSES.iter().map(|c| ema.update(*c)).collect();
+
+        assert!(values[..9].iter().all(Option::is_none));
+        // Seeded with the SMA of the first ten closes
+        assert_close(values[9].unwrap(), 44.779, 1e-9);
+        assert_close(values[10].unwrap(), 44.981, 1e-9);
+        assert_close(values[15].unwrap(), 45.665725, 1e-6);
+        assert_close(values[32].unwrap(), 44.119299, 1e-6);
+    }
+
+    #[test]
+    fn rsi_matches_wilder_reference() {
+        let expected = [
+            70.46, 66.25, 66.48, 69.35, 66.29, 57.92, 62.88, 63.21, 56.01, 62.34, 54.67, 50.39, 40.02,
+            41.49, 41.90, 45.50, 37.32, 33.09, 37.79,
+        ];
+        let mut rsi = Rsi::new(14);
+        let values: Vec<Option<f64>> = CLOSES.iter().map(|c| rsi.update(*c)).collect();
+
+        // Fourteen changes need fifteen closes
+        assert!(values[..14].iter().all(Option::is_none));
+        for (value, expected) in values[14..].iter().zip(expected) {
+            assert_close(value.unwrap(), expected, 0.005);
+        }
+    }
+
+    #[test]
+    fn rsi_is_100_without_losses() {
+        let mut rsi = Rsi::new(3);
+        let last = (1..=5).map(|i| rsi.update(i as f64)).last().flatten();
+        assert_eq!(last, Some(100.0));
+    }
+
+    #[test]
+    fn macd_matches_reference_series() {
+        let mut macd = Macd::new(3, 6, 4);
+        let values: Vec<Option<MacdValue>> = CLOSES.iter().map(|c| macd.update(*c)).collect();
+
+        // Slow EMA warms up after 6 closes, the signal EMA after 4 more MACD values
+        assert!(values[..8].iter().all(Option::is_none));
+        let first = values[8].unwrap();
+        assert_close(first.macd, 0.413757, 1e-6);
+        assert_close(first.signal, 0.332840, 1e-6);
+        assert_close(first.histogram, first.macd - first.signal, 1e-12);
+
+        let later = values[12].unwrap();
+        assert_close(later.macd, 0.128985, 1e-6);
+        assert_close(later.signal, 0.245343, 1e-6);
+    }
+
+    #[test]
+    fn bollinger_uses_population_deviation() {
+        let mut bollinger = Bollinger::new(20, 2.0);
+        let values: Vec<Option<BollingerValue>> = CLOSES.iter().map(|c| bollinger.update(*c)).collect();
+
+        assert!(values[..19].iter().all(Option::is_none));
+        let first = values[19].unwrap();
+        assert_close(first.middle, 45.409, 1e-9);
+        assert_close(first.upper, 47.115328, 1e-6);
+        assert_close(first.lower, 43.702672, 1e-6);
+        assert_close(first.bandwidth, (first.upper - first.lower) / first.middle, 1e-12);
+
+        // The rolling sums drop old closes exactly
+        let last = values[32].unwrap();
+        assert_close(last.middle, 45.241, 1e-9);
+        assert_close(last.upper, 47.620150, 1e-6);
+        assert_close(last.lower, 42.861850, 1e-6);
+    }
+
+    #[test]
+    fn atr_uses_true_range_with_wilder_smoothing() {
+        let bars = [
+            (10.0, 9.5, 9.8),
+            (10.6, 9.9, 10.5),
+            (10.9, 10.2, 10.3),
+            (10.4, 9.8, 10.1),
+            // Gap above the previous close widens the true range
+            (11.2, 10.3, 11.0),
+            (11.5, 10.8, 11.1),
+            (11.1, 10.4, 10.6),
+        ];
+        let expected = [None, None, Some(0.666667), Some(0.644444), Some(0.796296), Some(0.764198), Some(0.742798)];
+
+        let mut atr = Atr::new(3);
+        for (i, ((high, low, close), expected)) in bars.iter().zip(expected).enumerate() {
+            let value = atr.update(&candle(i as i64 * 60_000, *high, *low, *close, 1.0));
+            match expected {
+                Some(expected) => assert_close(value.unwrap(), expected, 1e-6),
+                None => assert_eq!(value, None),
+            }
+        }
+    }
+
+    #[test]
+    fn vwap_weights_typical_price_and_resets_each_session() {
+        let mut vwap = Vwap::new(3_600_000);
+        assert_eq!(vwap.value(), None);
+
+        // Typical prices 10 and 13
+        vwap.update(&candle(0, 11.0, 9.0, 10.0, 100.0));
+        let value = vwap.update(&candle(60_000, 14.0, 12.0, 13.0, 300.0)).unwrap();
+        assert_close(value, (10.0 * 100.0 + 13.0 * 300.0) / 400.0, 1e-12);
+
+        // A zero-volume bar leaves it unchanged
+        assert_close(vwap.update(&candle(120_000, 20.0, 20.0, 20.0, 0.0)).unwrap(), value, 1e-12);
+
+        let next_session = vwap.update(&candle(3_600_000, 6.0, 4.0, 5.0, 50.0)).unwrap();
+        assert_close(next_session, 5.0, 1e-12);
+    }
+
+    #[test]
+    fn obv_adds_up_volume_and_subtracts_down_volume() {
+        let mut obv = Obv::default();
+        assert_eq!(obv.update(&candle(0, 1.0, 1.0, 1.0, 10.0)), 0.0);
+        assert_eq!(obv.update(&candle(1, 2.0, 2.0, 2.0, 5.0)), 5.0);
+        assert_eq!(obv.update(&candle(2, 2.0, 2.0, 2.0, 7.0)), 5.0);
+        assert_eq!(obv.update(&candle(3, 1.5, 1.5, 1.5, 3.0)), 2.0);
+    }
+
+    #[test]
+    fn empty_input_yields_no_values() {
+        let snapshot = IndicatorSet::new(&IndicatorConfig::default()).snapshot();
+        assert!(snapshot.close.is_none());
+        assert!(snapshot.sma.is_none() && snapshot.ema.is_none() && snapshot.rsi.is_none());
+        assert!(snapshot.macd.is_none() && snapshot.bollinger.is_none());
+        assert!(snapshot.atr.is_none() && snapshot.vwap.is_none());
+        assert_eq!(snapshot.obv, 0.0);
+    }
+
+    #[test]
+    fn warm_up_lengths_follow_the_periods() {
+        let config = IndicatorConfig {
+            sma_period: 5,
+            ema_period: 4,
+            rsi_period: 3,
+            macd_fast: 2,
+            macd_slow: 4,
+            macd_signal: 3,
+            bollinger_period: 6,
+            atr_period: 2,
+            ..IndicatorConfig::default()
+        };
+        let mut set = IndicatorSet::new(&config);
+        let mut warm = Vec::new();
+        for (i, close) in CLOSES.iter().take(8).enumerate() {
+            set.update(&candle(i as i64 * 60_000, close + 0.1, close - 0.1, *close, 1.0));
+            let snapshot = set.snapshot();
+            warm.push([
+                snapshot.sma.is_some(),
+                snapshot.ema.is_some(),
+                snapshot.rsi.is_some(),
+                snapshot.macd.is_some(),
+                snapshot.bollinger.is_some(),
+                snapshot.atr.is_some(),
+            ]);
+        }
+
+        // First candle (1-based) at which each indicator reports
+        let first_ready: Vec<usize> = (0..6)
+            .map(|k| warm.iter().position(|ready| ready[k]).unwrap() + 1)
+            .collect();
+        assert_eq!(first_ready, vec![5, 4, 4, 6, 6, 2]);
+        // Once warm, an indicator stays warm
+        for k in 0..6 {
+            assert!(warm[first_ready[k] - 1..].iter().all(|ready| ready[k]));
+        }
+    }
+
+    #[test]
+    fn non_finite_inputs_are_ignored() {
+        let mut clean = IndicatorSet::new(&IndicatorConfig::default());
+        let mut noisy = IndicatorSet::new(&IndicatorConfig::default());
+        for (i, close) in CLOSES.iter().enumerate() {
+            let bar = candle(i as i64 * 60_000, close + 0.2, close - 0.2, *close, 10.0);
+            clean.update(&bar);
+            noisy.update(&bar);
+            if i % 5 == 0 {
+                noisy.update(&candle(i as i64 * 60_000, f64::NAN, f64::NAN, f64::NAN, 10.0));
+                noisy.update(&candle(i as i64 * 60_000, f64::INFINITY, 0.0, f64::INFINITY, f64::NAN));
+            }
+        }
+
+        let (clean, noisy) = (clean.snapshot(), noisy.snapshot());
+        assert_eq!(noisy.close, clean.close);
+        assert_eq!(noisy.sma, clean.sma);
+        assert_eq!(noisy.ema, clean.ema);
+        assert_eq!(noisy.rsi, clean.rsi);
+        assert_eq!(noisy.macd.map(|m| m.macd), clean.macd.map(|m| m.macd));
+        assert_eq!(noisy.bollinger.map(|b| b.upper), clean.bollinger.map(|b| b.upper));
+        assert_eq!(noisy.atr, clean.atr);
+        assert_eq!(noisy.vwap, clean.vwap);
+        assert_eq!(noisy.obv, clean.obv);
+        assert!(noisy.rsi.unwrap().is_finite());
+    }
+
+    /// Seeded random walk of candles with random volume and occasional multi-bar gaps
+    fn random_candles(seed: u64, count: usize) -> Vec<Candle> {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut close: f64 = rng.gen_range(0.5..100.0);
+        let mut start_ms = 0;
+        (0..count)
+            .map(|_| {
+                start_ms += 60_000 * rng.gen_range(1..4);
+                close *= 1.0 + rng.gen_range(-0.05..0.05);
+                let high = close * (1.0 + rng.gen_range(0.0..0.03));
+                let low = close * (1.0 - rng.gen_range(0.0..0.03));
+                let volume = if rng.gen_bool(0.1) { 0.0 } else { rng.gen_range(0.0..1_000.0) };
+                candle(start_ms, high, low, close, volume)
+            })
+            .collect()
+    }
+
+    fn mean(values: &[f64]) -> f64 {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+
+    /// Plain mean of every full window
+    fn sma_reference(values: &[f64], period: usize) -> Vec<Option<f64>> {
+        (0..values.len())
+            .map(|i| (i + 1 >= period).then(|| mean(&values[i + 1 - period..=i])))
+            .collect()
+    }
+
+    /// Recursive EMA seeded with the first window's mean
+    fn ema_reference(values: &[f64], period: usize) -> Vec<Option<f64>> {
+        let alpha = 2.0 / (period as f64 + 1.0);
+        let mut ema = None;
+        (0..values.len())
+            .map(|i| {
+                ema = match ema {
+                    None if i + 1 == period => Some(mean(&values[..period])),
+                    None => None,
+                    Some(previous) => Some(previous + alpha * (values[i] - previous)),
+                };
+                ema
+            })
+            .collect()
+    }
+
+    /// Wilder smoothing over a whole series, seeded with the first window's mean
+    fn wilder_reference(values: &[f64], period: usize) -> Vec<Option<f64>> {
+        let n = period as f64;
+        let mut average = None;
+        (0..values.len())
+            .map(|i| {
+                average = match average {
+                    None if i + 1 == period => Some(mean(&values[..period])),
+                    None => None,
+                    Some(previous) => Some((previous * (n - 1.0) + values[i]) / n),
+                };
+                average
+            })
+            .collect()
+    }
+
+    fn rsi_reference(closes: &[f64], period: usize) -> Vec<Option<f64>> {
+        let changes: Vec<f64> = closes.windows(2).map(|w| w[1] - w[0]).collect();
+        let gains: Vec<f64> = changes.iter().map(|c| c.max(0.0)).collect();
+        let losses: Vec<f64> = changes.iter().map(|c| (-c).max(0.0)).collect();
+        let rsi = wilder_reference(&gains, period)
+            .into_iter()
+            .zip(wilder_reference(&losses, period))
+            .map(|(gain, loss)| gain.zip(loss).map(|(g, l)| if l > 0.0 { 100.0 - 100.0 / (1.0 + g / l) } else { 100.0 }));
+        std::iter::once(None).chain(rsi).collect()
+    }
+
+    /// (middle, upper, lower) from a two-pass population deviation
+    fn bollinger_reference(closes: &[f64], period: usize, k: f64) -> Vec<Option<(f64, f64, f64)>> {
+        (0..closes.len())
+            .map(|i| {
+                (i + 1 >= period).then(|| {
+                    let window = &closes[i + 1 - period..=i];
+                    let middle = mean(window);
+                    let variance = window.iter().map(|c| (c - middle).powi(2)).sum::<f64>() / period as f64;
+                    (middle, middle + k * variance.sqrt(), middle - k * variance.sqrt())
+                })
+            })
+            .collect()
+    }
+
+    fn atr_reference(candles: &[Candle], period: usize) -> Vec<Option<f64>> {
+        let true_ranges: Vec<f64> = candles
+            .iter()
+            .enumerate()
+            .map(|(i, c)| match i {
+                0 => c.high - c.low,
+                _ => {
+                    let previous = candles[i - 1].close;
+                    (c.high - c.low).max((c.high - previous).abs()).max((c.low - previous).abs())
+                }
+            })
+            .collect();
+        wilder_reference(&true_ranges, period)
+    }
+
+    /// Volume-weighted typical price of every candle so far in the same session
+    fn vwap_reference(candles: &[Candle], session_ms: i64) -> Vec<Option<f64>> {
+        (0..candles.len())
+            .map(|i| {
+                let session = candles[i].start_ms.div_euclid(session_ms);
+                let in_session = candles[..=i].iter().filter(|c| c.start_ms.div_euclid(session_ms) == session);
+                let (pv, volume) = in_session.fold((0.0, 0.0), |(pv, v), c| (pv + c.typical_price() * c.volume, v + c.volume));
+                (volume > 0.0).then(|| pv / volume)
+            })
+            .collect()
+    }
+
+    /// Equal within a relative 1e-9, or both absent
+    fn assert_matches(streamed: Option<f64>, reference: Option<f64>, what: &str, seed: u64, i: usize) {
+        match (streamed, reference) {
+            (Some(a), Some(b)) => assert!(
+                (a - b).abs() <= 1e-9 * b.abs().max(1.0),
+                "{} seed {} bar {}: streamed {} reference {}",
+                what,
+                seed,
+                i,
+                a,
+                b
+            ),
+            (a, b) => assert_eq!(a.is_some(), b.is_some(), "{} seed {} bar {} warm-up differs", what, seed, i),
+        }
+    }
+
+    #[test]
+    fn streaming_averages_match_batch_reference() {
+        for seed in 0..50 {
+            let closes: Vec<f64> = random_candles(seed, 200).iter().map(|c| c.close).collect();
+            let period = 2 + seed as usize % 30;
+            let mut sma = Sma::new(period);
+            let mut ema = Ema::new(period);
+            let (sma_expected, ema_expected) = (sma_reference(&closes, period), ema_reference(&closes, period));
+
+            for (i, close) in closes.iter().enumerate() {
+                assert_matches(sma.update(*close), sma_expected[i], "SMA", seed, i);
+                assert_matches(ema.update(*close), ema_expected[i], "EMA", seed, i);
+            }
+        }
+    }
+
+    #[test]
+    fn streaming_rsi_matches_batch_reference() {
+        for seed in 0..50 {
+            let closes: Vec<f64> = random_candles(seed, 200).iter().map(|c| c.close).collect();
+            let period = 2 + seed as usize % 20;
+            let mut rsi = Rsi::new(period);
+            let expected = rsi_reference(&closes, period);
+
+            for (i, close) in closes.iter().enumerate() {
+                assert_matches(rsi.update(*close), expected[i], "RSI", seed, i);
+            }
+        }
+    }
+
+    #[test]
+    fn streaming_bollinger_matches_batch_reference() {
+        for seed in 0..50 {
+            let closes: Vec<f64> = random_candles(seed, 300).iter().map(|c| c.close).collect();
+            let period = 2 + seed as usize % 30;
+            let mut bollinger = Bollinger::new(period, 2.0);
+            let expected = bollinger_reference(&closes, period, 2.0);
+
+            for (i, close) in closes.iter().enumerate() {
+                let value = bollinger.update(*close);
+                assert_matches(value.map(|b| b.middle), expected[i].map(|e| e.0), "Bollinger middle", seed, i);
+                // The running sum of squares loses a little precision against the two-pass deviation
+                if let (Some(value), Some((_, upper, lower))) = (value, expected[i]) {
+                    assert_close(value.upper, upper, 1e-6 * upper.abs());
+                    assert_close(value.lower, lower, 1e-6 * upper.abs());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn streaming_atr_and_vwap_match_batch_reference() {
+        for seed in 0..50 {
+            let candles = random_candles(seed, 200);
+            let period = 2 + seed as usize % 20;
+            // Sessions of 10 to 60 minutes so several reset within the series
+            let session_ms = 600_000 * (1 + seed as i64 % 6);
+            let mut atr = Atr::new(period);
+            let mut vwap = Vwap::new(session_ms);
+            let (atr_expected, vwap_expected) = (atr_reference(&candles, period), vwap_reference(&candles, session_ms));
+
+            for (i, candle) in candles.iter().enumerate() {
+                assert_matches(atr.update(candle), atr_expected[i], "ATR", seed, i);
+                assert_matches(vwap.update(candle), vwap_expected[i], "VWAP", seed, i);
+            }
+        }
+    }
+
+    #[test]
+    fn engine_keeps_one_set_per_mint_and_interval() {
+        let mut engine = IndicatorEngine::new(IndicatorConfig {
+            sma_period: 2,
+            ..IndicatorConfig::default()
+        });
+        let mut other = candle(0, 5.0, 5.0, 5.0, 1.0);
+        other.mint = "OTHER".to_string();
+        let mut hourly = candle(0, 9.0, 9.0, 9.0, 1.0);
+        hourly.interval_ms = 3_600_000;
+
+        engine.update(&candle(0, 1.0, 1.0, 1.0, 1.0));
+        engine.update(&other);
+        engine.update(&hourly);
+        engine.update(&candle(60_000, 3.0, 3.0, 3.0, 1.0));
+
+        let minute = engine.snapshot("MINT", 60_000).unwrap();
+        assert_eq!(minute.close, Some(3.0));
+        assert_eq!(minute.sma, Some(2.0));
+        assert_eq!(engine.snapshot("MINT", 3_600_000).unwrap().close, Some(9.0));
+        assert_eq!(engine.snapshot("OTHER", 60_000).unwrap().sma, None);
+        assert!(engine.snapshot("MINT", 300_000).is_none());
+        assert!(engine.snapshot("UNKNOWN", 60_000).is_none());
+    }
+}
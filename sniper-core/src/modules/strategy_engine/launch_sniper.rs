@@ -0,0 +1,385 @@
+// 🎯 Launch Sniper Strategy
+// Buy new pools and pump.fun tokens that pass the entry filters
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::{debug, info};
+
+use super::registry::StrategyConfig;
+use super::strategy::{Strategy, StrategySignal};
+use crate::modules::data_ingestor::wallet_tracker::WSOL_MINT;
+use crate::modules::data_ingestor::{
+    LaunchEvent, LaunchKind, MarketDataMessage, MarketEvent, PriceTick, TokenProfiler, TradeSide,
+};
+
+/// Metadata JSON keys that count as a social link
+const SOCIAL_KEYS: [&str; 4] = ["twitter", "telegram", "website", "discord"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LaunchSniperParams {
+    pub kinds: Vec<LaunchKind>,
+    /// Accepted range of quote liquidity at launch, UI units
+    pub min_liquidity: f64,
+    pub max_liquidity: f64,
+    pub quote_mints: Vec<String>,
+    pub creator_blacklist: Vec<String>,
+    /// Require a name, symbol and metadata URI
+    pub require_metadata: bool,
+    pub max_symbol_len: usize,
+    /// Require at least one social link in the metadata JSON
+    pub require_socials: bool,
+    pub metadata_timeout_ms: u64,
+    /// Slots after the launch an entry may still land in
+    pub max_slot_delay: u64,
+    pub size_sol: f64,
+    /// Exit levels relative to the launch price
+    pub take_profit_pct: f64,
+    pub stop_loss_pct: f64,
+}
+
+impl Default for LaunchSniperParams {
+    fn default() -> Self {
+        Self {
+            kinds: vec![LaunchKind::RaydiumPool, LaunchKind::PumpFun],
+            min_liquidity: 0.0,
+            max_liquidity: 500.0,
+            quote_mints: vec![WSOL_MINT.to_string()],
+            creator_blacklist: Vec::new(),
+            require_metadata: true,
+            max_symbol_len: 10,
+            require_socials: false,
+            metadata_timeout_ms: 1_500,
+            max_slot_delay: 3,
+            size_sol: 0.05,
+            take_profit_pct: 1.0,
+            stop_loss_pct: 0.3,
+        }
+    }
+}
+
+pub struct LaunchSniper {
+    params: LaunchSniperParams,
+    profiler: TokenProfiler,
+    http: reqwest::Client,
+    /// Newest slot seen on any event; entry delay is measured against it
+    latest_slot: u64,
+}
+
+/// `StrategyFactory` for kind `launch_sniper`
+pub fn build(config: &StrategyConfig) -> anyhow::Result<Box<dyn Strategy>> {
+    let params: LaunchSniperParams = config.parse_params()?;
+    if params.size_sol <= 0.0 {
+        anyhow::bail!("launch_sniper size_sol must be positive");
+    }
+    if !(0.0..1.0).contains(&params.stop_loss_pct) {
+        anyhow::bail!("launch_sniper stop_loss_pct must be in [0, 1)");
+    }
+    let rpc_url = std::env::var("QUICKNODE_RPC_URL").unwrap_or_else(|_| "https://api.devnet.solana.com".to_string());
+    Ok(Box::new(LaunchSniper::new(params, TokenProfiler::new(rpc_url))))
+}
+
+impl LaunchSniper {
+    pub fn new(params: LaunchSniperParams, profiler: TokenProfiler) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_millis(params.metadata_timeout_ms))
+            .build()
+            .unwrap_or_default();
+        Self {
+            params,
+            profiler,
+            http,
+            latest_slot: 0,
+        }
+    }
+
+    /// Checks that need no network access; returns the first failed one
+    fn static_filters(&self, launch: &LaunchEvent) -> Result<(), String> {
+        let params = &self.params;
+        if !params.kinds.contains(&launch.kind) {
+            return Err(format!("{:?} launches disabled", launch.kind));
+        }
+        if !params.quote_mints.contains(&launch.quote_mint) {
+            return Err(format!("quote mint {} not allowed", launch.quote_mint));
+        }
+        if params.creator_blacklist.contains(&launch.creator) {
+            return Err(format!("creator {} blacklisted", launch.creator));
+        }
+        if launch.quote_liquidity < params.min_liquidity || launch.quote_liquidity > params.max_liquidity {
+            return Err(format!("liquidity {:.4} outside range", launch.quote_liquidity));
+        }
+        let delay = self.latest_slot.saturating_sub(launch.slot);
+        if delay > params.max_slot_delay {
+            return Err(format!("seen {} slots after launch", delay));
+        }
+        Ok(())
+    }
+
+    /// Name, symbol and URI from the launch itself, or from Metaplex metadata
+    async fn metadata(&self, launch: &LaunchEvent) -> Option<(String, String, String)> {
+        if let (Some(name), Some(symbol), Some(uri)) = (&launch.name, &launch.symbol, &launch.uri) {
+            return Some((name.clone(), symbol.clone(), uri.clone()));
+        }
+        let mint = Pubkey::from_str(&launch.mint).ok()?;
+        match self.profiler.fetch_metadata(&mint).await {
+            Ok(metadata) => metadata.map(|m| (m.name, m.symbol, m.uri)),
+            Err(e) => {
+                debug!("🎯 Metadata lookup failed for {}: {}", launch.mint, e);
+                None
+            }
+        }
+    }
+
+    async fn metadata_filters(&self, launch: &LaunchEvent) -> Result<(), String> {
+        let params = &self.params;
+        if !params.require_metadata && !params.require_socials {
+            return Ok(());
+        }
+
+        let (name, symbol, uri) = self.metadata(launch).await.ok_or("no metadata")?;
+        let (name, symbol, uri) = (name.trim(), symbol.trim(), uri.trim());
+        if params.require_metadata {
+            if name.is_empty() || symbol.is_empty() || uri.is_empty() {
+                return Err("incomplete metadata".to_string());
+            }
+            if symbol.chars().count() > params.max_symbol_len {
+                return Err(format!("symbol {} too long", symbol));
+            }
+        }
+
+        if params.require_socials && !self.has_socials(uri).await {
+            return Err("no social links".to_string());
+        }
+        Ok(())
+    }
+
+    /// Whether the off-chain metadata JSON links any social account
+    async fn has_socials(&self, uri: &str) -> bool {
+        let json: Value = match self.http.get(uri).send().await {
+            Ok(response) => response.json().await.unwrap_or_default(),
+            Err(e) => {
+                debug!("🎯 Metadata JSON unavailable at {}: {}", uri, e);
+                return false;
+            }
+        };
+
+        let linked = |object: &Value| {
+            SOCIAL_KEYS
+                .iter()
+                .any(|key| object[*key].as_str().is_some_and(|link| !link.trim().is_empty()))
+        };
+        linked(&json) || linked(&json["extensions"]) || linked(&json["properties"])
+    }
+
+    async fn on_launch(&mut self, launch: &LaunchEvent, timestamp_ms: i64) -> Option<StrategySignal> {
+        let checks = match self.static_filters(launch) {
+            Ok(()) => self.metadata_filters(launch).await,
+            Err(reason) => Err(reason),
+        };
+        if let Err(reason) = checks {
+            debug!("🎯 Skipping {} launch {}: {}", kind_label(launch.kind), launch.mint, reason);
+            return None;
+        }
+
+        let params = &self.params;
+        let price = launch.price.filter(|p| *p > 0.0);
+        info!("🎯 Sniping {} launch {} ({:.4} liquidity, price {:?})",
+              kind_label(launch.kind), launch.mint, launch.quote_liquidity, price);
+
+        Some(StrategySignal {
+            mint: launch.mint.clone(),
+            side: TradeSide::Buy,
+            size_sol: Some(params.size_sol),
+            price: price.unwrap_or(0.0),
+            confidence: 1.0,
+            expected_return: Some(params.take_profit_pct),
+            target_price: price.map(|p| p * (1.0 + params.take_profit_pct)),
+            stop_price: price.map(|p| p * (1.0 - params.stop_loss_pct)),
+            arbitrage: None,
+            deadline_slot: Some(launch.slot + params.max_slot_delay),
+            timestamp_ms,
+        })
+    }
+}
+
+fn kind_label(kind: LaunchKind) -> &'static str {
+    match kind {
+        LaunchKind::RaydiumPool => "Raydium",
+        LaunchKind::PumpFun => "pump.fun",
+    }
+}
+
+#[async_trait]
+impl Strategy for LaunchSniper {
+    async fn on_tick(&mut self, _tick: &PriceTick) -> anyhow::Result<Vec<StrategySignal>> {
+        Ok(Vec::new())
+    }
+
+    async fn on_event(&mut self, event: &MarketEvent) -> anyhow::Result<Vec<StrategySignal>> {
+        if let Some(slot) = event.event.slot() {
+            self.latest_slot = self.latest_slot.max(slot);
+        }
+        let MarketDataMessage::Launch(launch) = &event.event else {
+            return Ok(Vec::new());
+        };
+
+        let timestamp_ms = event.received_at_us / 1000;
+        Ok(self.on_launch(launch, timestamp_ms).await.into_iter().collect())
+    }
+
+    fn params(&self) -> Value {
+        serde_json::to_value(&self.params).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Json, Router};
+    use serde_json::json;
+
+    /// Nothing listens here, so Metaplex lookups fail fast
+    const UNREACHABLE: &str = "http://127.0.0.1:1";
+    const MINT: &str = "So11111111111111111111111111111111111111112";
+
+    fn sniper(params: LaunchSniperParams) -> LaunchSniper {
+        LaunchSniper::new(params, TokenProfiler::new(UNREACHABLE.to_string()))
+    }
+
+    fn launch() -> LaunchEvent {
+        LaunchEvent {
+            signature: "sig".to_string(),
+            slot: 100,
+            block_time: None,
+            kind: LaunchKind::PumpFun,
+            mint: MINT.to_string(),
+            quote_mint: WSOL_MINT.to_string(),
+            pool: "curve".to_string(),
+            creator: "creator".to_string(),
+            quote_liquidity: 30.0,
+            price: Some(0.000_1),
+            name: Some("Token".to_string()),
+            symbol: Some("TKN".to_string()),
+            uri: Some(format!("{UNREACHABLE}/meta.json")),
+        }
+    }
+
+    fn event(launch: LaunchEvent) -> MarketEvent {
+        MarketEvent {
+            source: "test".to_string(),
+            received_at_us: 5_000_000,
+            latency_us: None,
+            event: MarketDataMessage::Launch(launch),
+        }
+    }
+
+    /// Metadata JSON host: `/socials.json` links a Twitter account, `/bare.json` only blank links
+    async fn metadata_host() -> String {
+        let app = Router::new()
+            .route("/socials.json", get(|| async { Json(json!({ "extensions": { "twitter": "https://x.com/token" } })) }))
+            .route("/bare.json", get(|| async { Json(json!({ "twitter": "  ", "website": "" })) }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        url
+    }
+
+    #[tokio::test]
+    async fn snipes_a_launch_that_passes_every_filter() {
+        let mut sniper = sniper(LaunchSniperParams::default());
+        let signals = sniper.on_event(&event(launch())).await.unwrap();
+        assert_eq!(signals.len(), 1);
+
+        let signal = &signals[0];
+        assert_eq!(signal.side, TradeSide::Buy);
+        assert_eq!(signal.mint, MINT);
+        assert_eq!(signal.size_sol, Some(0.05));
+        assert_eq!(signal.deadline_slot, Some(103));
+        assert_eq!(signal.timestamp_ms, 5_000);
+        assert!((signal.target_price.unwrap() - 0.000_2).abs() < 1e-12);
+        assert!((signal.stop_price.unwrap() - 0.000_07).abs() < 1e-12);
+    }
+
+    #[test]
+    fn static_filters_reject_kind_quote_creator_liquidity_and_delay() {
+        let mut sniper = sniper(LaunchSniperParams {
+            kinds: vec![LaunchKind::PumpFun],
+            min_liquidity: 5.0,
+            max_liquidity: 100.0,
+            creator_blacklist: vec!["rugger".to_string()],
+            ..LaunchSniperParams::default()
+        });
+        assert_eq!(sniper.static_filters(&launch()), Ok(()));
+
+        let rejected = |sniper: &LaunchSniper, launch: LaunchEvent, reason: &str| {
+            let error = sniper.static_filters(&launch).unwrap_err();
+            assert!(error.contains(reason), "{error} should mention {reason}");
+        };
+        rejected(&sniper, LaunchEvent { kind: LaunchKind::RaydiumPool, ..launch() }, "disabled");
+        rejected(&sniper, LaunchEvent { quote_mint: "USDC".to_string(), ..launch() }, "not allowed");
+        rejected(&sniper, LaunchEvent { creator: "rugger".to_string(), ..launch() }, "blacklisted");
+        rejected(&sniper, LaunchEvent { quote_liquidity: 4.9, ..launch() }, "outside range");
+        rejected(&sniper, LaunchEvent { quote_liquidity: 100.1, ..launch() }, "outside range");
+
+        // Three slots late is still in time, four is not
+        sniper.latest_slot = 103;
+        assert_eq!(sniper.static_filters(&launch()), Ok(()));
+        sniper.latest_slot = 104;
+        rejected(&sniper, launch(), "slots after launch");
+    }
+
+    #[tokio::test]
+    async fn late_launch_is_skipped_once_newer_slots_are_seen() {
+        let mut sniper = sniper(LaunchSniperParams::default());
+        let newer = LaunchEvent {
+            mint: "other".to_string(),
+            slot: 110,
+            kind: LaunchKind::RaydiumPool,
+            quote_mint: "USDC".to_string(),
+            ..launch()
+        };
+        assert!(sniper.on_event(&event(newer)).await.unwrap().is_empty());
+        assert!(sniper.on_event(&event(launch())).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn metadata_filters_need_complete_metadata_and_a_short_symbol() {
+        let strict = sniper(LaunchSniperParams::default());
+        assert_eq!(strict.metadata_filters(&launch()).await, Ok(()));
+
+        let blank_symbol = LaunchEvent { symbol: Some("  ".to_string()), ..launch() };
+        assert_eq!(strict.metadata_filters(&blank_symbol).await.unwrap_err(), "incomplete metadata");
+        let long_symbol = LaunchEvent { symbol: Some("ELEVENCHARS".to_string()), ..launch() };
+        assert!(strict.metadata_filters(&long_symbol).await.unwrap_err().contains("too long"));
+        // Raydium pools carry no metadata, and the Metaplex lookup fails here
+        let no_metadata = LaunchEvent { name: None, symbol: None, uri: None, ..launch() };
+        assert_eq!(strict.metadata_filters(&no_metadata).await.unwrap_err(), "no metadata");
+
+        // With the checks off, none of it matters
+        let lenient = sniper(LaunchSniperParams {
+            require_metadata: false,
+            ..LaunchSniperParams::default()
+        });
+        assert_eq!(lenient.metadata_filters(&no_metadata).await, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn socials_filter_needs_a_non_blank_link() {
+        let host = metadata_host().await;
+        let sniper = sniper(LaunchSniperParams {
+            require_socials: true,
+            ..LaunchSniperParams::default()
+        });
+
+        let linked = LaunchEvent { uri: Some(format!("{host}/socials.json")), ..launch() };
+        assert_eq!(sniper.metadata_filters(&linked).await, Ok(()));
+        let blank = LaunchEvent { uri: Some(format!("{host}/bare.json")), ..launch() };
+        assert_eq!(sniper.metadata_filters(&blank).await.unwrap_err(), "no social links");
+        // An unreachable metadata host counts as no socials
+        assert_eq!(sniper.metadata_filters(&launch()).await.unwrap_err(), "no social links");
+    }
+}
@@ -0,0 +1,381 @@
+// 🔄 Mean Reversion Strategy
+// Oversold RSI at the lower Bollinger band, weighted by nearby support
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+
+use super::indicators::{Bollinger, Rsi};
+use super::registry::StrategyConfig;
+use super::strategy::{swap_trade, Strategy, StrategySignal};
+use crate::modules::data_ingestor::{Candle, CandleBuilder, MarketEvent, PriceTick, TradeSide};
+use crate::modules::executor::ExecutionReport;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MeanReversionParams {
+    pub candle_interval_ms: i64,
+    pub rsi_period: usize,
+    pub oversold: f64,
+    pub overbought: f64,
+    pub bollinger_period: usize,
+    pub bollinger_k: f64,
+    /// Candles scanned for support (lowest low) and resistance (highest high)
+    pub sr_lookback: usize,
+    /// Distance from a level, as a fraction, still counted as touching it
+    pub sr_tolerance: f64,
+    /// Stop placed this fraction below support
+    pub stop_buffer: f64,
+    pub min_confidence: f64,
+    pub max_confidence: f64,
+    /// Fixed order size; `None` leaves sizing to the risk manager
+    pub size_sol: Option<f64>,
+}
+
+impl Default for MeanReversionParams {
+    fn default() -> Self {
+        Self {
+            candle_interval_ms: 60_000,
+            rsi_period: 14,
+            oversold: 30.0,
+            overbought: 70.0,
+            bollinger_period: 20,
+            bollinger_k: 2.0,
+            sr_lookback: 50,
+            sr_tolerance: 0.01,
+            stop_buffer: 0.01,
+            min_confidence: 0.55,
+            max_confidence: 0.85,
+            size_sol: None,
+        }
+    }
+}
+
+struct MintState {
+    rsi: Rsi,
+    bollinger: Bollinger,
+    /// (low, high) of the candles before the current one
+    history: VecDeque<(f64, f64)>,
+    /// Exit level of the open trade
+    target: Option<f64>,
+}
+
+impl MintState {
+    fn support(&self) -> Option<f64> {
+        self.history.iter().map(|(low, _)| *low).reduce(f64::min)
+    }
+
+    fn resistance(&self) -> Option<f64> {
+        self.history.iter().map(|(_, high)| *high).reduce(f64::max)
+    }
+}
+
+pub struct MeanReversionStrategy {
+    params: MeanReversionParams,
+    candles: CandleBuilder,
+    mints: HashMap<String, MintState>,
+}
+
+/// `StrategyFactory` for kind `mean_reversion`
+pub fn build(config: &StrategyConfig) -> anyhow::Result<Box<dyn Strategy>> {
+    let params: MeanReversionParams = config.parse_params()?;
+    if params.oversold >= params.overbought {
+        anyhow::bail!("mean_reversion oversold must be below overbought");
+    }
+    Ok(Box::new(MeanReversionStrategy::new(params)))
+}
+
+impl MeanReversionStrategy {
+    pub fn new(params: MeanReversionParams) -> Self {
+        Self {
+            candles: CandleBuilder::new(params.candle_interval_ms),
+            params,
+            mints: HashMap::new(),
+        }
+    }
+
+    fn update(&mut self, mint: &str, price: f64, volume: f64, timestamp_ms: i64) -> Vec<StrategySignal> {
+        self.candles
+            .update(mint, price, volume, timestamp_ms)
+            .iter()
+            .filter_map(|candle| self.on_candle(candle))
+            .collect()
+    }
+
+    fn on_candle(&mut self, candle: &Candle) -> Option<StrategySignal> {
+        let params = &self.params;
+        let state = self.mints.entry(candle.mint.clone()).or_insert_with(|| MintState {
+            rsi: Rsi::new(params.rsi_period),
+            bollinger: Bollinger::new(params.bollinger_period, params.bollinger_k),
+            history: VecDeque::with_capacity(params.sr_lookback),
+            target: None,
+        });
+
+        let rsi = state.rsi.update(candle.close);
+        let bands = state.bollinger.update(candle.close);
+        let levels = state.support().zip(state.resistance());
+
+        state.history.push_back((candle.low, candle.high));
+        if state.history.len() > params.sr_lookback {
+            state.history.pop_front();
+        }
+
+        let (rsi, bands, (support, resistance)) = (rsi?, bands?, levels?);
+        let close = candle.close;
+        let timestamp_ms = candle.start_ms + candle.interval_ms;
+
+        if let Some(target) = state.target {
+            if close < target && rsi < params.overbought {
+                return None;
+            }
+            state.target = None;
+            let stretch = ((rsi - 50.0) / (100.0 - params.overbought).max(f64::EPSILON)).clamp(0.0, 1.0);
+            return Some(StrategySignal {
+                mint: candle.mint.clone(),
+                side: TradeSide::Sell,
+                size_sol: None,
+                price: close,
+                confidence: params.min_confidence + (params.max_confidence - params.min_confidence) * stretch,
+                expected_return: None,
+                target_price: None,
+                stop_price: None,
+                arbitrage: None,
+                deadline_slot: None,
+                timestamp_ms,
+            });
+        }
+
+        if rsi > params.oversold || close > bands.lower {
+            return None;
+        }
+        // Closing well under support is a breakdown, not a stretch to revert from
+        if close < support * (1.0 - params.sr_tolerance) {
+            return None;
+        }
+
+        let depth = ((params.oversold - rsi) / params.oversold.max(f64::EPSILON)).clamp(0.0, 1.0);
+        let at_support = close <= support * (1.0 + params.sr_tolerance);
+        let score = 0.5 * depth + if at_support { 0.5 } else { 0.0 };
+        let confidence = params.min_confidence + (params.max_confidence - params.min_confidence) * score;
+
+        // Revert to the band middle, capped by resistance overhead
+        let target = bands.middle.min(resistance);
+        if target <= close {
+            return None;
+        }
+        let stop = support.min(close) * (1.0 - params.stop_buffer);
+        state.target = Some(target);
+
+        Some(StrategySignal {
+            mint: candle.mint.clone(),
+            side: TradeSide::Buy,
+            size_sol: params.size_sol,
+            price: close,
+            confidence,
+            expected_return: Some((target - close) / close),
+            target_price: Some(target),
+            stop_price: Some(stop),
+            arbitrage: None,
+            deadline_slot: None,
+            timestamp_ms,
+        })
+    }
+}
+
+#[async_trait]
+impl Strategy for MeanReversionStrategy {
+    async fn on_tick(&mut self, tick: &PriceTick) -> anyhow::Result<Vec<StrategySignal>> {
+        Ok(self.update(&tick.mint, tick.price, 0.0, tick.timestamp_ms))
+    }
+
+    async fn on_event(&mut self, event: &MarketEvent) -> anyhow::Result<Vec<StrategySignal>> {
+        Ok(match swap_trade(event) {
+            Some((mint, price, volume, timestamp_ms)) => self.update(mint, price, volume, timestamp_ms),
+            None => Vec::new(),
+        })
+    }
+
+    async fn on_fill(&mut self, report: &ExecutionReport) -> anyhow::Result<Vec<StrategySignal>> {
+        // A buy that did not fill leaves nothing to exit, and after any sold fill the
+        // position is gone or being exited by the risk manager (stop, max hold, emergency)
+        let exited = match report.side {
+            TradeSide::Buy => !report.success,
+            TradeSide::Sell => report.success,
+        };
+        if exited {
+            if let Some(state) = self.mints.get_mut(&report.mint) {
+                state.target = None;
+            }
+        }
+        Ok(Vec::new())
+    }
+
+    fn params(&self) -> Value {
+        serde_json::to_value(&self.params).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINT: &str = "MINT";
+
+    fn params() -> MeanReversionParams {
+        MeanReversionParams {
+            rsi_period: 3,
+            bollinger_period: 5,
+            bollinger_k: 1.0,
+            sr_lookback: 10,
+            sr_tolerance: 0.02,
+            ..MeanReversionParams::default()
+        }
+    }
+
+    fn candle(index: usize, close: f64) -> Candle {
+        Candle {
+            mint: MINT.to_string(),
+            start_ms: index as i64 * 60_000,
+            interval_ms: 60_000,
+            open: close,
+            high: close * 1.005,
+            low: close * 0.995,
+            close,
+            volume: 1.0,
+            trades: 1,
+        }
+    }
+
+    /// Feed closes one candle each; returns (candle index, signal)
+    fn run(strategy: &mut MeanReversionStrategy, closes: &[f64]) -> Vec<(usize, StrategySignal)> {
+        closes
+            .iter()
+            .enumerate()
+            .filter_map(|(i, close)| strategy.on_candle(&candle(i, *close)).map(|signal| (i, signal)))
+            .collect()
+    }
+
+    /// Range between ~96 and ~101: a dip sets support, later pullbacks to it revert
+    const RANGE: [f64; 20] = [
+        100.0, 101.0, 100.0, 96.0, 99.0, 101.0, 100.0, 101.0, 100.0, 99.0, 97.0, 96.2, 97.0, 98.5, 100.0,
+        101.0, 100.0, 99.0, 97.0, 96.5,
+    ];
+
+    #[test]
+    fn buys_the_pullback_to_support_and_sells_the_reversion() {
+        let mut strategy = MeanReversionStrategy::new(params());
+        let signals = run(&mut strategy, &RANGE);
+
+        let emitted: Vec<(usize, TradeSide)> = signals.iter().map(|(i, s)| (*i, s.side)).collect();
+        assert_eq!(emitted, vec![(10, TradeSide::Buy), (14, TradeSide::Sell), (18, TradeSide::Buy)]);
+
+        let (_, buy) = &signals[0];
+        assert_eq!(buy.price, 97.0);
+        assert_eq!(buy.timestamp_ms, 11 * 60_000);
+        // Target is the band middle, under the resistance overhead
+        let target = buy.target_price.unwrap();
+        assert!((target - 99.4).abs() < 1e-9);
+        assert!((buy.expected_return.unwrap() - (99.4 - 97.0) / 97.0).abs() < 1e-9);
+        // Stop sits the buffer under support (the 96 dip's low)
+        assert!((buy.stop_price.unwrap() - 96.0 * 0.995 * 0.99).abs() < 1e-9);
+        // At support, plus partial oversold depth
+        assert!(buy.confidence > 0.7 && buy.confidence < params().max_confidence);
+        assert_eq!(buy.size_sol, None);
+
+        let (_, sell) = &signals[1];
+        assert_eq!(sell.price, 100.0);
+        assert!(sell.price >= target);
+        assert!(sell.confidence >= params().min_confidence && sell.confidence <= params().max_confidence);
+    }
+
+    #[test]
+    fn stays_flat_until_indicators_warm_up() {
+        let mut strategy = MeanReversionStrategy::new(MeanReversionParams {
+            bollinger_period: 12,
+            ..params()
+        });
+        // The same pullback at candle 10 comes before the 12-candle bands exist
+        let signals = run(&mut strategy, &RANGE[..11]);
+        assert!(signals.is_empty());
+    }
+
+    #[tokio::test]
+    async fn failed_entry_rearms_the_buy() {
+        let mut strategy = MeanReversionStrategy::new(params());
+        let signals = run(&mut strategy, &RANGE[..11]);
+        assert_eq!(signals.len(), 1);
+
+        let report = ExecutionReport {
+            order_id: "order".to_string(),
+            mint: MINT.to_string(),
+            side: TradeSide::Buy,
+            success: false,
+            signature: None,
+            in_amount: 0,
+            out_amount: 0,
+            error: Some("slippage".to_string()),
+            latency_ms: 0,
+            simulated: false,
+        };
+        strategy.on_fill(&report).await.unwrap();
+
+        // Without an open trade, the next oversold close at support is a fresh entry
+        let next = strategy.on_candle(&candle(11, RANGE[11])).unwrap();
+        assert_eq!(next.side, TradeSide::Buy);
+        assert_eq!(next.price, 96.2);
+    }
+
+    #[tokio::test]
+    async fn exit_by_the_risk_manager_rearms_the_buy() {
+        let mut strategy = MeanReversionStrategy::new(params());
+        let signals = run(&mut strategy, &RANGE[..11]);
+        assert_eq!(signals.len(), 1);
+
+        // Stopped out before the reversion target was reached
+        let report = ExecutionReport {
+            order_id: "stop".to_string(),
+            mint: MINT.to_string(),
+            side: TradeSide::Sell,
+            success: true,
+            signature: None,
+            in_amount: 1_000,
+            out_amount: 900_000,
+            error: None,
+            latency_ms: 0,
+            simulated: false,
+        };
+        strategy.on_fill(&report).await.unwrap();
+        assert!(strategy.mints[MINT].target.is_none());
+
+        // No sell for tokens already gone, and the next pullback is a fresh entry
+        let next = strategy.on_candle(&candle(11, RANGE[11])).unwrap();
+        assert_eq!(next.side, TradeSide::Buy);
+    }
+
+    #[test]
+    fn falling_trend_breaks_support_and_never_buys() {
+        let mut strategy = MeanReversionStrategy::new(MeanReversionParams {
+            sr_tolerance: 0.01,
+            ..params()
+        });
+        // 3% lower every candle, so each close undercuts the prior lows
+        let closes: Vec<f64> = (0..30).map(|i| 100.0 * 0.97f64.powi(i)).collect();
+        assert!(run(&mut strategy, &closes).is_empty());
+
+        // Suppressed by the breakdown rule, not by the oscillators
+        let state = &strategy.mints[MINT];
+        let close = *closes.last().unwrap();
+        assert!(state.rsi.value().unwrap() < params().oversold);
+        assert!(close < state.bollinger.value().unwrap().lower);
+        // Support seen by the last candle is the previous candle's low
+        assert!(close < closes[closes.len() - 2] * 0.995 * (1.0 - 0.01));
+    }
+
+    #[test]
+    fn rising_trend_is_never_oversold() {
+        let mut strategy = MeanReversionStrategy::new(params());
+        let closes: Vec<f64> = (0..30).map(|i| 100.0 * 1.02f64.powi(i)).collect();
+        assert!(run(&mut strategy, &closes).is_empty());
+    }
+}
@@ -0,0 +1,431 @@
+// 🚀 Momentum Strategy
+// Fast/slow EMA crossovers confirmed by trend strength in ATR units
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use tracing::debug;
+
+use super::indicators::{Atr, Ema};
+use super::registry::StrategyConfig;
+use super::strategy::{swap_trade, Strategy, StrategySignal};
+use super::volume::{VolumeAnalyzer, VolumeFilter};
+use crate::modules::data_ingestor::{Candle, CandleBuilder, MarketDataMessage, MarketEvent, PriceTick, TradeSide};
+use crate::modules::executor::ExecutionReport;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MomentumParams {
+    pub candle_interval_ms: i64,
+    pub fast_period: usize,
+    pub slow_period: usize,
+    pub atr_period: usize,
+    /// Smallest EMA spread, in ATRs, for a crossover to count
+    pub min_trend_strength: f64,
+    /// Spread in ATRs at which confidence reaches `max_confidence`
+    pub full_trend_strength: f64,
+    pub min_confidence: f64,
+    pub max_confidence: f64,
+    /// Target and stop distances from the entry, in ATRs
+    pub target_atr: f64,
+    pub stop_atr: f64,
+    /// Fixed order size; `None` leaves sizing to the risk manager
+    pub size_sol: Option<f64>,
+    /// Swap volume a crossover must be backed by; `None` disables the check
+    pub volume_filter: Option<VolumeFilter>,
+}
+
+impl Default for MomentumParams {
+    fn default() -> Self {
+        Self {
+            candle_interval_ms: 60_000,
+            fast_period: 9,
+            slow_period: 21,
+            atr_period: 14,
+            min_trend_strength: 0.5,
+            full_trend_strength: 2.0,
+            min_confidence: 0.55,
+            max_confidence: 0.9,
+            target_atr: 3.0,
+            stop_atr: 1.5,
+            size_sol: None,
+            volume_filter: None,
+        }
+    }
+}
+
+struct MintState {
+    fast: Ema,
+    slow: Ema,
+    atr: Atr,
+    previous_spread: Option<f64>,
+    previous_slow: Option<f64>,
+    /// Raw tokens bought and not yet sold, from fill reports
+    held_raw: u64,
+    /// Most held during the current position, to tell rounding dust from a partial exit
+    peak_raw: u64,
+    /// Buy signalled and not yet reported
+    entry_pending: bool,
+}
+
+/// Holdings at or below this share of the position's peak count as flat
+const DUST_SHARE: u64 = 1_000;
+
+impl MintState {
+    fn holding(&self) -> bool {
+        self.entry_pending || self.held_raw > 0
+    }
+
+    /// Track the position from a fill report for this mint
+    fn apply_fill(&mut self, report: &ExecutionReport) {
+        match (report.side, report.success) {
+            (TradeSide::Buy, success) => {
+                self.entry_pending = false;
+                if success {
+                    self.held_raw += report.out_amount;
+                    self.peak_raw = self.peak_raw.max(self.held_raw);
+                }
+            }
+            // Exits may come from the risk manager too: stops, take-profits, emergency
+            (TradeSide::Sell, true) => {
+                self.held_raw = self.held_raw.saturating_sub(report.in_amount);
+                if self.held_raw <= self.peak_raw / DUST_SHARE {
+                    self.held_raw = 0;
+                    self.peak_raw = 0;
+                }
+            }
+            // A failed sell leaves the position as it was
+            (TradeSide::Sell, false) => {}
+        }
+    }
+}
+
+pub struct MomentumStrategy {
+    params: MomentumParams,
+    candles: CandleBuilder,
+    mints: HashMap<String, MintState>,
+    volume: Option<VolumeAnalyzer>,
+}
+
+/// `StrategyFactory` for kind `momentum`
+pub fn build(config: &StrategyConfig) -> anyhow::Result<Box<dyn Strategy>> {
+    let params: MomentumParams = config.parse_params()?;
+    if params.fast_period == 0 || params.fast_period >= params.slow_period {
+        anyhow::bail!("momentum fast_period must be between 1 and slow_period");
+    }
+    Ok(Box::new(MomentumStrategy::new(params)))
+}
+
+impl MomentumStrategy {
+    pub fn new(params: MomentumParams) -> Self {
+        Self {
+            candles: CandleBuilder::new(params.candle_interval_ms),
+            volume: params.volume_filter.as_ref().map(|f| VolumeAnalyzer::new(f.analysis.clone())),
+            params,
+            mints: HashMap::new(),
+        }
+    }
+
+    fn update(&mut self, mint: &str, price: f64, volume: f64, timestamp_ms: i64) -> Vec<StrategySignal> {
+        self.candles
+            .update(mint, price, volume, timestamp_ms)
+            .iter()
+            .filter_map(|candle| self.on_candle(candle))
+            .collect()
+    }
+
+    fn on_candle(&mut self, candle: &Candle) -> Option<StrategySignal> {
+        let params = &self.params;
+        let state = self.mints.entry(candle.mint.clone()).or_insert_with(|| MintState {
+            fast: Ema::new(params.fast_period),
+            slow: Ema::new(params.slow_period),
+            atr: Atr::new(params.atr_period),
+            previous_spread: None,
+            previous_slow: None,
+            held_raw: 0,
+            peak_raw: 0,
+            entry_pending: false,
+        });
+
+        let fast = state.fast.update(candle.close);
+        let slow = state.slow.update(candle.close);
+        let atr = state.atr.update(candle);
+        let (fast, slow, atr) = (fast?, slow?, atr?);
+
+        let spread = fast - slow;
+        let previous_spread = state.previous_spread.replace(spread);
+        let previous_slow = state.previous_slow.replace(slow);
+        let (previous_spread, previous_slow) = (previous_spread?, previous_slow?);
+        if atr <= 0.0 {
+            return None;
+        }
+
+        // Spread in ATRs measures how decisively the fast average broke away
+        let strength = spread.abs() / atr;
+        let confidence = params.min_confidence
+            + (params.max_confidence - params.min_confidence)
+                * ((strength - params.min_trend_strength)
+                    / (params.full_trend_strength - params.min_trend_strength).max(f64::EPSILON))
+                .clamp(0.0, 1.0);
+        let timestamp_ms = candle.start_ms + candle.interval_ms;
+
+        let crossed_up = previous_spread <= 0.0 && spread > 0.0;
+        let crossed_down = previous_spread >= 0.0 && spread < 0.0;
+
+        if crossed_up && !state.holding() && strength >= params.min_trend_strength && slow > previous_slow {
+            if let (Some(filter), Some(volume)) = (&params.volume_filter, &self.volume) {
+                let snapshot = volume.snapshot(&candle.mint, timestamp_ms);
+                if !snapshot.as_ref().is_some_and(|s| s.confirms_buy(filter)) {
+                    let flow = match &snapshot {
+                        Some(s) if s.is_distribution() => "distribution",
+                        Some(s) if s.is_accumulation() => "weak accumulation",
+                        Some(_) => "mixed flow",
+                        None => "no recent swaps",
+                    };
+                    debug!("🚀 Crossover on {} not backed by volume: {} (score {:?})", candle.mint, flow, snapshot.map(|s| s.score));
+                    return None;
+                }
+            }
+            state.entry_pending = true;
+            let target = candle.close + params.target_atr * atr;
+            let stop = (candle.close - params.stop_atr * atr).max(0.0);
+            return Some(StrategySignal {
+                mint: candle.mint.clone(),
+                side: TradeSide::Buy,
+                size_sol: params.size_sol,
+                price: candle.close,
+                confidence,
+                expected_return: Some((target - candle.close) / candle.close),
+                target_price: Some(target),
+                stop_price: Some(stop),
+                arbitrage: None,
+                deadline_slot: None,
+                timestamp_ms,
+            });
+        }
+
+        // Stays holding until the sell is reported, so a failed exit is retried on the next cross
+        if crossed_down && state.held_raw > 0 {
+            return Some(StrategySignal {
+                mint: candle.mint.clone(),
+                side: TradeSide::Sell,
+                size_sol: None,
+                price: candle.close,
+                confidence,
+                expected_return: None,
+                target_price: None,
+                stop_price: None,
+                arbitrage: None,
+                deadline_slot: None,
+                timestamp_ms,
+            });
+        }
+
+        None
+    }
+}
+
+#[async_trait]
+impl Strategy for MomentumStrategy {
+    async fn on_tick(&mut self, tick: &PriceTick) -> anyhow::Result<Vec<StrategySignal>> {
+        Ok(self.update(&tick.mint, tick.price, 0.0, tick.timestamp_ms))
+    }
+
+    async fn on_event(&mut self, event: &MarketEvent) -> anyhow::Result<Vec<StrategySignal>> {
+        if let (Some(volume), MarketDataMessage::Swap(swap), Some((_, _, _, timestamp_ms))) =
+            (&mut self.volume, &event.event, swap_trade(event))
+        {
+            volume.update(swap, timestamp_ms);
+        }
+        Ok(match swap_trade(event) {
+            Some((mint, price, volume, timestamp_ms)) => self.update(mint, price, volume, timestamp_ms),
+            None => Vec::new(),
+        })
+    }
+
+    async fn on_fill(&mut self, report: &ExecutionReport) -> anyhow::Result<Vec<StrategySignal>> {
+        if let Some(state) = self.mints.get_mut(&report.mint) {
+            state.apply_fill(report);
+        }
+        Ok(Vec::new())
+    }
+
+    fn params(&self) -> Value {
+        serde_json::to_value(&self.params).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::data_ingestor::SwapEvent;
+
+    const MINT: &str = "MINT";
+
+    fn params() -> MomentumParams {
+        MomentumParams {
+            fast_period: 2,
+            slow_period: 4,
+            atr_period: 2,
+            min_trend_strength: 0.3,
+            ..MomentumParams::default()
+        }
+    }
+
+    fn candle(index: usize, close: f64) -> Candle {
+        Candle {
+            mint: MINT.to_string(),
+            start_ms: index as i64 * 60_000,
+            interval_ms: 60_000,
+            open: close,
+            high: close * 1.005,
+            low: close * 0.995,
+            close,
+            volume: 1.0,
+            trades: 1,
+        }
+    }
+
+    /// Feed closes one candle each; returns (candle index, signal)
+    fn run(strategy: &mut MomentumStrategy, closes: &[f64]) -> Vec<(usize, StrategySignal)> {
+        closes
+            .iter()
+            .enumerate()
+            .filter_map(|(i, close)| strategy.on_candle(&candle(i, *close)).map(|signal| (i, signal)))
+            .collect()
+    }
+
+    fn fill(side: TradeSide, success: bool, in_amount: u64, out_amount: u64) -> ExecutionReport {
+        ExecutionReport {
+            order_id: "order".to_string(),
+            mint: MINT.to_string(),
+            side,
+            success,
+            signature: None,
+            in_amount,
+            out_amount,
+            error: (!success).then(|| "slippage".to_string()),
+            latency_ms: 0,
+            simulated: false,
+        }
+    }
+
+    /// Flat at 10, then a breakout candle
+    const BREAKOUT: [f64; 7] = [10.0, 10.0, 10.0, 10.0, 10.0, 10.0, 10.5];
+
+    /// The breakout, then a slide back under the slow average
+    const REVERSAL: [f64; 10] = [10.0, 10.0, 10.0, 10.0, 10.0, 10.0, 10.5, 10.3, 9.8, 9.4];
+
+    #[test]
+    fn buys_the_crossover_up() {
+        let mut strategy = MomentumStrategy::new(params());
+        let signals = run(&mut strategy, &BREAKOUT);
+
+        assert_eq!(signals.len(), 1);
+        let (index, buy) = &signals[0];
+        assert_eq!(*index, 6);
+        assert_eq!(buy.side, TradeSide::Buy);
+        assert_eq!(buy.price, 10.5);
+        assert_eq!(buy.timestamp_ms, 7 * 60_000);
+        assert_eq!(buy.size_sol, None);
+        // Target and stop sit `target_atr` and `stop_atr` ATRs either side of the entry
+        let (target, stop) = (buy.target_price.unwrap(), buy.stop_price.unwrap());
+        assert!(((target - 10.5) / (10.5 - stop) - params().target_atr / params().stop_atr).abs() < 1e-9);
+        assert!(buy.confidence >= params().min_confidence && buy.confidence < params().max_confidence);
+    }
+
+    #[test]
+    fn weak_crossover_is_filtered_by_trend_strength() {
+        let mut strategy = MomentumStrategy::new(MomentumParams {
+            min_trend_strength: 0.5,
+            ..params()
+        });
+        assert!(run(&mut strategy, &BREAKOUT).is_empty());
+
+        // The fast average did cross, just not by enough ATRs
+        let state = &strategy.mints[MINT];
+        let spread = state.previous_spread.unwrap();
+        assert!(spread > 0.0);
+        assert!(spread / state.atr.value().unwrap() < 0.5);
+    }
+
+    #[tokio::test]
+    async fn sells_the_crossover_down_after_a_filled_entry() {
+        let mut strategy = MomentumStrategy::new(params());
+        let signals = run(&mut strategy, &REVERSAL[..7]);
+        assert_eq!(signals.len(), 1);
+        strategy.on_fill(&fill(TradeSide::Buy, true, 1_000_000, 5_000)).await.unwrap();
+
+        let signals: Vec<_> =
+            (7..REVERSAL.len()).filter_map(|i| strategy.on_candle(&candle(i, REVERSAL[i])).map(|s| (i, s))).collect();
+        assert_eq!(signals.len(), 1);
+        let (index, sell) = &signals[0];
+        assert_eq!(*index, 8);
+        assert_eq!(sell.side, TradeSide::Sell);
+        assert_eq!(sell.price, 9.8);
+    }
+
+    #[tokio::test]
+    async fn failed_entry_has_nothing_to_sell() {
+        let mut strategy = MomentumStrategy::new(params());
+        assert_eq!(run(&mut strategy, &REVERSAL[..7]).len(), 1);
+        strategy.on_fill(&fill(TradeSide::Buy, false, 0, 0)).await.unwrap();
+
+        assert!((7..REVERSAL.len()).all(|i| strategy.on_candle(&candle(i, REVERSAL[i])).is_none()));
+        assert!(!strategy.mints[MINT].holding());
+    }
+
+    #[tokio::test]
+    async fn holds_until_the_whole_position_is_sold() {
+        let mut strategy = MomentumStrategy::new(params());
+        run(&mut strategy, &BREAKOUT);
+        strategy.on_fill(&fill(TradeSide::Buy, true, 1_000_000, 5_000)).await.unwrap();
+
+        // A partial take-profit by the risk manager leaves the rest held
+        strategy.on_fill(&fill(TradeSide::Sell, true, 2_000, 600_000)).await.unwrap();
+        assert!(strategy.mints[MINT].holding());
+        // A failed sell changes nothing
+        strategy.on_fill(&fill(TradeSide::Sell, false, 0, 0)).await.unwrap();
+        assert_eq!(strategy.mints[MINT].held_raw, 3_000);
+
+        // Rounding dust left by the final exit counts as flat
+        strategy.on_fill(&fill(TradeSide::Sell, true, 2_996, 500_000)).await.unwrap();
+        assert!(!strategy.mints[MINT].holding());
+        // Fills for a sold position keep it flat
+        strategy.on_fill(&fill(TradeSide::Sell, false, 0, 0)).await.unwrap();
+        assert!(!strategy.mints[MINT].holding());
+    }
+
+    #[test]
+    fn volume_filter_needs_broad_buying() {
+        let filtered = MomentumParams {
+            volume_filter: Some(VolumeFilter::default()),
+            ..params()
+        };
+
+        // No swaps seen for the mint, so nothing backs the crossover
+        let mut strategy = MomentumStrategy::new(filtered.clone());
+        assert!(run(&mut strategy, &BREAKOUT).is_empty());
+
+        // Ten distinct buyers in the breakout minute: full imbalance and breadth
+        let mut strategy = MomentumStrategy::new(filtered);
+        let volume = strategy.volume.as_mut().unwrap();
+        for trader in 0..10 {
+            let swap = SwapEvent {
+                signature: format!("sig{trader}"),
+                slot: 1,
+                block_time: None,
+                trader: format!("trader{trader}"),
+                mint: MINT.to_string(),
+                side: TradeSide::Buy,
+                token_amount: 10.0,
+                sol_amount: 1.0,
+            };
+            volume.update(&swap, 6 * 60_000 + 1_000);
+        }
+        let signals = run(&mut strategy, &BREAKOUT);
+        assert_eq!(signals.len(), 1);
+        assert_eq!(signals[0].1.side, TradeSide::Buy);
+    }
+}
@@ -0,0 +1,94 @@
+// 📚 Strategy Registry
+// Strategy factories by kind and per-instance configuration
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use tracing::warn;
+
+use super::strategy::Strategy;
+
+/// Builds a strategy instance from its config
+pub type StrategyFactory = fn(&StrategyConfig) -> anyhow::Result<Box<dyn Strategy>>;
+
+/// One strategy instance; several may share a kind with different params
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyConfig {
+    /// Unique id, attached to every signal the instance emits
+    pub id: String,
+    /// Registered factory name
+    pub kind: String,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    /// Public key of the wallet the instance trades from; `None` uses the executor's wallet
+    #[serde(default)]
+    pub wallet: Option<String>,
+    /// SOL the instance may have deployed at once
+    pub capital_sol: f64,
+    /// Strategy-specific parameters
+    #[serde(default)]
+    pub params: Value,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+impl StrategyConfig {
+    /// Typed `params`, with defaults for anything left out
+    pub fn parse_params<T: DeserializeOwned + Default>(&self) -> anyhow::Result<T> {
+        if self.params.is_null() {
+            return Ok(T::default());
+        }
+        serde_json::from_value(self.params.clone())
+            .map_err(|e| anyhow::anyhow!("invalid params for strategy {}: {}", self.id, e))
+    }
+}
+
+/// Strategy kinds available to configs
+#[derive(Default)]
+pub struct StrategyRegistry {
+    factories: HashMap<String, StrategyFactory>,
+}
+
+impl StrategyRegistry {
+    pub fn register(&mut self, kind: &str, factory: StrategyFactory) {
+        if self.factories.insert(kind.to_string(), factory).is_some() {
+            warn!("🧠 Strategy kind {} registered twice - keeping the latest", kind);
+        }
+    }
+
+    pub fn kinds(&self) -> Vec<&str> {
+        self.factories.keys().map(String::as_str).collect()
+    }
+
+    pub fn build(&self, config: &StrategyConfig) -> anyhow::Result<Box<dyn Strategy>> {
+        let factory = self
+            .factories
+            .get(&config.kind)
+            .ok_or_else(|| anyhow::anyhow!("unknown strategy kind {} for {}", config.kind, config.id))?;
+        factory(config)
+    }
+}
+
+/// Strategy instances from the JSON array at `STRATEGY_CONFIG_PATH`
+pub fn load_configs() -> anyhow::Result<Vec<StrategyConfig>> {
+    let path = std::env::var("STRATEGY_CONFIG_PATH").unwrap_or_else(|_| "strategies.json".to_string());
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            warn!("🧠 No strategy config at {} - no strategies will run", path);
+            return Ok(Vec::new());
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let configs: Vec<StrategyConfig> = serde_json::from_str(&contents)?;
+    let mut ids = std::collections::HashSet::new();
+    for config in &configs {
+        if !ids.insert(&config.id) {
+            anyhow::bail!("duplicate strategy id {}", config.id);
+        }
+    }
+    Ok(configs)
+}
@@ -0,0 +1,69 @@
+// 🧩 Strategy Trait
+// Interface every trading strategy implements
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::modules::data_ingestor::{MarketDataMessage, MarketEvent, PriceTick, TradeSide};
+use crate::modules::executor::arbitrage::ArbitrageOrder;
+use crate::modules::executor::ExecutionReport;
+
+/// Trade idea emitted by a strategy, before the engine tags it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategySignal {
+    pub mint: String,
+    pub side: TradeSide,
+    /// Order notional in SOL; `None` leaves sizing to the risk manager
+    pub size_sol: Option<f64>,
+    /// Reference price in SOL per token
+    pub price: f64,
+    /// Probability the trade works out, 0..1
+    pub confidence: f64,
+    /// Expected gain (fraction) if it does
+    pub expected_return: Option<f64>,
+    /// Price the strategy expects to exit at with a profit
+    #[serde(default)]
+    pub target_price: Option<f64>,
+    /// Price at which the idea is invalidated
+    #[serde(default)]
+    pub stop_price: Option<f64>,
+    /// Round trip to execute atomically instead of a plain buy
+    #[serde(default)]
+    pub arbitrage: Option<ArbitrageOrder>,
+    /// Last slot the entry is still worth taking at
+    #[serde(default)]
+    pub deadline_slot: Option<u64>,
+    pub timestamp_ms: i64,
+}
+
+/// Mint, price, token volume and timestamp (ms) of a decoded swap event
+pub fn swap_trade(event: &MarketEvent) -> Option<(&str, f64, f64, i64)> {
+    let MarketDataMessage::Swap(swap) = &event.event else {
+        return None;
+    };
+    let timestamp_ms = swap.block_time.map(|t| t * 1000).unwrap_or(event.received_at_us / 1000);
+    Some((swap.mint.as_str(), swap.price(), swap.token_amount, timestamp_ms))
+}
+
+/// A trading strategy driven by market data and its own fills
+///
+/// Each strategy runs in its own task, so callbacks may keep mutable state freely.
+#[async_trait]
+pub trait Strategy: Send {
+    /// Called for every price tick
+    async fn on_tick(&mut self, tick: &PriceTick) -> anyhow::Result<Vec<StrategySignal>>;
+
+    /// Called for every other market event
+    async fn on_event(&mut self, _event: &MarketEvent) -> anyhow::Result<Vec<StrategySignal>> {
+        Ok(Vec::new())
+    }
+
+    /// Called when an order from one of this strategy's signals finishes
+    async fn on_fill(&mut self, _report: &ExecutionReport) -> anyhow::Result<Vec<StrategySignal>> {
+        Ok(Vec::new())
+    }
+
+    /// Current parameters, for logs and the API
+    fn params(&self) -> Value;
+}
@@ -0,0 +1,347 @@
+// 📊 Volume Analysis
+// Rolling per-mint swap volume: spikes, buy/sell imbalance, buyer breadth and whales
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::modules::data_ingestor::{SwapEvent, TradeSide};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VolumeParams {
+    pub bucket_ms: i64,
+    /// Completed buckets the spike z-score is measured against
+    pub lookback: usize,
+    /// Buckets needed before z-scores are reported
+    pub min_history: usize,
+    /// Floor on the standard deviation so quiet mints do not spike on dust
+    pub min_std_sol: f64,
+    /// Z-score at which a bucket counts as a spike
+    pub spike_z: f64,
+    /// Single swap size, in SOL, that counts as a whale trade
+    pub whale_sol: f64,
+    /// Unique buyers in a bucket that count as broad demand
+    pub broad_buyers: usize,
+}
+
+impl Default for VolumeParams {
+    fn default() -> Self {
+        Self {
+            bucket_ms: 60_000,
+            lookback: 30,
+            min_history: 5,
+            min_std_sol: 0.1,
+            spike_z: 3.0,
+            whale_sol: 10.0,
+            broad_buyers: 10,
+        }
+    }
+}
+
+/// Volume conditions a buy must meet, for strategies that use volume as confirmation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VolumeFilter {
+    pub analysis: VolumeParams,
+    pub min_score: f64,
+    pub min_imbalance: f64,
+    pub require_spike: bool,
+}
+
+impl Default for VolumeFilter {
+    fn default() -> Self {
+        Self {
+            analysis: VolumeParams::default(),
+            min_score: 0.5,
+            min_imbalance: 0.0,
+            require_spike: false,
+        }
+    }
+}
+
+/// Volume picture of a mint's current bucket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeSnapshot {
+    pub mint: String,
+    pub bucket_start_ms: i64,
+    pub volume_sol: f64,
+    pub buy_volume_sol: f64,
+    pub sell_volume_sol: f64,
+    /// Bucket volume against the lookback, `None` until enough history
+    pub z_score: Option<f64>,
+    pub spike: bool,
+    /// (buys - sells) / total, -1..1
+    pub imbalance: f64,
+    pub unique_buyers: usize,
+    pub unique_sellers: usize,
+    pub whale_buys: u32,
+    pub whale_sells: u32,
+    /// Accumulation strength 0..1 from spike, imbalance, breadth and whales
+    pub score: f64,
+}
+
+impl VolumeSnapshot {
+    pub fn is_accumulation(&self) -> bool {
+        self.imbalance > 0.0 && self.unique_buyers > self.unique_sellers
+    }
+
+    pub fn is_distribution(&self) -> bool {
+        self.imbalance < 0.0 && self.unique_sellers > self.unique_buyers
+    }
+
+    /// Whether volume backs a buy under `filter`
+    pub fn confirms_buy(&self, filter: &VolumeFilter) -> bool {
+        self.score >= filter.min_score
+            && self.imbalance >= filter.min_imbalance
+            && (self.spike || !filter.require_spike)
+    }
+}
+
+#[derive(Debug, Default)]
+struct VolumeBucket {
+    start_ms: i64,
+    buy_sol: f64,
+    sell_sol: f64,
+    buyers: HashSet<String>,
+    sellers: HashSet<String>,
+    whale_buys: u32,
+    whale_sells: u32,
+}
+
+impl VolumeBucket {
+    fn total(&self) -> f64 {
+        self.buy_sol + self.sell_sol
+    }
+}
+
+#[derive(Debug, Default)]
+struct MintVolume {
+    current: VolumeBucket,
+    /// Totals of completed buckets, oldest first, empty buckets included
+    history: VecDeque<f64>,
+}
+
+/// Rolling volume statistics for every mint seen in swap events
+pub struct VolumeAnalyzer {
+    params: VolumeParams,
+    mints: HashMap<String, MintVolume>,
+}
+
+impl VolumeAnalyzer {
+    pub fn new(params: VolumeParams) -> Self {
+        Self {
+            params: VolumeParams {
+                bucket_ms: params.bucket_ms.max(1),
+                ..params
+            },
+            mints: HashMap::new(),
+        }
+    }
+
+    /// Add a swap and return its mint's updated snapshot
+    pub fn update(&mut self, swap: &SwapEvent, timestamp_ms: i64) -> VolumeSnapshot {
+        let params = &self.params;
+        let start_ms = timestamp_ms - timestamp_ms.rem_euclid(params.bucket_ms);
+        let state = self.mints.entry(swap.mint.clone()).or_default();
+
+        if state.current.start_ms == 0 {
+            state.current.start_ms = start_ms;
+        } else if start_ms > state.current.start_ms {
+            // Buckets nobody traded in still count, as zero volume
+            let skipped = ((start_ms - state.current.start_ms) / params.bucket_ms - 1) as usize;
+            let finished = std::mem::take(&mut state.current);
+            state.history.push_back(finished.total());
+            state.history.extend(std::iter::repeat_n(0.0, skipped.min(params.lookback)));
+            while state.history.len() > params.lookback {
+                state.history.pop_front();
+            }
+            state.current.start_ms = start_ms;
+        }
+        // Late swaps from an earlier bucket are counted in the current one
+
+        let whale = swap.sol_amount >= params.whale_sol;
+        let bucket = &mut state.current;
+        match swap.side {
+            TradeSide::Buy => {
+                bucket.buy_sol += swap.sol_amount;
+                bucket.buyers.insert(swap.trader.clone());
+                bucket.whale_buys += whale as u32;
+            }
+            TradeSide::Sell => {
+                bucket.sell_sol += swap.sol_amount;
+                bucket.sellers.insert(swap.trader.clone());
+                bucket.whale_sells += whale as u32;
+            }
+        }
+
+        build_snapshot(params, &swap.mint, state)
+    }
+
+    /// Snapshot of `mint`, or `None` if it has not traded in the bucket containing `now_ms` or the one before
+    pub fn snapshot(&self, mint: &str, now_ms: i64) -> Option<VolumeSnapshot> {
+        let state = self.mints.get(mint)?;
+        if now_ms - state.current.start_ms >= 2 * self.params.bucket_ms {
+            return None;
+        }
+        Some(build_snapshot(&self.params, mint, state))
+    }
+}
+
+fn build_snapshot(params: &VolumeParams, mint: &str, state: &MintVolume) -> VolumeSnapshot {
+    let bucket = &state.current;
+    let volume = bucket.total();
+
+    let z_score = (state.history.len() >= params.min_history.max(2)).then(|| {
+        let n = state.history.len() as f64;
+        let mean = state.history.iter().sum::<f64>() / n;
+        let variance = state.history.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+        (volume - mean) / variance.sqrt().max(params.min_std_sol).max(f64::EPSILON)
+    });
+    let spike = z_score.is_some_and(|z| z >= params.spike_z);
+    let imbalance = if volume > 0.0 { (bucket.buy_sol - bucket.sell_sol) / volume } else { 0.0 };
+
+    let spike_part = z_score.map_or(0.0, |z| (z / params.spike_z.max(f64::EPSILON)).clamp(0.0, 1.0));
+    let breadth = (bucket.buyers.len() as f64 / params.broad_buyers.max(1) as f64).min(1.0);
+    let whales = if bucket.whale_buys > bucket.whale_sells { 1.0 } else { 0.0 };
+    let score = 0.35 * spike_part + 0.35 * imbalance.max(0.0) + 0.2 * breadth + 0.1 * whales;
+
+    VolumeSnapshot {
+        mint: mint.to_string(),
+        bucket_start_ms: bucket.start_ms,
+        volume_sol: volume,
+        buy_volume_sol: bucket.buy_sol,
+        sell_volume_sol: bucket.sell_sol,
+        z_score,
+        spike,
+        imbalance,
+        unique_buyers: bucket.buyers.len(),
+        unique_sellers: bucket.sellers.len(),
+        whale_buys: bucket.whale_buys,
+        whale_sells: bucket.whale_sells,
+        score,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINT: &str = "MINT";
+    const MINUTE: i64 = 60_000;
+    /// Test clock origin; real swaps never fall in the epoch's first minute
+    const T0: i64 = 1_000 * MINUTE;
+
+    fn params() -> VolumeParams {
+        VolumeParams {
+            lookback: 4,
+            min_history: 3,
+            broad_buyers: 4,
+            ..VolumeParams::default()
+        }
+    }
+
+    fn swap(trader: &str, side: TradeSide, sol: f64) -> SwapEvent {
+        SwapEvent {
+            signature: "sig".to_string(),
+            slot: 1,
+            block_time: None,
+            trader: trader.to_string(),
+            mint: MINT.to_string(),
+            side,
+            token_amount: sol * 100.0,
+            sol_amount: sol,
+        }
+    }
+
+    fn history(analyzer: &VolumeAnalyzer) -> Vec<f64> {
+        analyzer.mints[MINT].history.iter().copied().collect()
+    }
+
+    #[test]
+    fn rolls_buckets_into_history_with_quiet_minutes_as_zero() {
+        let mut analyzer = VolumeAnalyzer::new(params());
+        analyzer.update(&swap("a", TradeSide::Buy, 1.0), T0 + 10_000);
+        analyzer.update(&swap("b", TradeSide::Sell, 2.0), T0 + 50_000);
+        assert!(history(&analyzer).is_empty());
+
+        // Next minute closes the first bucket
+        let snapshot = analyzer.update(&swap("c", TradeSide::Buy, 4.0), T0 + MINUTE + 5_000);
+        assert_eq!(history(&analyzer), vec![3.0]);
+        assert_eq!(snapshot.bucket_start_ms, T0 + MINUTE);
+        assert_eq!((snapshot.volume_sol, snapshot.unique_buyers, snapshot.unique_sellers), (4.0, 1, 0));
+
+        // Two silent minutes are recorded as empty buckets
+        analyzer.update(&swap("d", TradeSide::Buy, 1.0), T0 + 4 * MINUTE);
+        assert_eq!(history(&analyzer), vec![3.0, 4.0, 0.0, 0.0]);
+
+        // The lookback keeps only the newest buckets, however long the gap
+        analyzer.update(&swap("e", TradeSide::Buy, 1.0), T0 + 5 * MINUTE);
+        assert_eq!(history(&analyzer), vec![4.0, 0.0, 0.0, 1.0]);
+        analyzer.update(&swap("f", TradeSide::Buy, 1.0), T0 + 100 * MINUTE);
+        assert_eq!(history(&analyzer), vec![0.0, 0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn late_swaps_count_toward_the_current_bucket() {
+        let mut analyzer = VolumeAnalyzer::new(params());
+        analyzer.update(&swap("a", TradeSide::Buy, 1.0), T0 + 2 * MINUTE);
+        let snapshot = analyzer.update(&swap("b", TradeSide::Buy, 1.0), T0 + MINUTE);
+        assert_eq!(snapshot.bucket_start_ms, T0 + 2 * MINUTE);
+        assert_eq!(snapshot.volume_sol, 2.0);
+        assert!(history(&analyzer).is_empty());
+    }
+
+    #[test]
+    fn snapshot_goes_stale_after_a_full_quiet_bucket() {
+        let mut analyzer = VolumeAnalyzer::new(params());
+        analyzer.update(&swap("a", TradeSide::Buy, 1.0), T0 + MINUTE + 30_000);
+
+        assert!(analyzer.snapshot(MINT, T0 + MINUTE + 59_000).is_some());
+        // Still describes the previous minute
+        assert!(analyzer.snapshot(MINT, T0 + 2 * MINUTE + 59_000).is_some());
+        assert!(analyzer.snapshot(MINT, T0 + 3 * MINUTE).is_none());
+        assert!(analyzer.snapshot("other", T0 + MINUTE).is_none());
+    }
+
+    #[test]
+    fn spike_needs_history_and_scores_broad_buying() {
+        let mut analyzer = VolumeAnalyzer::new(params());
+        for minute in 0..3 {
+            analyzer.update(&swap("a", TradeSide::Buy, 0.5), T0 + minute * MINUTE);
+        }
+        // Two finished buckets are short of min_history
+        assert_eq!(analyzer.snapshot(MINT, T0 + 2 * MINUTE).unwrap().z_score, None);
+
+        // Four buyers, one whale, after three quiet 0.5 SOL minutes
+        for (trader, sol) in [("a", 1.0), ("b", 2.0), ("c", 3.0), ("d", 12.0)] {
+            analyzer.update(&swap(trader, TradeSide::Buy, sol), T0 + 3 * MINUTE);
+        }
+        let snapshot = analyzer.update(&swap("e", TradeSide::Sell, 2.0), T0 + 3 * MINUTE);
+        assert_eq!(history(&analyzer), vec![0.5, 0.5, 0.5]);
+
+        // Flat history: deviation falls back to min_std_sol
+        let z = snapshot.z_score.unwrap();
+        assert!((z - (20.0 - 0.5) / 0.1).abs() < 1e-9);
+        assert!(snapshot.spike);
+        assert!((snapshot.imbalance - 16.0 / 20.0).abs() < 1e-9);
+        assert_eq!((snapshot.whale_buys, snapshot.whale_sells), (1, 0));
+        assert!(snapshot.is_accumulation() && !snapshot.is_distribution());
+        assert!((snapshot.score - (0.35 + 0.35 * 0.8 + 0.2 + 0.1)).abs() < 1e-9);
+        assert!(snapshot.confirms_buy(&VolumeFilter {
+            require_spike: true,
+            ..VolumeFilter::default()
+        }));
+    }
+
+    #[test]
+    fn selling_pressure_is_distribution_and_never_confirms() {
+        let mut analyzer = VolumeAnalyzer::new(params());
+        analyzer.update(&swap("a", TradeSide::Buy, 1.0), T0);
+        analyzer.update(&swap("b", TradeSide::Sell, 3.0), T0);
+        let snapshot = analyzer.update(&swap("c", TradeSide::Sell, 3.0), T0);
+
+        assert!(snapshot.is_distribution() && !snapshot.is_accumulation());
+        assert!(snapshot.imbalance < 0.0);
+        assert!(!snapshot.confirms_buy(&VolumeFilter::default()));
+    }
+}
@@ -192,8 +192,9 @@ TOKIO_WORKER_THREADS=5
 
 # Trading Configuration
 TRADING_MODE=paper
-MAX_POSITION_SIZE=1000.0
-MAX_DAILY_LOSS=500.0
+# Limits in SOL (not USD)
+MAX_POSITION_SIZE=1.0
+MAX_DAILY_LOSS=5.0
 MIN_CONFIDENCE=0.6
 
 # Database Configuration